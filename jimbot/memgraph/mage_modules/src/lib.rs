@@ -8,6 +8,9 @@ pub mod victory_path_analyzer;
 
 use std::os::raw::{c_int, c_void};
 
+/// Memgraph internal node id
+pub type NodeId = i64;
+
 /// FFI wrapper for Memgraph module initialization
 #[no_mangle]
 pub extern "C" fn mgp_init_module(_module: *mut c_void, _memory: *mut c_void) -> c_int {
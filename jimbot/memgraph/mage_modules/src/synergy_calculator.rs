@@ -1,6 +1,8 @@
 //! High-performance synergy calculation algorithms
 
-// use std::collections::HashMap; // Unused import
+use std::collections::HashMap;
+
+use crate::NodeId;
 
 /// Card representation for fast operations
 #[derive(Debug, Clone)]
@@ -41,10 +43,71 @@ pub struct SynergyResult {
     pub joker2: String,
     pub strength: f64,
     pub synergy_type: String,
+    /// Joker names in sorted order; orders results with equal strength
+    pub tie_break_key: (String, String),
+}
+
+/// A `:Joker` node taking part in a combo
+#[derive(Debug, Clone)]
+pub struct JokerNode {
+    pub id: NodeId,
+    pub name: String,
+}
+
+/// A `SYNERGIZES_WITH` relationship: `source` boosts `target`
+#[derive(Debug, Clone)]
+pub struct SynergyEdge {
+    pub source: NodeId,
+    pub target: NodeId,
+    pub strength: f64,
+    pub synergy_type: String,
+}
+
+/// Score how much a set of jokers gains from playing together
+///
+/// Every joker starts with a contribution of 1.0, its value on its own. Each
+/// edge between two jokers of the set then boosts its target the way scoring
+/// does in game: `additive` and `complementary` edges add `strength`, and
+/// `multiplicative` edges (e.g. a retrigger joker replaying a mult joker)
+/// scale the total by `1 + strength`, so they amplify additive bonuses and
+/// compound with each other. The synergy is the summed gain over the
+/// standalone contributions, so it is 0.0 for an empty set, a lone joker or
+/// jokers with no edges, and adding an unrelated joker never lowers it.
+///
+/// Edges touching jokers outside the set are ignored, as are self-loops and
+/// unknown synergy types. Negative strengths (anti-synergy) can take a
+/// joker's contribution down to nothing but not below.
+pub fn calculate_synergy(jokers: &[JokerNode], edges: &[SynergyEdge]) -> f64 {
+    let mut bonus: HashMap<NodeId, f64> = jokers.iter().map(|j| (j.id, 0.0)).collect();
+    let mut factor: HashMap<NodeId, f64> = jokers.iter().map(|j| (j.id, 1.0)).collect();
+
+    for edge in edges {
+        if edge.source == edge.target || !bonus.contains_key(&edge.source) {
+            continue;
+        }
+        match edge.synergy_type.as_str() {
+            "additive" | "complementary" => {
+                if let Some(bonus) = bonus.get_mut(&edge.target) {
+                    *bonus += edge.strength;
+                }
+            }
+            "multiplicative" => {
+                if let Some(factor) = factor.get_mut(&edge.target) {
+                    *factor *= (1.0 + edge.strength).max(0.0);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    bonus
+        .iter()
+        .map(|(id, bonus)| ((1.0 + bonus).max(0.0) * factor[id]) - 1.0)
+        .sum()
 }
 
-/// Calculate synergy score between two jokers
-pub fn calculate_synergy(joker1: &JokerAttributes, joker2: &JokerAttributes) -> f64 {
+/// Calculate synergy score between two jokers from their attributes alone
+pub fn calculate_pair_synergy(joker1: &JokerAttributes, joker2: &JokerAttributes) -> f64 {
     let mut score: f64 = 0.0;
 
     // Same scaling type creates strong synergy
@@ -89,7 +152,7 @@ pub fn calculate_all_synergies(
 
     for i in 0..jokers.len() {
         for j in (i + 1)..jokers.len() {
-            let synergy = calculate_synergy(&jokers[i], &jokers[j]);
+            let synergy = calculate_pair_synergy(&jokers[i], &jokers[j]);
 
             if synergy >= min_strength {
                 results.push(SynergyResult {
@@ -97,16 +160,30 @@ pub fn calculate_all_synergies(
                     joker2: jokers[j].name.clone(),
                     strength: synergy,
                     synergy_type: determine_synergy_type(&jokers[i], &jokers[j]),
+                    tie_break_key: tie_break_key(&jokers[i].name, &jokers[j].name),
                 });
             }
         }
     }
 
-    // Sort by strength descending
-    results.sort_by(|a, b| b.strength.partial_cmp(&a.strength).unwrap());
+    // Sort by strength descending, then by sorted joker names so equal
+    // strengths rank the same regardless of input order
+    results.sort_by(|a, b| {
+        b.strength
+            .total_cmp(&a.strength)
+            .then_with(|| a.tie_break_key.cmp(&b.tie_break_key))
+    });
     results
 }
 
+fn tie_break_key(joker1: &str, joker2: &str) -> (String, String) {
+    if joker1 <= joker2 {
+        (joker1.to_string(), joker2.to_string())
+    } else {
+        (joker2.to_string(), joker1.to_string())
+    }
+}
+
 fn determine_synergy_type(joker1: &JokerAttributes, joker2: &JokerAttributes) -> String {
     if joker1.scaling_type == joker2.scaling_type {
         "amplifying".to_string()
@@ -141,7 +218,120 @@ mod tests {
             scaling_type: "copy".to_string(),
         };
 
-        let synergy = calculate_synergy(&joker1, &joker2);
+        let synergy = calculate_pair_synergy(&joker1, &joker2);
         assert!(synergy > 0.5); // Same type and rarity should have good synergy
     }
+
+    fn joker(name: &str) -> JokerAttributes {
+        JokerAttributes {
+            name: name.to_string(),
+            rarity: "common".to_string(),
+            cost: 5,
+            base_chips: 0,
+            base_mult: 4,
+            scaling_type: "additive".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_equal_strength_ties_break_by_sorted_names() {
+        let names = |results: &[SynergyResult]| -> Vec<(String, String)> {
+            results.iter().map(|r| r.tie_break_key.clone()).collect()
+        };
+
+        // Identical attributes give every pair the same strength
+        let forward =
+            calculate_all_synergies(&[joker("Joker"), joker("Greedy"), joker("Abstract")], 0.0);
+        let reversed =
+            calculate_all_synergies(&[joker("Abstract"), joker("Greedy"), joker("Joker")], 0.0);
+        assert!(forward.iter().all(|r| r.strength == forward[0].strength));
+
+        let expected = vec![
+            ("Abstract".to_string(), "Greedy".to_string()),
+            ("Abstract".to_string(), "Joker".to_string()),
+            ("Greedy".to_string(), "Joker".to_string()),
+        ];
+        assert_eq!(names(&forward), expected);
+        assert_eq!(names(&reversed), expected);
+        assert_eq!(
+            names(&calculate_all_synergies(
+                &[joker("Joker"), joker("Greedy"), joker("Abstract")],
+                0.0
+            )),
+            expected
+        );
+    }
+
+    fn node(id: NodeId, name: &str) -> JokerNode {
+        JokerNode {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    fn edge(source: NodeId, target: NodeId, strength: f64, synergy_type: &str) -> SynergyEdge {
+        SynergyEdge {
+            source,
+            target,
+            strength,
+            synergy_type: synergy_type.to_string(),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_combo_synergy_of_empty_and_single_sets() {
+        assert_eq!(calculate_synergy(&[], &[]), 0.0);
+
+        // A self-loop and an edge to a joker we don't hold add nothing
+        let edges = [
+            edge(1, 1, 0.5, "additive"),
+            edge(1, 2, 0.8, "multiplicative"),
+        ];
+        assert_eq!(calculate_synergy(&[node(1, "Fibonacci")], &edges), 0.0);
+    }
+
+    #[test]
+    fn test_known_three_joker_combo() {
+        let jokers = [node(1, "Hack"), node(2, "Scholar"), node(3, "Fibonacci")];
+        let edges = [
+            // Hack retriggers the 2s, 3s and 5s Fibonacci scores on
+            edge(1, 3, 1.0, "multiplicative"),
+            edge(2, 3, 0.6, "additive"),
+            // Odd Todd isn't held
+            edge(3, 4, 0.8, "multiplicative"),
+        ];
+
+        // Fibonacci: (1 + 0.6) * 2 = 3.2, a gain of 2.2 over playing it alone
+        assert_close(calculate_synergy(&jokers, &edges), 2.2);
+
+        // Without the retrigger the same set only gains the additive bonus
+        assert_close(calculate_synergy(&jokers, &edges[1..]), 0.6);
+
+        // An unrelated joker doesn't dilute the combo
+        let mut with_filler = jokers.to_vec();
+        with_filler.push(node(5, "Joker"));
+        assert_close(calculate_synergy(&with_filler, &edges), 2.2);
+    }
+
+    #[test]
+    fn test_multiplicative_edges_compound() {
+        let jokers = [
+            node(1, "Hanging Chad"),
+            node(2, "Sock and Buskin"),
+            node(3, "Scary Face"),
+        ];
+        let edges = [
+            edge(1, 3, 1.0, "multiplicative"),
+            edge(2, 3, 1.0, "multiplicative"),
+        ];
+        assert_close(calculate_synergy(&jokers, &edges), 3.0);
+
+        // Anti-synergy can cancel a joker out but never goes below it
+        let edges = [edge(1, 3, -2.0, "additive")];
+        assert_close(calculate_synergy(&jokers, &edges), -1.0);
+    }
 }
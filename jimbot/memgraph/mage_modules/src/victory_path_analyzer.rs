@@ -1,6 +1,12 @@
 //! Victory path analysis for optimal joker progression
 
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::NodeId;
+
+/// Most paths `find_optimal_paths` and `analyze_paths` return
+pub const TOP_PATHS: usize = 10;
 
 /// Represents a joker in the progression path
 #[derive(Debug, Clone)]
@@ -99,8 +105,283 @@ pub fn find_optimal_paths(
     // Sort by success rate descending
     paths.sort_by(|a, b| b.success_rate.partial_cmp(&a.success_rate).unwrap());
 
-    // Return top 10 paths
-    paths.into_iter().take(10).collect()
+    paths.truncate(TOP_PATHS);
+    paths
+}
+
+/// What a node of a recorded run stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunNodeKind {
+    GameStart,
+    Round,
+    Shop,
+    Win,
+    Loss,
+}
+
+/// A point in a run: the start, a round or shop decision, or the outcome
+#[derive(Debug, Clone)]
+pub struct RunNode {
+    pub id: NodeId,
+    pub kind: RunNodeKind,
+    /// The choice made here, e.g. "play flush" or "buy Blueprint"
+    pub decision: String,
+    pub ante: i32,
+    /// Score gained at this node
+    pub score: f64,
+}
+
+/// Decision graph of runs, edges weighted by the chance of surviving them
+#[derive(Debug, Clone, Default)]
+pub struct RunGraph {
+    pub nodes: HashMap<NodeId, RunNode>,
+    pub edges: HashMap<NodeId, Vec<(NodeId, f64)>>, // node -> [(next, survival)]
+}
+
+impl RunGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: RunNode) {
+        self.nodes.insert(node.id, node);
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, survival: f64) {
+        self.edges.entry(from).or_default().push((to, survival));
+    }
+}
+
+/// A path from the game start to a win
+#[derive(Debug, Clone)]
+pub struct ScoredPath {
+    pub nodes: Vec<NodeId>,
+    /// Decisions of the round and shop nodes along the path, in order
+    pub decisions: Vec<String>,
+    /// Product of the survival chances of every edge taken
+    pub survival: f64,
+    /// Score gained along the path divided by the ante it won at
+    pub score_per_ante: f64,
+    /// `survival * score_per_ante`
+    pub score: f64,
+}
+
+/// Find the best winning paths from `start`
+///
+/// Follows paths out of `start` that never revisit a node, so cycles in the
+/// graph end a path rather than loop. Paths reaching a `Win` node are scored
+/// by how much they score per ante, weighted by the chance of surviving every
+/// edge on the way; survival chances are clamped to `[0, 1]`. Paths ending in
+/// a `Loss` node or at a node with nowhere left to go are dropped. Returns up
+/// to `TOP_PATHS` paths, highest score first; ties go to the path with the
+/// lower node ids.
+///
+/// The search is best-first on an upper bound of the score a partial path can
+/// still reach, and stops once no partial path can beat the `TOP_PATHS`-th
+/// win found so far, so it only walks the part of the graph that can matter.
+pub fn analyze_paths(start: NodeId, graph: &RunGraph) -> Vec<ScoredPath> {
+    let mut paths = Vec::new();
+    if !graph.nodes.contains_key(&start) {
+        return paths;
+    }
+    let Some(min_win_ante) = graph
+        .nodes
+        .values()
+        .filter(|node| node.kind == RunNodeKind::Win)
+        .map(|node| node.ante.max(1))
+        .min()
+    else {
+        return paths;
+    };
+
+    let mut bounds = ScoreBounds::new(graph, min_win_ante);
+    // Paths are kept as parent links so extending one doesn't copy it
+    let mut steps = vec![PathStep {
+        node: start,
+        parent: None,
+    }];
+    let mut frontier = BinaryHeap::new();
+    let start_score = graph.nodes[&start].score;
+    frontier.push(Partial {
+        bound: bounds.bound(start, 1.0, start_score),
+        survival: 1.0,
+        score: start_score,
+        step: 0,
+    });
+    // Scores of the best wins found so far, highest first
+    let mut best: Vec<f64> = Vec::with_capacity(TOP_PATHS + 1);
+
+    while let Some(partial) = frontier.pop() {
+        if best.len() == TOP_PATHS && partial.bound < best[TOP_PATHS - 1] {
+            break;
+        }
+
+        let tip = steps[partial.step].node;
+        let last = &graph.nodes[&tip];
+        match last.kind {
+            RunNodeKind::Win => {
+                let path = score_path(walk_back(&steps, partial.step), partial.survival, graph);
+                let at = best.partition_point(|&score| score >= path.score);
+                best.insert(at, path.score);
+                best.truncate(TOP_PATHS);
+                paths.push(path);
+                continue;
+            }
+            RunNodeKind::Loss => continue,
+            _ => {}
+        }
+
+        for &(next, edge_survival) in graph.edges.get(&tip).into_iter().flatten() {
+            let Some(next_node) = graph.nodes.get(&next) else {
+                continue;
+            };
+            if on_path(&steps, partial.step, next) {
+                continue;
+            }
+            let survival = partial.survival * edge_survival.clamp(0.0, 1.0);
+            let score = partial.score + next_node.score;
+            steps.push(PathStep {
+                node: next,
+                parent: Some(partial.step),
+            });
+            frontier.push(Partial {
+                bound: bounds.bound(next, survival, score),
+                survival,
+                score,
+                step: steps.len() - 1,
+            });
+        }
+    }
+
+    paths.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.nodes.cmp(&b.nodes))
+    });
+    paths.truncate(TOP_PATHS);
+    paths
+}
+
+/// One node of a partial path, linked back to the node before it
+struct PathStep {
+    node: NodeId,
+    parent: Option<usize>,
+}
+
+fn on_path(steps: &[PathStep], mut step: usize, node: NodeId) -> bool {
+    loop {
+        if steps[step].node == node {
+            return true;
+        }
+        match steps[step].parent {
+            Some(parent) => step = parent,
+            None => return false,
+        }
+    }
+}
+
+fn walk_back(steps: &[PathStep], mut step: usize) -> Vec<NodeId> {
+    let mut nodes = vec![steps[step].node];
+    while let Some(parent) = steps[step].parent {
+        nodes.push(steps[parent].node);
+        step = parent;
+    }
+    nodes.reverse();
+    nodes
+}
+
+/// A path still being extended, ordered by its score bound
+struct Partial {
+    bound: f64,
+    survival: f64,
+    score: f64,
+    step: usize,
+}
+
+impl PartialEq for Partial {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Partial {}
+
+impl PartialOrd for Partial {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Partial {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.total_cmp(&other.bound)
+    }
+}
+
+/// Upper bounds on the score a partial path can still reach
+struct ScoreBounds<'a> {
+    graph: &'a RunGraph,
+    min_win_ante: i32,
+    /// Positive score of every node reachable from a node, memoized
+    gains: HashMap<NodeId, f64>,
+}
+
+impl<'a> ScoreBounds<'a> {
+    fn new(graph: &'a RunGraph, min_win_ante: i32) -> Self {
+        Self {
+            graph,
+            min_win_ante,
+            gains: HashMap::new(),
+        }
+    }
+
+    /// Survival only falls and score only grows by what is still reachable,
+    /// and no win comes before `min_win_ante`
+    fn bound(&mut self, tip: NodeId, survival: f64, score: f64) -> f64 {
+        let total = (score + self.gain(tip)).max(0.0);
+        survival * total / self.min_win_ante as f64
+    }
+
+    fn gain(&mut self, from: NodeId) -> f64 {
+        if let Some(&gain) = self.gains.get(&from) {
+            return gain;
+        }
+
+        let mut seen = HashSet::from([from]);
+        let mut stack = vec![from];
+        let mut gain = 0.0;
+        while let Some(id) = stack.pop() {
+            for &(next, _) in self.graph.edges.get(&id).into_iter().flatten() {
+                if let Some(node) = self.graph.nodes.get(&next) {
+                    if seen.insert(next) {
+                        gain += node.score.max(0.0);
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        self.gains.insert(from, gain);
+        gain
+    }
+}
+
+fn score_path(nodes: Vec<NodeId>, survival: f64, graph: &RunGraph) -> ScoredPath {
+    let path: Vec<_> = nodes.iter().map(|id| &graph.nodes[id]).collect();
+    let total_score: f64 = path.iter().map(|node| node.score).sum();
+    let antes = path.last().unwrap().ante.max(1);
+    let score_per_ante = total_score / antes as f64;
+
+    ScoredPath {
+        decisions: path
+            .iter()
+            .filter(|node| matches!(node.kind, RunNodeKind::Round | RunNodeKind::Shop))
+            .map(|node| node.decision.clone())
+            .collect(),
+        nodes,
+        survival,
+        score_per_ante,
+        score: survival * score_per_ante,
+    }
 }
 
 fn calculate_path_success(
@@ -188,4 +469,101 @@ mod tests {
         let paths = find_optimal_paths(&jokers, &transitions, config);
         assert!(!paths.is_empty());
     }
+
+    fn run_node(id: NodeId, kind: RunNodeKind, decision: &str, ante: i32, score: f64) -> RunNode {
+        RunNode {
+            id,
+            kind,
+            decision: decision.to_string(),
+            ante,
+            score,
+        }
+    }
+
+    /// Two ways to win: a risky flush build that scores big and a safer pair
+    /// build, plus a shop loop, a loss and a dead end
+    fn competing_paths() -> RunGraph {
+        use RunNodeKind::*;
+
+        let mut graph = RunGraph::new();
+        graph.add_node(run_node(0, GameStart, "", 1, 0.0));
+        graph.add_node(run_node(1, Shop, "buy Blueprint", 1, 0.0));
+        graph.add_node(run_node(2, Shop, "buy Greedy Joker", 1, 0.0));
+        graph.add_node(run_node(3, Round, "play flush", 2, 1200.0));
+        graph.add_node(run_node(4, Round, "play pair", 2, 600.0));
+        graph.add_node(run_node(5, Round, "reroll", 1, 50.0));
+        graph.add_node(run_node(6, Win, "", 3, 0.0));
+        graph.add_node(run_node(7, Loss, "", 2, 0.0));
+
+        graph.add_edge(0, 1, 1.0);
+        graph.add_edge(0, 2, 1.0);
+        graph.add_edge(1, 3, 0.8);
+        graph.add_edge(3, 6, 0.9);
+        graph.add_edge(3, 7, 0.1);
+        graph.add_edge(3, 1, 0.5); // Back to the shop
+        graph.add_edge(2, 4, 0.9);
+        graph.add_edge(4, 6, 0.95);
+        graph.add_edge(2, 5, 1.0); // Nowhere to go from here
+        graph
+    }
+
+    #[test]
+    fn test_higher_scoring_path_ranks_first() {
+        let paths = analyze_paths(0, &competing_paths());
+
+        // Only the two winning paths; the loop, loss and dead end are dropped
+        let nodes: Vec<_> = paths.iter().map(|p| p.nodes.clone()).collect();
+        assert_eq!(nodes, vec![vec![0, 1, 3, 6], vec![0, 2, 4, 6]]);
+
+        // 0.72 * 1200 / 3 beats 0.855 * 600 / 3 despite the lower survival
+        assert!((paths[0].survival - 0.72).abs() < 1e-9);
+        assert!((paths[0].score - 288.0).abs() < 1e-9);
+        assert!((paths[1].score - 171.0).abs() < 1e-9);
+        assert_eq!(paths[0].decisions, vec!["buy Blueprint", "play flush"]);
+        assert_eq!(paths[1].decisions, vec!["buy Greedy Joker", "play pair"]);
+    }
+
+    #[test]
+    fn test_no_winning_path() {
+        let graph = competing_paths();
+        assert!(analyze_paths(7, &graph).is_empty());
+        assert!(analyze_paths(5, &graph).is_empty());
+        assert!(analyze_paths(42, &graph).is_empty());
+    }
+
+    #[test]
+    fn test_search_stops_once_top_paths_are_settled() {
+        use RunNodeKind::*;
+
+        // 40 rounds of "high" or "low", so 2^40 winning paths in total
+        const ROUNDS: NodeId = 40;
+        let mut graph = RunGraph::new();
+        graph.add_node(run_node(0, GameStart, "", 1, 0.0));
+        let win = 2 * ROUNDS + 1;
+        graph.add_node(run_node(win, Win, "", 8, 0.0));
+        for round in 0..ROUNDS {
+            let (high, low) = (2 * round + 1, 2 * round + 2);
+            graph.add_node(run_node(high, Round, "high", 1, 10.0));
+            graph.add_node(run_node(low, Round, "low", 1, 0.0));
+            let before = if round == 0 {
+                vec![0]
+            } else {
+                vec![high - 2, low - 2]
+            };
+            for from in before {
+                graph.add_edge(from, high, 1.0);
+                graph.add_edge(from, low, 1.0);
+            }
+        }
+        graph.add_edge(2 * ROUNDS - 1, win, 1.0);
+        graph.add_edge(2 * ROUNDS, win, 1.0);
+
+        let paths = analyze_paths(0, &graph);
+        assert_eq!(paths.len(), TOP_PATHS);
+        assert!((paths[0].score - 400.0 / 8.0).abs() < 1e-9);
+        assert!(paths[0].decisions.iter().all(|d| d == "high"));
+        assert!(paths[1..]
+            .iter()
+            .all(|p| (p.score - 390.0 / 8.0).abs() < 1e-9));
+    }
 }
@@ -1,6 +1,8 @@
 //! Performance benchmarks for the Balatro RNG system
 
-use balatro_emulator::utils::{BalatroRng, PseudorandomState, SeedType};
+use balatro_emulator::utils::{
+    AliasTable, BalatroRng, PseudorandomState, ReplayCache, RngCall, RngDump, SeedType,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn benchmark_pseudoseed_generation(c: &mut Criterion) {
@@ -45,6 +47,91 @@ fn benchmark_pseudorandom_string(c: &mut Criterion) {
     });
 }
 
+fn benchmark_pseudorandom_repeated(c: &mut Criterion) {
+    let mut uncached = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
+    let mut cached = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
+    cached.enable_pseudorandom_cache(256);
+
+    let mut group = c.benchmark_group("pseudorandom_repeated");
+    group.bench_function("uncached", |b| {
+        b.iter(|| uncached.pseudorandom(black_box(SeedType::Numeric(42)), Some(1), Some(100)))
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| cached.pseudorandom(black_box(SeedType::Numeric(42)), Some(1), Some(100)))
+    });
+
+    // A full cache, hit in an order that keeps moving entries around
+    let seeds: Vec<u64> = (0..256).map(|i| (i * 97) % 256).collect();
+    for &seed in &seeds {
+        cached.pseudorandom(SeedType::Numeric(seed), Some(1), Some(100));
+    }
+    group.bench_function("uncached_many_keys", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % seeds.len();
+            uncached.pseudorandom(black_box(SeedType::Numeric(seeds[i])), Some(1), Some(100))
+        })
+    });
+    group.bench_function("cached_full", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % seeds.len();
+            cached.pseudorandom(black_box(SeedType::Numeric(seeds[i])), Some(1), Some(100))
+        })
+    });
+    group.finish();
+}
+
+fn benchmark_replay_recorded_run(c: &mut Criterion) {
+    // Record a run: 2000 draws spread over a handful of keys
+    let start = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
+    let mut recorder = BalatroRng::from_state(start.state().clone());
+    let keys = [
+        "rarity1",
+        "front1",
+        "cdt1",
+        "lucky_mult",
+        "wheel_of_fortune",
+    ];
+    let calls = (0..2000)
+        .map(|i| {
+            let key = keys[i % keys.len()];
+            let (min, max) = if i % 2 == 0 {
+                (None, None)
+            } else {
+                (Some(1), Some(52))
+            };
+            let seed = recorder.pseudoseed(key);
+            RngCall {
+                line: i + 1,
+                key: key.to_string(),
+                min,
+                max,
+                result: recorder.pseudorandom(SeedType::Numeric(seed), min, max),
+                tolerance: 0.0,
+            }
+        })
+        .collect();
+    let dump = RngDump { calls };
+
+    // Branch-and-compare re-walks the same prefix, so the cache stays warm
+    let mut cache = ReplayCache::new();
+    let mut group = c.benchmark_group("replay_recorded_run");
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            let mut rng = BalatroRng::from_state(start.state().clone());
+            black_box(rng.verify_against_dump(black_box(&dump)))
+        })
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            let mut rng = BalatroRng::from_state(start.state().clone());
+            black_box(rng.verify_against_dump_cached(black_box(&dump), &mut cache))
+        })
+    });
+    group.finish();
+}
+
 fn benchmark_pseudoshuffle(c: &mut Criterion) {
     let mut rng = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
 
@@ -95,6 +182,32 @@ fn benchmark_weighted_choice(c: &mut Criterion) {
     });
 }
 
+fn benchmark_alias_vs_linear_choice(c: &mut Criterion) {
+    let mut rng = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
+    let choices: Vec<(usize, f64)> = (0..300).map(|i| (i, 1.0 + (i % 7) as f64)).collect();
+    let weights: Vec<f64> = choices.iter().map(|(_, w)| *w).collect();
+    let table = AliasTable::from_weights(&weights).unwrap();
+
+    let mut group = c.benchmark_group("weighted_choice_300");
+    group.bench_function("linear", |b| {
+        let mut counter = 0;
+        b.iter(|| {
+            let choice = rng.weighted_choice(black_box(&choices), counter);
+            counter += 1;
+            black_box(choice.copied())
+        })
+    });
+    group.bench_function("alias", |b| {
+        let mut counter = 0;
+        b.iter(|| {
+            let choice = rng.alias_choice(black_box(&table), counter);
+            counter += 1;
+            black_box(choice)
+        })
+    });
+    group.finish();
+}
+
 fn benchmark_probability_check(c: &mut Criterion) {
     let mut rng = BalatroRng::new(SeedType::String("BENCHMARK".to_string()));
 
@@ -221,9 +334,12 @@ criterion_group!(
     benchmark_pseudoseed_generation,
     benchmark_pseudorandom_numeric,
     benchmark_pseudorandom_string,
+    benchmark_pseudorandom_repeated,
+    benchmark_replay_recorded_run,
     benchmark_pseudoshuffle,
     benchmark_pseudorandom_element,
     benchmark_weighted_choice,
+    benchmark_alias_vs_linear_choice,
     benchmark_probability_check,
     benchmark_card_rng_generation,
     benchmark_shop_rng_generation,
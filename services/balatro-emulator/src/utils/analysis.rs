@@ -0,0 +1,185 @@
+//! Batch analysis over ranges of seeds
+//!
+//! Runs an analyzer against a fresh `BalatroRng` per seed in parallel and
+//! folds the results into aggregate statistics for seed-quality tooling.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use super::rng::{BalatroRng, SeedType};
+
+/// Aggregate statistics over per-seed analyzer results
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary<T> {
+    /// Number of seeds analyzed
+    pub count: usize,
+    /// How many seeds produced each distinct result
+    pub histogram: BTreeMap<T, usize>,
+    /// Smallest result, if any seeds were analyzed
+    pub min: Option<T>,
+    /// Largest result, if any seeds were analyzed
+    pub max: Option<T>,
+    /// All results folded with the caller's reducer, in seed order
+    pub reduced: Option<T>,
+}
+
+impl<T: Ord + Clone> Summary<T> {
+    fn empty() -> Self {
+        Self {
+            count: 0,
+            histogram: BTreeMap::new(),
+            min: None,
+            max: None,
+            reduced: None,
+        }
+    }
+
+    fn add(&mut self, value: T, reduce: &impl Fn(T, T) -> T) {
+        self.count += 1;
+        *self.histogram.entry(value.clone()).or_insert(0) += 1;
+        if self.min.as_ref().is_none_or(|min| value < *min) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| value > *max) {
+            self.max = Some(value.clone());
+        }
+        self.reduced = Some(match self.reduced.take() {
+            Some(acc) => reduce(acc, value),
+            None => value,
+        });
+    }
+
+    fn merge(&mut self, other: Summary<T>, reduce: &impl Fn(T, T) -> T) {
+        self.count += other.count;
+        for (value, n) in other.histogram {
+            *self.histogram.entry(value).or_insert(0) += n;
+        }
+        self.min = match (self.min.take(), other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max.take(), other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.reduced = match (self.reduced.take(), other.reduced) {
+            (Some(a), Some(b)) => Some(reduce(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl<T: Copy + Into<f64>> Summary<T> {
+    /// Mean of all results, if any seeds were analyzed
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let total: f64 = self
+            .histogram
+            .iter()
+            .map(|(value, n)| (*value).into() * *n as f64)
+            .sum();
+        Some(total / self.count as f64)
+    }
+}
+
+/// Run `analyzer` for each numeric seed in `start..start + count` and summarize
+///
+/// Each seed gets a fresh `BalatroRng::new(SeedType::Numeric(seed))`. Seeds
+/// are split into contiguous chunks across threads; `reduce` must be
+/// associative since partial results are combined chunk by chunk.
+///
+/// # Panics
+///
+/// Panics if the range runs past `u64::MAX`. A range ending exactly at
+/// `u64::MAX` is fine.
+pub fn analyze_seed_range<T, A, R>(start: u64, count: u64, analyzer: A, reduce: R) -> Summary<T>
+where
+    T: Ord + Clone + Send,
+    A: Fn(&mut BalatroRng) -> T + Sync,
+    R: Fn(T, T) -> T + Sync,
+{
+    assert!(
+        count == 0 || start.checked_add(count - 1).is_some(),
+        "seed range {start} + {count} runs past u64::MAX"
+    );
+    let threads = thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1) as u64;
+    let chunk = count.div_ceil(threads.max(1)).max(1);
+
+    let partials: Vec<Summary<T>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..count)
+            .step_by(chunk as usize)
+            .map(|offset| {
+                let (analyzer, reduce) = (&analyzer, &reduce);
+                scope.spawn(move || {
+                    let mut summary = Summary::empty();
+                    for i in offset..offset.saturating_add(chunk).min(count) {
+                        let mut rng = BalatroRng::new(SeedType::Numeric(start + i));
+                        summary.add(analyzer(&mut rng), reduce);
+                    }
+                    summary
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("seed analyzer panicked"))
+            .collect()
+    });
+
+    partials
+        .into_iter()
+        .fold(Summary::empty(), |mut acc, partial| {
+            acc.merge(partial, &reduce);
+            acc
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_draw(rng: &mut BalatroRng) -> u32 {
+        let seed = rng.pseudoseed("rarity1");
+        rng.pseudorandom(SeedType::Numeric(seed), Some(1), Some(10)) as u32
+    }
+
+    #[test]
+    fn test_analyze_seed_range_summary() {
+        let summary = analyze_seed_range(1000, 50, first_draw, |a, b| a + b);
+
+        let expected: Vec<u32> = (1000..1050)
+            .map(|seed| first_draw(&mut BalatroRng::new(SeedType::Numeric(seed))))
+            .collect();
+
+        assert_eq!(summary.count, 50);
+        assert_eq!(summary.histogram.values().sum::<usize>(), 50);
+        assert_eq!(summary.min, expected.iter().min().copied());
+        assert_eq!(summary.max, expected.iter().max().copied());
+        assert!(summary.min.unwrap() >= 1 && summary.max.unwrap() <= 10);
+        assert_eq!(summary.reduced, Some(expected.iter().sum()));
+
+        let mean = expected.iter().sum::<u32>() as f64 / 50.0;
+        assert!((summary.mean().unwrap() - mean).abs() < 1e-9);
+
+        let empty = analyze_seed_range(0, 0, first_draw, |a, b| a + b);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.mean(), None);
+    }
+
+    #[test]
+    fn test_analyze_seed_range_up_to_u64_max() {
+        let summary = analyze_seed_range(u64::MAX - 4, 5, first_draw, |a, b| a + b);
+        assert_eq!(summary.count, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "runs past u64::MAX")]
+    fn test_analyze_seed_range_rejects_overflow() {
+        analyze_seed_range(u64::MAX - 4, 6, first_draw, |a, b| a + b);
+    }
+}
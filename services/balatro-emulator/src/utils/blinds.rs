@@ -0,0 +1,187 @@
+//! Blind definitions for the Balatro emulator
+//!
+//! Boss blinds are identified by the same keys the game uses (`bl_hook`,
+//! `bl_final_acorn`, ...) so emulated runs can be compared against real ones.
+
+use serde::{Deserialize, Serialize};
+
+/// Ante a run is won at; every multiple of it is a finisher boss ante
+pub const WIN_ANTE: u8 = 8;
+
+/// A boss blind that can appear as the third blind of an ante
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BossBlind {
+    TheHook,
+    TheOx,
+    TheHouse,
+    TheWall,
+    TheWheel,
+    TheArm,
+    TheClub,
+    TheFish,
+    ThePsychic,
+    TheGoad,
+    TheWater,
+    TheWindow,
+    TheManacle,
+    TheEye,
+    TheMouth,
+    ThePlant,
+    TheSerpent,
+    ThePillar,
+    TheNeedle,
+    TheHead,
+    TheTooth,
+    TheFlint,
+    TheMark,
+    AmberAcorn,
+    VerdantLeaf,
+    VioletVessel,
+    CrimsonHeart,
+    CeruleanBell,
+}
+
+impl BossBlind {
+    /// Every boss blind, in the game's definition order
+    pub const ALL: [BossBlind; 28] = [
+        BossBlind::TheHook,
+        BossBlind::TheOx,
+        BossBlind::TheHouse,
+        BossBlind::TheWall,
+        BossBlind::TheWheel,
+        BossBlind::TheArm,
+        BossBlind::TheClub,
+        BossBlind::TheFish,
+        BossBlind::ThePsychic,
+        BossBlind::TheGoad,
+        BossBlind::TheWater,
+        BossBlind::TheWindow,
+        BossBlind::TheManacle,
+        BossBlind::TheEye,
+        BossBlind::TheMouth,
+        BossBlind::ThePlant,
+        BossBlind::TheSerpent,
+        BossBlind::ThePillar,
+        BossBlind::TheNeedle,
+        BossBlind::TheHead,
+        BossBlind::TheTooth,
+        BossBlind::TheFlint,
+        BossBlind::TheMark,
+        BossBlind::AmberAcorn,
+        BossBlind::VerdantLeaf,
+        BossBlind::VioletVessel,
+        BossBlind::CrimsonHeart,
+        BossBlind::CeruleanBell,
+    ];
+
+    /// Whether this is a finisher (showdown) boss, which only appears on
+    /// antes that are multiples of `WIN_ANTE`
+    pub fn is_finisher(&self) -> bool {
+        matches!(
+            self,
+            BossBlind::AmberAcorn
+                | BossBlind::VerdantLeaf
+                | BossBlind::VioletVessel
+                | BossBlind::CrimsonHeart
+                | BossBlind::CeruleanBell
+        )
+    }
+
+    /// Earliest ante the boss can appear at (`boss.min` in the game)
+    ///
+    /// Finishers report `WIN_ANTE`, the first ante they can appear at.
+    pub fn min_ante(&self) -> u8 {
+        match self {
+            BossBlind::TheOx => 6,
+            BossBlind::TheSerpent => 5,
+            BossBlind::ThePlant => 4,
+            BossBlind::TheEye | BossBlind::TheTooth => 3,
+            BossBlind::TheHouse
+            | BossBlind::TheWall
+            | BossBlind::TheWheel
+            | BossBlind::TheArm
+            | BossBlind::TheFish
+            | BossBlind::TheWater
+            | BossBlind::TheMouth
+            | BossBlind::TheNeedle
+            | BossBlind::TheFlint
+            | BossBlind::TheMark => 2,
+            boss if boss.is_finisher() => WIN_ANTE,
+            _ => 1,
+        }
+    }
+
+    /// Whether the game offers this boss at `ante`
+    ///
+    /// Finisher antes (multiples of `WIN_ANTE` from 8 on) offer only
+    /// finishers; every other ante offers the regular bosses whose
+    /// `min_ante` has been reached.
+    pub fn is_eligible(&self, ante: u8) -> bool {
+        // `u8::is_multiple_of` needs Rust 1.87
+        #[allow(clippy::manual_is_multiple_of)]
+        let finisher_ante = ante >= 2 && ante % WIN_ANTE == 0;
+        if self.is_finisher() {
+            finisher_ante
+        } else {
+            !finisher_ante && self.min_ante() <= ante.max(1)
+        }
+    }
+
+    /// The game's key for this blind
+    pub fn key(&self) -> &'static str {
+        match self {
+            BossBlind::TheHook => "bl_hook",
+            BossBlind::TheOx => "bl_ox",
+            BossBlind::TheHouse => "bl_house",
+            BossBlind::TheWall => "bl_wall",
+            BossBlind::TheWheel => "bl_wheel",
+            BossBlind::TheArm => "bl_arm",
+            BossBlind::TheClub => "bl_club",
+            BossBlind::TheFish => "bl_fish",
+            BossBlind::ThePsychic => "bl_psychic",
+            BossBlind::TheGoad => "bl_goad",
+            BossBlind::TheWater => "bl_water",
+            BossBlind::TheWindow => "bl_window",
+            BossBlind::TheManacle => "bl_manacle",
+            BossBlind::TheEye => "bl_eye",
+            BossBlind::TheMouth => "bl_mouth",
+            BossBlind::ThePlant => "bl_plant",
+            BossBlind::TheSerpent => "bl_serpent",
+            BossBlind::ThePillar => "bl_pillar",
+            BossBlind::TheNeedle => "bl_needle",
+            BossBlind::TheHead => "bl_head",
+            BossBlind::TheTooth => "bl_tooth",
+            BossBlind::TheFlint => "bl_flint",
+            BossBlind::TheMark => "bl_mark",
+            BossBlind::AmberAcorn => "bl_final_acorn",
+            BossBlind::VerdantLeaf => "bl_final_leaf",
+            BossBlind::VioletVessel => "bl_final_vessel",
+            BossBlind::CrimsonHeart => "bl_final_heart",
+            BossBlind::CeruleanBell => "bl_final_bell",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ante_eligibility() {
+        let eligible = |ante| -> Vec<BossBlind> {
+            BossBlind::ALL
+                .into_iter()
+                .filter(|boss| boss.is_eligible(ante))
+                .collect()
+        };
+        for ante in [8, 16, 24] {
+            assert!(eligible(ante).iter().all(BossBlind::is_finisher));
+            assert_eq!(eligible(ante).len(), 5);
+        }
+        assert_eq!(eligible(1).len(), 8);
+        assert!(!eligible(5).contains(&BossBlind::TheOx));
+        assert!(eligible(6).contains(&BossBlind::TheOx));
+        assert!(!eligible(7).iter().any(BossBlind::is_finisher));
+        assert_eq!(eligible(0), eligible(1));
+    }
+}
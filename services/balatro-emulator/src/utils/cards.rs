@@ -0,0 +1,139 @@
+//! Playing card modifier definitions for the Balatro emulator
+//!
+//! Enhancements, editions and seals are rolled independently when a card is
+//! generated; `ModifierOdds` holds the chance of each aspect.
+
+use serde::{Deserialize, Serialize};
+
+/// Card enhancement (the center applied to a playing card)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Enhancement {
+    Bonus,
+    Mult,
+    Wild,
+    Glass,
+    Steel,
+    Stone,
+    Gold,
+    Lucky,
+}
+
+impl Enhancement {
+    /// Every enhancement, in the game's definition order
+    pub const ALL: [Enhancement; 8] = [
+        Enhancement::Bonus,
+        Enhancement::Mult,
+        Enhancement::Wild,
+        Enhancement::Glass,
+        Enhancement::Steel,
+        Enhancement::Stone,
+        Enhancement::Gold,
+        Enhancement::Lucky,
+    ];
+}
+
+/// Card edition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Edition {
+    Foil,
+    Holographic,
+    Polychrome,
+    Negative,
+}
+
+/// Card seal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Seal {
+    Gold,
+    Red,
+    Blue,
+    Purple,
+}
+
+impl Seal {
+    /// Map a uniform roll in [0, 1) to a seal type as Standard packs do:
+    /// Red above 0.75, Blue above 0.5, Gold above 0.25, otherwise Purple
+    pub fn for_roll(roll: f64) -> Seal {
+        if roll > 0.75 {
+            Seal::Red
+        } else if roll > 0.5 {
+            Seal::Blue
+        } else if roll > 0.25 {
+            Seal::Gold
+        } else {
+            Seal::Purple
+        }
+    }
+}
+
+/// Result of rolling every modifier aspect for one card
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CardModifiers {
+    pub enhancement: Option<Enhancement>,
+    pub edition: Option<Edition>,
+    pub seal: Option<Seal>,
+}
+
+/// Chance of each modifier aspect applying to a generated card
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModifierOdds {
+    /// Chance the card is enhanced at all
+    pub enhancement_chance: f64,
+    /// Chance the card gets a seal at all
+    pub seal_chance: f64,
+    pub foil_chance: f64,
+    pub holographic_chance: f64,
+    pub polychrome_chance: f64,
+    pub negative_chance: f64,
+}
+
+impl Default for ModifierOdds {
+    /// Standard pack odds; playing cards never roll Negative
+    ///
+    /// Standard packs call `poll_edition` with its rate doubled, so editions
+    /// are twice the base odds: 4% Foil, 2.8% Holographic, 1.2% Polychrome.
+    fn default() -> Self {
+        Self {
+            enhancement_chance: 0.4,
+            seal_chance: 0.2,
+            foil_chance: 0.04,
+            holographic_chance: 0.028,
+            polychrome_chance: 0.012,
+            negative_chance: 0.0,
+        }
+    }
+}
+
+impl ModifierOdds {
+    /// These odds with `poll_edition`'s base edition chances (2% Foil, 1.4%
+    /// Holographic, 0.6% Polychrome), as for cards outside Standard packs
+    pub fn with_base_editions(self) -> Self {
+        Self {
+            foil_chance: 0.02,
+            holographic_chance: 0.014,
+            polychrome_chance: 0.006,
+            ..self
+        }
+    }
+
+    /// Map a uniform roll in [0, 1) to an edition, rarest first from the top
+    pub fn edition_for_roll(&self, roll: f64) -> Option<Edition> {
+        let mut threshold = 1.0 - self.negative_chance;
+        if roll >= threshold {
+            return Some(Edition::Negative);
+        }
+        threshold -= self.polychrome_chance;
+        if roll >= threshold {
+            return Some(Edition::Polychrome);
+        }
+        threshold -= self.holographic_chance;
+        if roll >= threshold {
+            return Some(Edition::Holographic);
+        }
+        threshold -= self.foil_chance;
+        if roll >= threshold {
+            return Some(Edition::Foil);
+        }
+        None
+    }
+}
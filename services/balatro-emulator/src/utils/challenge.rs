@@ -0,0 +1,69 @@
+//! Challenge run definitions for the Balatro emulator
+//!
+//! A challenge fixes the starting deck and jokers and can pre-seed or pin
+//! specific RNG keys, so emulated challenge runs consume keys the way the
+//! game does rather than following the vanilla progression.
+
+use serde::{Deserialize, Serialize};
+
+/// A challenge definition applied to a `BalatroRng`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Challenge {
+    /// Game id of the challenge (e.g. `c_omelette_1`)
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Joker keys the run starts with
+    pub jokers: Vec<String>,
+    /// Playing card keys (`S_A`, `H_T`, ...) the run starts with
+    pub deck: Vec<String>,
+    /// Key seed values installed before the run starts
+    pub seeded_keys: Vec<(String, u64)>,
+    /// Keys whose pseudoseed output is pinned for the whole run
+    pub fixed_seeds: Vec<(String, u64)>,
+}
+
+impl Challenge {
+    /// "The Omelette": five Eggs and a standard deck
+    pub fn omelette() -> Self {
+        Self {
+            id: "c_omelette_1".to_string(),
+            name: "The Omelette".to_string(),
+            jokers: vec!["j_egg".to_string(); 5],
+            deck: standard_deck(),
+            seeded_keys: Vec::new(),
+            fixed_seeds: Vec::new(),
+        }
+    }
+
+    /// "Jokerless": no starting jokers and a standard deck
+    pub fn jokerless() -> Self {
+        Self {
+            id: "c_jokerless_1".to_string(),
+            name: "Jokerless".to_string(),
+            jokers: Vec::new(),
+            deck: standard_deck(),
+            seeded_keys: Vec::new(),
+            fixed_seeds: Vec::new(),
+        }
+    }
+
+    /// Pinned pseudoseed output for `key`, if the challenge fixes one
+    pub fn fixed_seed(&self, key: &str) -> Option<u64> {
+        self.fixed_seeds
+            .iter()
+            .find(|(fixed, _)| fixed == key)
+            .map(|(_, seed)| *seed)
+    }
+}
+
+/// The 52 standard playing card keys in suit-major order
+pub fn standard_deck() -> Vec<String> {
+    let ranks = [
+        "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
+    ];
+    ["S", "H", "C", "D"]
+        .iter()
+        .flat_map(|suit| ranks.iter().map(move |rank| format!("{suit}_{rank}")))
+        .collect()
+}
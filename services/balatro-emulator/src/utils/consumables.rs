@@ -0,0 +1,186 @@
+//! Consumable definitions for the Balatro emulator
+//!
+//! Pools hold the game's center keys (`c_fool`, `c_pluto`, ...) and can be
+//! replaced wholesale so modded content draws through the same RNG keys.
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of consumable card, which selects both the pool and the RNG key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsumableKind {
+    Tarot,
+    Planet,
+    Spectral,
+}
+
+impl ConsumableKind {
+    /// The game's set name, used as the pseudoseed key prefix
+    pub fn key(&self) -> &'static str {
+        match self {
+            ConsumableKind::Tarot => "Tarot",
+            ConsumableKind::Planet => "Planet",
+            ConsumableKind::Spectral => "Spectral",
+        }
+    }
+}
+
+/// A consumable center key such as `c_fool`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConsumableId(pub String);
+
+impl ConsumableId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ConsumableId {
+    fn from(key: &str) -> Self {
+        ConsumableId(key.to_string())
+    }
+}
+
+/// Pools that consumables are drawn from, one per kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsumablePools {
+    pub tarot: Vec<ConsumableId>,
+    pub planet: Vec<ConsumableId>,
+    pub spectral: Vec<ConsumableId>,
+}
+
+impl ConsumablePools {
+    /// The pool for a given kind
+    pub fn pool(&self, kind: ConsumableKind) -> &[ConsumableId] {
+        match kind {
+            ConsumableKind::Tarot => &self.tarot,
+            ConsumableKind::Planet => &self.planet,
+            ConsumableKind::Spectral => &self.spectral,
+        }
+    }
+}
+
+/// A pool that does not repeat a consumable until every item has been drawn
+///
+/// Drawn items are remembered until the pool is exhausted, at which point the
+/// refresh window restarts with the full pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsumablePool {
+    items: Vec<ConsumableId>,
+    drawn: Vec<bool>,
+}
+
+impl ConsumablePool {
+    pub fn new(items: Vec<ConsumableId>) -> Self {
+        let drawn = vec![false; items.len()];
+        Self { items, drawn }
+    }
+
+    /// Items not yet drawn in the current refresh window, in pool order
+    pub fn available(&self) -> Vec<&ConsumableId> {
+        self.items
+            .iter()
+            .zip(&self.drawn)
+            .filter(|(_, &drawn)| !drawn)
+            .map(|(item, _)| item)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Start a new refresh window with every item available again
+    pub fn refresh(&mut self) {
+        self.drawn.fill(false);
+    }
+
+    /// Mark the `n`th available item as drawn and return it
+    pub(crate) fn take_available(&mut self, n: usize) -> ConsumableId {
+        let index = self
+            .drawn
+            .iter()
+            .enumerate()
+            .filter(|(_, &drawn)| !drawn)
+            .nth(n)
+            .map(|(index, _)| index)
+            .expect("index within available items");
+        self.drawn[index] = true;
+        self.items[index].clone()
+    }
+}
+
+const VANILLA_TAROTS: [&str; 22] = [
+    "c_fool",
+    "c_magician",
+    "c_high_priestess",
+    "c_empress",
+    "c_emperor",
+    "c_heirophant",
+    "c_lovers",
+    "c_chariot",
+    "c_justice",
+    "c_hermit",
+    "c_wheel_of_fortune",
+    "c_strength",
+    "c_hanged_man",
+    "c_death",
+    "c_temperance",
+    "c_devil",
+    "c_tower",
+    "c_star",
+    "c_moon",
+    "c_sun",
+    "c_judgement",
+    "c_world",
+];
+
+const VANILLA_PLANETS: [&str; 12] = [
+    "c_mercury",
+    "c_venus",
+    "c_earth",
+    "c_mars",
+    "c_jupiter",
+    "c_saturn",
+    "c_uranus",
+    "c_neptune",
+    "c_pluto",
+    "c_planet_x",
+    "c_ceres",
+    "c_eris",
+];
+
+// The Soul and Black Hole are hidden and never drawn from the regular pool
+const VANILLA_SPECTRALS: [&str; 16] = [
+    "c_familiar",
+    "c_grim",
+    "c_incantation",
+    "c_talisman",
+    "c_aura",
+    "c_wraith",
+    "c_sigil",
+    "c_ouija",
+    "c_ectoplasm",
+    "c_immolate",
+    "c_ankh",
+    "c_deja_vu",
+    "c_hex",
+    "c_trance",
+    "c_medium",
+    "c_cryptid",
+];
+
+impl Default for ConsumablePools {
+    /// The vanilla game's pools in definition order
+    fn default() -> Self {
+        let ids = |keys: &[&str]| keys.iter().map(|&key| ConsumableId::from(key)).collect();
+        Self {
+            tarot: ids(&VANILLA_TAROTS),
+            planet: ids(&VANILLA_PLANETS),
+            spectral: ids(&VANILLA_SPECTRALS),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Conformance checking against the game's RNG debug dump
+//!
+//! A modded game can log every `pseudorandom(key, min, max)` call. The dump is
+//! plain text, one call per line with tab-separated fields:
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! rarity1\tnil\tnil\t0.7152
+//! front1\t1\t52\t17
+//! ```
+//!
+//! `min`/`max` are integers or `nil`; the last field is the value the game got.
+//! Results are compared only to the precision they were printed at, so
+//! `0.7152` matches anything that rounds to it. Print with `%.17g` for an exact
+//! check. Replaying the calls through [`BalatroRng::verify_against_dump`] reports the
+//! first call where the emulator diverges.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::rng::BalatroRng;
+
+/// Results closer than this are always considered equal, however many digits
+/// the dump printed
+const RESULT_TOLERANCE: f64 = 1e-9;
+
+/// Half a unit in the last printed digit of `text`, e.g. `0.00005` for `0.7152`
+fn printed_tolerance(text: &str) -> f64 {
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse().unwrap_or(0)),
+        None => (text, 0),
+    };
+    let decimals = mantissa.split_once('.').map_or(0, |(_, frac)| frac.len());
+    let last_digit = exponent - i32::try_from(decimals).unwrap_or(i32::MAX);
+    (0.5 * 10f64.powi(last_digit)).max(RESULT_TOLERANCE)
+}
+
+/// Errors parsing an RNG dump
+#[derive(Debug, thiserror::Error)]
+pub enum RngDumpError {
+    #[error("line {line}: expected 4 tab-separated fields, found {found}")]
+    FieldCount { line: usize, found: usize },
+    #[error("line {line}: invalid {field} '{value}'")]
+    InvalidField {
+        line: usize,
+        field: &'static str,
+        value: String,
+    },
+}
+
+/// One recorded `pseudorandom` call
+#[derive(Debug, Clone, PartialEq)]
+pub struct RngCall {
+    /// 1-based line in the dump, for error context
+    pub line: usize,
+    pub key: String,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub result: f64,
+    /// How far the emulator may be from `result`, given the digits printed
+    pub tolerance: f64,
+}
+
+impl fmt::Display for RngCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |b: Option<i32>| b.map_or("nil".to_string(), |b| b.to_string());
+        write!(
+            f,
+            "pseudorandom('{}', {}, {}) = {}",
+            self.key,
+            bound(self.min),
+            bound(self.max),
+            self.result
+        )
+    }
+}
+
+/// A parsed RNG dump, in call order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RngDump {
+    pub calls: Vec<RngCall>,
+}
+
+impl FromStr for RngDump {
+    type Err = RngDumpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut calls = Vec::new();
+        for (idx, raw) in s.lines().enumerate() {
+            let line = idx + 1;
+            let text = raw.trim_end_matches('\r');
+            if text.trim().is_empty() || text.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = text.split('\t').collect();
+            if fields.len() != 4 {
+                return Err(RngDumpError::FieldCount {
+                    line,
+                    found: fields.len(),
+                });
+            }
+
+            let invalid = |field, value: &str| RngDumpError::InvalidField {
+                line,
+                field,
+                value: value.to_string(),
+            };
+            let bound = |field, value: &str| match value.trim() {
+                "nil" => Ok(None),
+                v => v.parse().map(Some).map_err(|_| invalid(field, value)),
+            };
+
+            let result = fields[3].trim();
+            calls.push(RngCall {
+                line,
+                key: fields[0].to_string(),
+                min: bound("min", fields[1])?,
+                max: bound("max", fields[2])?,
+                result: result.parse().map_err(|_| invalid("result", fields[3]))?,
+                tolerance: printed_tolerance(result),
+            });
+        }
+        Ok(Self { calls })
+    }
+}
+
+/// The first call the emulator got wrong
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Index of the call in the dump
+    pub index: usize,
+    /// The recorded call, including the game's result
+    pub expected: RngCall,
+    /// What the emulator produced for the same call
+    pub actual: f64,
+    /// The last call that still matched, if any
+    pub previous: Option<RngCall>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "call {} (line {}) diverged: expected {}, emulator returned {}",
+            self.index, self.expected.line, self.expected, self.actual
+        )?;
+        if let Some(previous) = &self.previous {
+            write!(f, "; last match was {previous} (line {})", previous.line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of replaying a dump
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyReport {
+    /// Calls replayed, including the diverging one
+    pub calls_checked: usize,
+    pub divergence: Option<Divergence>,
+}
+
+impl VerifyReport {
+    pub fn is_match(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+impl BalatroRng {
+    /// Replay every call in `dump` and stop at the first result that differs
+    ///
+    /// Each call advances the key's seed exactly as the game does
+    /// (`pseudoseed(key)` then `pseudorandom`), so the RNG should start from
+    /// the same seed and state the game had when the dump began.
+    pub fn verify_against_dump(&mut self, dump: &RngDump) -> VerifyReport {
+        self.verify_with(dump, |rng, call| {
+            rng.pseudorandom_keyed(&call.key, call.min, call.max)
+        })
+    }
+
+    /// Replay `dump` producing each call's result with `draw`
+    pub(super) fn verify_with(
+        &mut self,
+        dump: &RngDump,
+        mut draw: impl FnMut(&mut Self, &RngCall) -> f64,
+    ) -> VerifyReport {
+        for (index, call) in dump.calls.iter().enumerate() {
+            let actual = draw(self, call);
+
+            if (actual - call.result).abs() > call.tolerance {
+                return VerifyReport {
+                    calls_checked: index + 1,
+                    divergence: Some(Divergence {
+                        index,
+                        expected: call.clone(),
+                        actual,
+                        previous: index.checked_sub(1).map(|i| dump.calls[i].clone()),
+                    }),
+                };
+            }
+        }
+
+        VerifyReport {
+            calls_checked: dump.calls.len(),
+            divergence: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rng::SeedType;
+
+    /// Record calls from the emulator itself in the dump format
+    fn record(seed: &str, calls: &[(&str, Option<i32>, Option<i32>)]) -> String {
+        let mut rng = BalatroRng::new(SeedType::String(seed.to_string()));
+        let bound = |b: Option<i32>| b.map_or("nil".to_string(), |b| b.to_string());
+        let mut dump = String::from("# synthetic dump\n\n");
+        for &(key, min, max) in calls {
+            let seed = rng.pseudoseed(key);
+            let result = rng.pseudorandom(SeedType::Numeric(seed), min, max);
+            dump.push_str(&format!(
+                "{key}\t{}\t{}\t{result}\n",
+                bound(min),
+                bound(max)
+            ));
+        }
+        dump
+    }
+
+    const CALLS: [(&str, Option<i32>, Option<i32>); 4] = [
+        ("rarity1", None, None),
+        ("front1", Some(1), Some(52)),
+        ("rarity1", None, None),
+        ("lucky_mult", Some(1), Some(5)),
+    ];
+
+    #[test]
+    fn test_matching_dump_verifies() {
+        let dump: RngDump = record("DUMP", &CALLS).parse().unwrap();
+        assert_eq!(dump.calls.len(), 4);
+        assert_eq!(dump.calls[1].line, 4);
+
+        let mut rng = BalatroRng::new(SeedType::String("DUMP".to_string()));
+        let report = rng.verify_against_dump(&dump);
+        assert!(report.is_match(), "{:?}", report.divergence);
+        assert_eq!(report.calls_checked, 4);
+    }
+
+    #[test]
+    fn test_divergence_is_pinpointed() {
+        let mut dump: RngDump = record("DUMP", &CALLS).parse().unwrap();
+        dump.calls[2].result += 0.5;
+
+        let mut rng = BalatroRng::new(SeedType::String("DUMP".to_string()));
+        let report = rng.verify_against_dump(&dump);
+        let divergence = report.divergence.expect("tampered call must diverge");
+        assert_eq!(report.calls_checked, 3);
+        assert_eq!(divergence.index, 2);
+        assert_eq!(divergence.expected.key, "rarity1");
+        assert_eq!(divergence.previous.unwrap().key, "front1");
+    }
+
+    #[test]
+    fn test_rounded_results_verify_at_printed_precision() {
+        let mut rng = BalatroRng::new(SeedType::String("DUMP".to_string()));
+        let seed = rng.pseudoseed("rarity1");
+        let exact = rng.pseudorandom(SeedType::Numeric(seed), None, None);
+        let rounded: RngDump = format!("rarity1\tnil\tnil\t{exact:.4}").parse().unwrap();
+        assert_eq!(rounded.calls[0].tolerance, 0.5e-4);
+
+        let mut rng = BalatroRng::new(SeedType::String("DUMP".to_string()));
+        assert!(rng.verify_against_dump(&rounded).is_match());
+
+        // One unit off in the last printed digit is a real divergence
+        let off: RngDump = format!("rarity1\tnil\tnil\t{:.4}", exact + 1e-4)
+            .parse()
+            .unwrap();
+        let mut rng = BalatroRng::new(SeedType::String("DUMP".to_string()));
+        assert!(!rng.verify_against_dump(&off).is_match());
+    }
+
+    #[test]
+    fn test_printed_tolerance() {
+        assert_eq!(printed_tolerance("17"), 0.5);
+        assert_eq!(printed_tolerance("7.152e-1"), 0.5e-4);
+        assert_eq!(printed_tolerance("0.71523947189312345"), RESULT_TOLERANCE);
+    }
+
+    #[test]
+    fn test_malformed_lines_report_line_number() {
+        let err = "rarity1\tnil\tnil\t0.5\nfront1\t1\t52\n"
+            .parse::<RngDump>()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RngDumpError::FieldCount { line: 2, found: 3 }
+        ));
+
+        let err = "front1\tone\t52\t3".parse::<RngDump>().unwrap_err();
+        assert!(matches!(
+            err,
+            RngDumpError::InvalidField {
+                line: 1,
+                field: "min",
+                ..
+            }
+        ));
+    }
+}
@@ -3,6 +3,37 @@
 //! This module contains utility functions and structures that support
 //! the core game engine, including RNG, object pooling, and helper functions.
 
+pub mod analysis;
+pub mod blinds;
+pub mod cards;
+pub mod challenge;
+pub mod consumables;
+pub mod dump;
+pub mod replay;
+pub mod retrigger;
 pub mod rng;
+pub mod scoring;
+pub mod shop;
+pub mod siphash;
+pub mod stake;
+pub mod vouchers;
 
-pub use rng::{BalatroRng, PseudorandomState, SeedType};
+pub use analysis::{analyze_seed_range, Summary};
+pub use blinds::BossBlind;
+pub use cards::{CardModifiers, Edition, Enhancement, ModifierOdds, Seal};
+pub use challenge::{standard_deck, Challenge};
+pub use consumables::{ConsumableId, ConsumableKind, ConsumablePool, ConsumablePools};
+pub use dump::{Divergence, RngCall, RngDump, RngDumpError, VerifyReport};
+pub use replay::ReplayCache;
+pub use retrigger::{
+    CardArea, HandState, Retrigger, RetriggerRule, RetriggerScheduler, RetriggerSource, ScoredCard,
+};
+pub use rng::{
+    advance_game_seed, permutation_to_rank, rank_to_permutation, stable_hash, AliasTable,
+    BalatroRng, PseudorandomState, SeedParseError, SeedType, StateLoadError,
+    MAX_RANKED_PERMUTATION_LEN, MAX_SEED_LEN, PSEUDORANDOM_STATE_VERSION,
+};
+pub use scoring::{hand_base, HandBase, HandType, LuckyOutcome, PlanetLevels, ScoringContext};
+pub use shop::{reroll_cost, slot_count, ShopModifiers};
+pub use stake::{JokerRarity, JokerStickers, Stake, StakeModifiers};
+pub use vouchers::{vanilla_vouchers, VoucherDef, VoucherEffect, VoucherState};
@@ -0,0 +1,238 @@
+//! Draw memoization for replaying recorded runs
+//!
+//! A replayed `pseudorandom(key, min, max)` call is a pure function of the
+//! state version, base seed, the key, the key's current seed and the bounds.
+//! `ReplayCache` memoizes draws on exactly those inputs, so re-walking the
+//! same prefix of a run (as branch-and-compare search does from a shared
+//! start) skips the seed hashing and ChaCha setup, while any divergence
+//! changes a key's seed and misses instead of returning a stale draw.
+
+use ahash::AHashMap;
+
+use super::dump::{RngDump, VerifyReport};
+use super::rng::BalatroRng;
+
+/// The parts of a `PseudorandomState` every draw depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StateFingerprint {
+    version: u32,
+    base_seed: u64,
+}
+
+impl StateFingerprint {
+    fn of(rng: &BalatroRng) -> Self {
+        Self {
+            version: rng.state().version(),
+            base_seed: rng.state().base_seed(),
+        }
+    }
+}
+
+/// A key's seed and the `min`/`max` bounds of one draw on it
+type Draw = (u64, Option<i32>, Option<i32>);
+
+/// Memoized draws of a replay session, shareable across branches of one run
+///
+/// The cache is bound to the state fingerprint of the first RNG that uses it
+/// and clears itself when used with a different seed or state version. The
+/// hit and miss counts cover only the current binding.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayCache {
+    fingerprint: Option<StateFingerprint>,
+    /// Results by key, then by draw
+    draws: AHashMap<String, AHashMap<Draw, f64>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ReplayCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of memoized draws
+    pub fn len(&self) -> usize {
+        self.draws.values().map(|draws| draws.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    /// Draws answered from the cache since it was last cleared
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Draws that had to be computed since the cache was last cleared
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drop every memoized draw, reset the hit and miss counts and unbind
+    /// from the current state
+    pub fn clear(&mut self) {
+        self.fingerprint = None;
+        self.draws.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Bind to `rng`'s state, clearing draws memoized for another one
+    fn bind(&mut self, rng: &BalatroRng) {
+        let fingerprint = StateFingerprint::of(rng);
+        if self.fingerprint != Some(fingerprint) {
+            self.clear();
+            self.fingerprint = Some(fingerprint);
+        }
+    }
+}
+
+impl BalatroRng {
+    /// `pseudoseed(key)` followed by `pseudorandom` on it, memoized in `cache`
+    ///
+    /// Advances the key exactly as the uncached call does, so results and the
+    /// resulting state are identical with or without the cache. Keys pinned
+    /// by the active challenge bypass the cache.
+    pub fn replay_draw(
+        &mut self,
+        cache: &mut ReplayCache,
+        key: &str,
+        min: Option<i32>,
+        max: Option<i32>,
+    ) -> f64 {
+        if self
+            .challenge()
+            .is_some_and(|challenge| challenge.fixed_seed(key).is_some())
+        {
+            return self.pseudorandom_keyed(key, min, max);
+        }
+
+        cache.bind(self);
+        let key_seed = self.state().get_key_seed(key);
+        let draw = (key_seed, min, max);
+        if let Some(value) = cache.draws.get(key).and_then(|draws| draws.get(&draw)) {
+            cache.hits += 1;
+            self.state_mut().set_key_seed(key, key_seed.wrapping_add(1));
+            return *value;
+        }
+
+        cache.misses += 1;
+        let value = self.pseudorandom_keyed(key, min, max);
+        cache
+            .draws
+            .entry(key.to_string())
+            .or_default()
+            .insert(draw, value);
+        value
+    }
+
+    /// `verify_against_dump` with draws memoized in `cache`
+    pub fn verify_against_dump_cached(
+        &mut self,
+        dump: &RngDump,
+        cache: &mut ReplayCache,
+    ) -> VerifyReport {
+        self.verify_with(dump, |rng, call| {
+            rng.replay_draw(cache, &call.key, call.min, call.max)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::dump::RngCall;
+    use crate::utils::rng::SeedType;
+
+    const CALLS: [(&str, Option<i32>, Option<i32>); 5] = [
+        ("rarity1", None, None),
+        ("front1", Some(1), Some(52)),
+        ("rarity1", None, None),
+        ("lucky_mult", Some(1), Some(5)),
+        ("front1", Some(1), Some(52)),
+    ];
+
+    fn replay(rng: &mut BalatroRng, cache: Option<&mut ReplayCache>) -> Vec<f64> {
+        match cache {
+            Some(cache) => CALLS
+                .iter()
+                .map(|&(key, min, max)| rng.replay_draw(cache, key, min, max))
+                .collect(),
+            None => CALLS
+                .iter()
+                .map(|&(key, min, max)| {
+                    let seed = rng.pseudoseed(key);
+                    rng.pseudorandom(SeedType::Numeric(seed), min, max)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_cached_replay_matches_uncached() {
+        let start = BalatroRng::new(SeedType::from("REPLAY"));
+        let mut uncached = BalatroRng::from_state(start.state().clone());
+        let expected = replay(&mut uncached, None);
+
+        let mut cache = ReplayCache::new();
+        for walk in 0..3 {
+            let mut rng = BalatroRng::from_state(start.state().clone());
+            assert_eq!(replay(&mut rng, Some(&mut cache)), expected);
+            assert_eq!(rng.state().key_seeds(), uncached.state().key_seeds());
+            assert_eq!(cache.misses(), CALLS.len() as u64, "walk {walk}");
+        }
+        assert_eq!(cache.hits(), 2 * CALLS.len() as u64);
+        assert_eq!(cache.len(), CALLS.len());
+
+        let dump = RngDump {
+            calls: CALLS
+                .iter()
+                .zip(&expected)
+                .enumerate()
+                .map(|(line, (&(key, min, max), &result))| RngCall {
+                    line: line + 1,
+                    key: key.to_string(),
+                    min,
+                    max,
+                    result,
+                    tolerance: 0.0,
+                })
+                .collect(),
+        };
+        let mut rng = BalatroRng::from_state(start.state().clone());
+        assert!(rng.verify_against_dump_cached(&dump, &mut cache).is_match());
+    }
+
+    #[test]
+    fn test_diverged_state_misses() {
+        let start = BalatroRng::new(SeedType::from("REPLAY"));
+        let mut cache = ReplayCache::new();
+        replay(
+            &mut BalatroRng::from_state(start.state().clone()),
+            Some(&mut cache),
+        );
+
+        // A branch that drew an extra `front1` first diverges on that key
+        let mut branch = BalatroRng::from_state(start.state().clone());
+        let mut reference = branch.state().clone();
+        branch.replay_draw(&mut cache, "front1", Some(1), Some(52));
+        reference.pseudoseed("front1");
+        let mut uncached = BalatroRng::from_state(reference);
+        assert_eq!(
+            replay(&mut branch, Some(&mut cache)),
+            replay(&mut uncached, None)
+        );
+
+        // Another seed must not see this seed's draws, nor its hit rate
+        assert!(cache.hits() > 0);
+        let mut other = BalatroRng::new(SeedType::from("OTHER"));
+        assert_eq!(
+            replay(&mut other, Some(&mut cache)),
+            replay(&mut BalatroRng::new(SeedType::from("OTHER")), None)
+        );
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), CALLS.len() as u64);
+        assert_eq!(cache.len(), CALLS.len());
+    }
+}
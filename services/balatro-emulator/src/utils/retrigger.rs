@@ -0,0 +1,282 @@
+//! Retrigger ordering for scored cards
+//!
+//! When a card scores, the game first applies its own Red Seal and then asks
+//! each joker, left to right, how many times to repeat it. The scheduler
+//! reproduces that order and gives every retrigger its own seed from the
+//! `ScoringContext`, so a schedule is reproducible whatever order cards are
+//! scored in. Those are the context's derived seeds, not draws from the
+//! game's own RNG streams. Rules are plain functions keyed by joker id, so
+//! mods can add or replace them.
+
+use super::scoring::ScoringContext;
+
+/// Where the scored card is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardArea {
+    /// Played and scoring
+    Played,
+    /// Held in hand (Steel, Gold and similar held effects)
+    Held,
+}
+
+impl CardArea {
+    /// Prefix for trigger keys, keeping held cards' seeds apart from played
+    /// cards at the same index; played cards use the bare key
+    fn trigger_prefix(self) -> &'static str {
+        match self {
+            CardArea::Played => "",
+            CardArea::Held => "held:",
+        }
+    }
+}
+
+/// The card being scored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoredCard {
+    /// Position within its area, left to right
+    pub index: usize,
+    /// 2-10, 11 = Jack, 12 = Queen, 13 = King, 14 = Ace
+    pub rank: u8,
+    /// Counts as a face card (Pareidolia makes every card one)
+    pub is_face: bool,
+    pub red_seal: bool,
+    pub area: CardArea,
+}
+
+/// Hand-level state retrigger rules can depend on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandState {
+    /// Last hand of the round (Dusk)
+    pub final_hand: bool,
+}
+
+/// Number of retriggers a joker grants for a card
+pub type RetriggerRule = fn(&ScoredCard, &HandState) -> u32;
+
+/// What caused a retrigger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetriggerSource {
+    RedSeal,
+    Joker { slot: usize, id: String },
+}
+
+/// One retrigger, in application order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retrigger {
+    pub source: RetriggerSource,
+    /// 1-based count of this card's retriggers; trigger 0 is the initial score
+    pub trigger_number: u32,
+    /// Seed for any RNG rolled during this retrigger
+    pub seed: u64,
+}
+
+/// Orders retriggers for a card given the joker lineup
+#[derive(Debug, Clone)]
+pub struct RetriggerScheduler {
+    rules: Vec<(String, RetriggerRule)>,
+}
+
+fn played(card: &ScoredCard) -> bool {
+    card.area == CardArea::Played
+}
+
+fn mime(card: &ScoredCard, _: &HandState) -> u32 {
+    u32::from(card.area == CardArea::Held)
+}
+
+fn hack(card: &ScoredCard, _: &HandState) -> u32 {
+    u32::from(played(card) && (2..=5).contains(&card.rank))
+}
+
+fn sock_and_buskin(card: &ScoredCard, _: &HandState) -> u32 {
+    u32::from(played(card) && card.is_face)
+}
+
+fn hanging_chad(card: &ScoredCard, _: &HandState) -> u32 {
+    if played(card) && card.index == 0 {
+        2
+    } else {
+        0
+    }
+}
+
+fn dusk(card: &ScoredCard, hand: &HandState) -> u32 {
+    u32::from(played(card) && hand.final_hand)
+}
+
+fn seltzer(card: &ScoredCard, _: &HandState) -> u32 {
+    u32::from(played(card))
+}
+
+impl Default for RetriggerScheduler {
+    /// Rules for the vanilla retrigger jokers
+    fn default() -> Self {
+        Self::empty()
+            .with_rule("j_mime", mime)
+            .with_rule("j_hack", hack)
+            .with_rule("j_sock_and_buskin", sock_and_buskin)
+            .with_rule("j_hanging_chad", hanging_chad)
+            .with_rule("j_dusk", dusk)
+            .with_rule("j_selzer", seltzer)
+    }
+}
+
+impl RetriggerScheduler {
+    /// A scheduler with no joker rules (Red Seals still retrigger)
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Add a rule for `joker_id`, replacing any existing one
+    pub fn with_rule(mut self, joker_id: &str, rule: RetriggerRule) -> Self {
+        match self.rules.iter_mut().find(|(id, _)| id == joker_id) {
+            Some(entry) => entry.1 = rule,
+            None => self.rules.push((joker_id.to_string(), rule)),
+        }
+        self
+    }
+
+    fn rule(&self, joker_id: &str) -> Option<RetriggerRule> {
+        self.rules
+            .iter()
+            .find(|(id, _)| id == joker_id)
+            .map(|&(_, rule)| rule)
+    }
+
+    /// Retriggers for `card` in the order the game applies them
+    ///
+    /// `jokers` are joker ids in slot order; jokers without a rule never
+    /// retrigger. The Red Seal comes first, then each joker's repetitions.
+    pub fn schedule(
+        &self,
+        jokers: &[&str],
+        card: &ScoredCard,
+        hand: &HandState,
+        ctx: &ScoringContext<'_>,
+    ) -> Vec<Retrigger> {
+        let red_seal = (
+            u32::from(card.red_seal),
+            RetriggerSource::RedSeal,
+            "red_seal",
+        );
+        let jokers = jokers.iter().enumerate().map(|(slot, &id)| {
+            let count = self.rule(id).map_or(0, |rule| rule(card, hand));
+            let source = RetriggerSource::Joker {
+                slot,
+                id: id.to_string(),
+            };
+            (count, source, id)
+        });
+
+        let mut retriggers = Vec::new();
+        for (count, source, key) in std::iter::once(red_seal).chain(jokers) {
+            for _ in 0..count {
+                let trigger_number = retriggers.len() as u32 + 1;
+                retriggers.push(Retrigger {
+                    source: source.clone(),
+                    trigger_number,
+                    seed: ctx.trigger_seed(
+                        card.index,
+                        &format!("{}{key}", card.area.trigger_prefix()),
+                        trigger_number,
+                    ),
+                });
+            }
+        }
+        retriggers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rng::{BalatroRng, SeedType};
+
+    fn card(rank: u8, area: CardArea) -> ScoredCard {
+        ScoredCard {
+            index: 1,
+            rank,
+            is_face: (11..=13).contains(&rank),
+            red_seal: true,
+            area,
+        }
+    }
+
+    fn schedule(card: &ScoredCard) -> Vec<Retrigger> {
+        let mut rng = BalatroRng::new(SeedType::String("RETRIGGER".to_string()));
+        let ctx = ScoringContext::new(&mut rng, "hand1");
+        let jokers = ["j_hack", "j_mime", "j_joker", "j_hack"];
+        RetriggerScheduler::default().schedule(&jokers, card, &HandState::default(), &ctx)
+    }
+
+    fn sources(retriggers: &[Retrigger]) -> Vec<RetriggerSource> {
+        retriggers.iter().map(|r| r.source.clone()).collect()
+    }
+
+    fn joker(slot: usize, id: &str) -> RetriggerSource {
+        RetriggerSource::Joker {
+            slot,
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_mime_and_hack_retrigger_order() {
+        // A played 3: Red Seal, then both Hacks left to right; Mime ignores played cards
+        let played = schedule(&card(3, CardArea::Played));
+        assert_eq!(
+            sources(&played),
+            vec![
+                RetriggerSource::RedSeal,
+                joker(0, "j_hack"),
+                joker(3, "j_hack")
+            ]
+        );
+        assert_eq!(
+            played.iter().map(|r| r.trigger_number).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // A held 3: Red Seal then Mime; Hack only affects played cards
+        let held = schedule(&card(3, CardArea::Held));
+        assert_eq!(
+            sources(&held),
+            vec![RetriggerSource::RedSeal, joker(1, "j_mime")]
+        );
+
+        // A played King gets only its Red Seal
+        assert_eq!(schedule(&card(13, CardArea::Played)).len(), 1);
+
+        // Same seed, same schedule and seeds; every retrigger seed is distinct
+        assert_eq!(played, schedule(&card(3, CardArea::Played)));
+        assert_ne!(played[1].seed, played[2].seed);
+        assert_ne!(played[0].seed, held[0].seed);
+    }
+
+    #[test]
+    fn test_mod_rules_override_vanilla() {
+        fn every_card_twice(_: &ScoredCard, _: &HandState) -> u32 {
+            2
+        }
+
+        let mut rng = BalatroRng::new(SeedType::String("RETRIGGER".to_string()));
+        let ctx = ScoringContext::new(&mut rng, "hand1");
+        let scheduler = RetriggerScheduler::empty()
+            .with_rule("j_hack", every_card_twice)
+            .with_rule("j_modded", every_card_twice);
+        let mut king = card(13, CardArea::Played);
+        king.red_seal = false;
+
+        let retriggers =
+            scheduler.schedule(&["j_modded", "j_hack"], &king, &HandState::default(), &ctx);
+        assert_eq!(
+            sources(&retriggers),
+            vec![
+                joker(0, "j_modded"),
+                joker(0, "j_modded"),
+                joker(1, "j_hack"),
+                joker(1, "j_hack")
+            ]
+        );
+    }
+}
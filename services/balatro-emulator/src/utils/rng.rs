@@ -13,8 +13,18 @@ use ahash::AHashMap;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+
+use super::blinds::BossBlind;
+use super::cards::{CardModifiers, Edition, Enhancement, ModifierOdds, Seal};
+use super::challenge::{standard_deck, Challenge};
+use super::consumables::{ConsumableId, ConsumableKind, ConsumablePool, ConsumablePools};
+use super::siphash::StableHasher;
+use super::stake::{JokerRarity, JokerStickers, StakeModifiers};
+use super::vouchers::VoucherState;
+use std::f64::consts::PI;
+use std::fmt;
+use std::hash::Hasher;
+use std::str::FromStr;
 
 /// Seed type that can be either a numeric seed or a string seed
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -43,15 +53,172 @@ impl From<&str> for SeedType {
     }
 }
 
+/// Longest seed string the game accepts
+pub const MAX_SEED_LEN: usize = 8;
+
+/// Errors parsing a seed string as typed into the game
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SeedParseError {
+    #[error("seed is empty")]
+    Empty,
+    #[error("seed has {len} characters (at most {MAX_SEED_LEN} allowed)")]
+    TooLong { len: usize },
+    #[error("invalid character {ch:?} at index {index} (only A-Z and 0-9 allowed)")]
+    InvalidChar { ch: char, index: usize },
+}
+
+impl SeedType {
+    /// Trimmed and uppercased (ASCII only); numeric seeds are unchanged
+    pub fn normalize(&self) -> SeedType {
+        match self {
+            SeedType::Numeric(n) => SeedType::Numeric(*n),
+            SeedType::String(s) => SeedType::String(s.trim().to_ascii_uppercase()),
+        }
+    }
+
+    /// Whether this is a seed string the game accepts as is: 1 to 8
+    /// characters of uppercase A-Z and 0-9
+    ///
+    /// Numeric seeds are never game seed strings.
+    pub fn is_valid_balatro_seed(&self) -> bool {
+        match self {
+            SeedType::Numeric(_) => false,
+            SeedType::String(s) => validate_seed_str(s).is_ok(),
+        }
+    }
+}
+
+fn validate_seed_str(s: &str) -> Result<(), SeedParseError> {
+    if s.is_empty() {
+        return Err(SeedParseError::Empty);
+    }
+    if let Some((index, ch)) = s
+        .chars()
+        .enumerate()
+        .find(|(_, ch)| !(ch.is_ascii_uppercase() || ch.is_ascii_digit()))
+    {
+        return Err(SeedParseError::InvalidChar { ch, index });
+    }
+    if s.len() > MAX_SEED_LEN {
+        return Err(SeedParseError::TooLong { len: s.len() });
+    }
+    Ok(())
+}
+
+impl FromStr for SeedType {
+    type Err = SeedParseError;
+
+    /// Parse a seed string as pasted from the game, normalizing it first
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let seed = SeedType::String(s.to_string()).normalize();
+        if let SeedType::String(normalized) = &seed {
+            validate_seed_str(normalized)?;
+        }
+        Ok(seed)
+    }
+}
+
+impl fmt::Display for SeedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedType::Numeric(n) => write!(f, "{n}"),
+            SeedType::String(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Current serialization version of `PseudorandomState`
+///
+/// Version 1 is the original format without a `version` field.
+pub const PSEUDORANDOM_STATE_VERSION: u32 = 2;
+
+/// Errors loading a serialized `PseudorandomState`
+#[derive(Debug, thiserror::Error)]
+pub enum StateLoadError {
+    #[error("unsupported PseudorandomState version {found} (newest supported is {supported})")]
+    UnsupportedVersion { found: u64, supported: u32 },
+    #[error("invalid PseudorandomState JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+fn unversioned_state() -> u32 {
+    1
+}
+
+/// Feed a `u64` to a hasher as little-endian bytes
+///
+/// `u64::hash` writes native-endian bytes, which would give big-endian hosts
+/// different seeds. Strings already hash as their UTF-8 bytes.
+fn write_u64_le(hasher: &mut impl Hasher, value: u64) {
+    hasher.write(&value.to_le_bytes());
+}
+
+/// Hash a seed with an algorithm pinned in this crate
+///
+/// Unlike `DefaultHasher`, the result never changes with the Rust release or
+/// platform. Numeric seeds hash as little-endian bytes, strings as their
+/// UTF-8 bytes plus a `0xff` terminator.
+pub fn stable_hash(seed: &SeedType) -> u64 {
+    match seed {
+        SeedType::Numeric(n) => {
+            let mut hasher = StableHasher::new();
+            write_u64_le(&mut hasher, *n);
+            hasher.finish()
+        }
+        SeedType::String(s) => stable_hash_str(s),
+    }
+}
+
+/// `stable_hash` of a string seed, without building a `SeedType`
+fn stable_hash_str(s: &str) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_str(s);
+    hasher.finish()
+}
+
 /// Pseudorandom state manager that tracks seeds for different game events
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StateRepr")]
 pub struct PseudorandomState {
+    /// Serialization version; blobs without one are version 1
+    version: u32,
     /// Base hashed seed derived from the global seed
     base_seed: u64,
     /// Per-key seed tracking (equivalent to G.GAME.pseudorandom)
     key_seeds: AHashMap<String, u64>,
     /// The original global seed for reference
     global_seed: SeedType,
+    /// Hasher that has already absorbed `base_seed`, cloned per seed
+    #[serde(skip)]
+    base_hasher: StableHasher,
+}
+
+/// Serialized layout of `PseudorandomState`, before the hasher is rebuilt
+#[derive(Deserialize)]
+struct StateRepr {
+    #[serde(default = "unversioned_state")]
+    version: u32,
+    base_seed: u64,
+    key_seeds: AHashMap<String, u64>,
+    global_seed: SeedType,
+}
+
+impl From<StateRepr> for PseudorandomState {
+    fn from(repr: StateRepr) -> Self {
+        Self {
+            version: repr.version,
+            base_seed: repr.base_seed,
+            key_seeds: repr.key_seeds,
+            global_seed: repr.global_seed,
+            base_hasher: base_hasher(repr.base_seed),
+        }
+    }
+}
+
+fn base_hasher(base_seed: u64) -> StableHasher {
+    let mut hasher = StableHasher::new();
+    write_u64_le(&mut hasher, base_seed);
+    hasher
 }
 
 impl PseudorandomState {
@@ -59,40 +226,79 @@ impl PseudorandomState {
     pub fn new(seed: SeedType) -> Self {
         let base_seed = Self::hash_seed(&seed);
         Self {
+            version: PSEUDORANDOM_STATE_VERSION,
             base_seed,
             key_seeds: AHashMap::new(),
             global_seed: seed,
+            base_hasher: base_hasher(base_seed),
+        }
+    }
+
+    /// Load a serialized state, migrating older versions to the current one
+    ///
+    /// Fails with `StateLoadError::UnsupportedVersion` for versions newer than
+    /// this build understands instead of guessing at their layout.
+    pub fn from_json_versioned(json: &str) -> Result<Self, StateLoadError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1);
+
+        if version == 0 || version > u64::from(PSEUDORANDOM_STATE_VERSION) {
+            return Err(StateLoadError::UnsupportedVersion {
+                found: version,
+                supported: PSEUDORANDOM_STATE_VERSION,
+            });
         }
+
+        // v1 -> v2 only added the version tag; the layout is unchanged
+        let mut state: Self = serde_json::from_value(value)?;
+        state.version = PSEUDORANDOM_STATE_VERSION;
+        Ok(state)
+    }
+
+    /// Serialization version of this state
+    pub fn version(&self) -> u32 {
+        self.version
     }
 
     /// Hash a seed to generate a base numeric seed
     fn hash_seed(seed: &SeedType) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        match seed {
-            SeedType::Numeric(n) => n.hash(&mut hasher),
-            SeedType::String(s) => s.hash(&mut hasher),
-        }
-        hasher.finish()
+        stable_hash(seed)
     }
 
     /// Generate a deterministic seed for a given key
     /// This combines the base seed, key, and stored seed value
     pub fn pseudoseed(&mut self, key: &str) -> u64 {
-        // Get current seed value for this key (or 0 if first time)
-        let current_seed = self.key_seeds.get(key).copied().unwrap_or(0);
-
-        // Create combined seed using base seed, key, and current seed
-        let mut hasher = DefaultHasher::new();
-        self.base_seed.hash(&mut hasher);
-        key.hash(&mut hasher);
-        current_seed.hash(&mut hasher);
-        let combined_seed = hasher.finish();
+        // Advance the stored seed for this key, allocating only for new keys
+        let current_seed = match self.key_seeds.get_mut(key) {
+            Some(seed) => {
+                let current = *seed;
+                *seed = current.wrapping_add(1);
+                current
+            }
+            None => {
+                self.key_seeds.insert(key.to_string(), 1);
+                0
+            }
+        };
+        self.combined_seed(key, current_seed)
+    }
 
-        // Advance the stored seed for this key
-        self.key_seeds
-            .insert(key.to_string(), current_seed.wrapping_add(1));
+    /// The seed `pseudoseed(key)` would return after `n` further advances
+    ///
+    /// Pure: `nth_seed(key, 0)` peeks at the next seed without consuming it.
+    pub fn nth_seed(&self, key: &str, n: u64) -> u64 {
+        self.combined_seed(key, self.get_key_seed(key).wrapping_add(n))
+    }
 
-        combined_seed
+    /// Combine the base seed, key and a key's stored seed value
+    fn combined_seed(&self, key: &str, key_seed: u64) -> u64 {
+        let mut hasher = self.base_hasher.clone();
+        hasher.write_str(key);
+        write_u64_le(&mut hasher, key_seed);
+        hasher.finish()
     }
 
     /// Get the current seed value for a key without advancing it
@@ -121,24 +327,250 @@ impl PseudorandomState {
     }
 }
 
+type CacheKey = (u64, Option<i32>, Option<i32>);
+
+/// Slot index standing for "no slot" in the LRU list
+const NO_SLOT: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct CacheSlot {
+    key: CacheKey,
+    value: f64,
+    /// Next more recently used slot
+    newer: usize,
+    /// Next less recently used slot
+    older: usize,
+}
+
+/// Bounded LRU memoization of `pseudorandom` results keyed by (seed, min, max)
+///
+/// Slots form a doubly linked list from most to least recently used, so
+/// hits, inserts and evictions are all O(1). The most recent entry is
+/// checked before hashing, which makes repeating the last query cheapest.
+#[derive(Debug, Clone)]
+struct PseudorandomCache {
+    capacity: usize,
+    index: AHashMap<CacheKey, usize>,
+    slots: Vec<CacheSlot>,
+    newest: usize,
+    oldest: usize,
+}
+
+impl PseudorandomCache {
+    fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            index: AHashMap::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            newest: NO_SLOT,
+            oldest: NO_SLOT,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<f64> {
+        if self.newest != NO_SLOT && self.slots[self.newest].key == *key {
+            return Some(self.slots[self.newest].value);
+        }
+        let slot = *self.index.get(key)?;
+        self.unlink(slot);
+        self.push_newest(slot);
+        Some(self.slots[slot].value)
+    }
+
+    fn insert(&mut self, key: CacheKey, value: f64) {
+        if let Some(&slot) = self.index.get(&key) {
+            self.slots[slot].value = value;
+            self.unlink(slot);
+            self.push_newest(slot);
+            return;
+        }
+
+        let slot = if self.slots.len() < self.capacity {
+            self.slots.push(CacheSlot {
+                key,
+                value,
+                newer: NO_SLOT,
+                older: NO_SLOT,
+            });
+            self.slots.len() - 1
+        } else {
+            // Reuse the least recently used slot
+            let slot = self.oldest;
+            self.unlink(slot);
+            self.index.remove(&self.slots[slot].key);
+            self.slots[slot].key = key;
+            self.slots[slot].value = value;
+            slot
+        };
+        self.index.insert(key, slot);
+        self.push_newest(slot);
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let CacheSlot { newer, older, .. } = self.slots[slot];
+        match newer {
+            NO_SLOT => self.newest = older,
+            newer => self.slots[newer].older = older,
+        }
+        match older {
+            NO_SLOT => self.oldest = newer,
+            older => self.slots[older].newer = newer,
+        }
+    }
+
+    fn push_newest(&mut self, slot: usize) {
+        self.slots[slot].newer = NO_SLOT;
+        self.slots[slot].older = self.newest;
+        match self.newest {
+            NO_SLOT => self.oldest = slot,
+            newest => self.slots[newest].newer = slot,
+        }
+        self.newest = slot;
+    }
+}
+
 /// Main RNG system for Balatro emulation
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BalatroRng {
     /// Pseudorandom state manager
     state: PseudorandomState,
+    /// Optional memoization of repeated identical `pseudorandom` queries
+    cache: Option<PseudorandomCache>,
+    /// Active challenge, which may pin some keys' outputs
+    challenge: Option<Challenge>,
+    /// Generation odds of the stake being played
+    stake: StakeModifiers,
+    /// Vouchers redeemed so far this run
+    vouchers: VoucherState,
 }
 
 impl BalatroRng {
     /// Create a new RNG system with the given seed
     pub fn new(seed: SeedType) -> Self {
-        Self {
-            state: PseudorandomState::new(seed),
-        }
+        Self::from_state(PseudorandomState::new(seed))
     }
 
     /// Create from existing state (for loading saved games)
     pub fn from_state(state: PseudorandomState) -> Self {
-        Self { state }
+        Self {
+            state,
+            cache: None,
+            challenge: None,
+            stake: StakeModifiers::default(),
+            vouchers: VoucherState::default(),
+        }
+    }
+
+    /// Snapshot this RNG to explore a branch from the exact same state
+    ///
+    /// The fork draws exactly what this RNG would draw next, and the two
+    /// advance independently afterwards. Unlike `split`, neither is reseeded
+    /// and this RNG is not advanced, so several forks taken at one decision
+    /// node all replay the same random future.
+    pub fn fork(&self) -> BalatroRng {
+        self.clone()
+    }
+
+    /// Branch into two independent child RNGs, e.g. at a search decision
+    ///
+    /// Each child is seeded from one advance of the parent's `split` key, so
+    /// the same parent state always yields the same pair, while the parent
+    /// itself advances and a repeated split yields a new pair. Children start
+    /// from fresh per-key state and keep the parent's challenge pins, stake
+    /// and vouchers.
+    pub fn split(&mut self) -> (BalatroRng, BalatroRng) {
+        let child = |rng: &mut Self| Self {
+            state: PseudorandomState::new(SeedType::Numeric(rng.pseudoseed("split"))),
+            cache: None,
+            challenge: rng.challenge.clone(),
+            stake: rng.stake,
+            vouchers: rng.vouchers.clone(),
+        };
+        let left = child(self);
+        let right = child(self);
+        (left, right)
+    }
+
+    /// Run under a challenge: installs its pre-seeded keys and pins its
+    /// fixed keys for every later `pseudoseed` call
+    pub fn with_challenge(mut self, challenge: Challenge) -> Self {
+        for (key, seed) in &challenge.seeded_keys {
+            self.state.set_key_seed(key, *seed);
+        }
+        self.challenge = Some(challenge);
+        self
+    }
+
+    /// The active challenge, if any
+    pub fn challenge(&self) -> Option<&Challenge> {
+        self.challenge.as_ref()
+    }
+
+    /// Play at a stake: `Stake::modifiers()` for vanilla, or a mod's own odds
+    pub fn with_stake(mut self, stake: StakeModifiers) -> Self {
+        self.stake = stake;
+        self
+    }
+
+    /// Generation odds of the current stake (White unless set)
+    pub fn stake(&self) -> &StakeModifiers {
+        &self.stake
+    }
+
+    /// Start with a set of vouchers (and their definitions) already in place
+    pub fn with_vouchers(mut self, vouchers: VoucherState) -> Self {
+        self.vouchers = vouchers;
+        self
+    }
+
+    /// Vouchers redeemed so far
+    pub fn vouchers(&self) -> &VoucherState {
+        &self.vouchers
+    }
+
+    /// Mutable vouchers, for redeeming during a run
+    pub fn vouchers_mut(&mut self) -> &mut VoucherState {
+        &mut self.vouchers
+    }
+
+    /// Starting joker keys: the challenge's, or none for a vanilla run
+    pub fn starting_jokers(&self) -> Vec<String> {
+        self.challenge
+            .as_ref()
+            .map(|challenge| challenge.jokers.clone())
+            .unwrap_or_default()
+    }
+
+    /// Starting deck card keys: the challenge's, or the standard 52
+    pub fn starting_deck(&self) -> Vec<String> {
+        self.challenge
+            .as_ref()
+            .map(|challenge| challenge.deck.clone())
+            .unwrap_or_else(standard_deck)
+    }
+
+    /// Enable memoization of `pseudorandom` results, keeping at most
+    /// `capacity` entries with least-recently-used eviction
+    ///
+    /// `pseudorandom` is a pure function of its arguments, so cached results
+    /// are identical to recomputed ones; this only trades memory for speed.
+    pub fn enable_pseudorandom_cache(&mut self, capacity: usize) {
+        self.cache = Some(PseudorandomCache::new(capacity));
+    }
+
+    /// Disable and drop the `pseudorandom` memoization cache
+    pub fn disable_pseudorandom_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Number of memoized `pseudorandom` results (0 when disabled)
+    pub fn pseudorandom_cache_len(&self) -> usize {
+        self.cache.as_ref().map_or(0, PseudorandomCache::len)
     }
 
     /// Get the current state (for saving games)
@@ -152,7 +584,17 @@ impl BalatroRng {
     }
 
     /// Generate a deterministic seed for a given key
+    ///
+    /// Keys pinned by the active challenge return their fixed seed without
+    /// advancing.
     pub fn pseudoseed(&mut self, key: &str) -> u64 {
+        if let Some(seed) = self
+            .challenge
+            .as_ref()
+            .and_then(|challenge| challenge.fixed_seed(key))
+        {
+            return seed;
+        }
         self.state.pseudoseed(key)
     }
 
@@ -166,9 +608,32 @@ impl BalatroRng {
         // Convert seed to numeric value
         let numeric_seed = match seed {
             SeedType::Numeric(n) => n,
-            SeedType::String(s) => self.pseudohash(&s),
+            SeedType::String(s) => stable_hash_str(&s),
         };
 
+        let key = (numeric_seed, min, max);
+        if let Some(value) = self.cache.as_mut().and_then(|cache| cache.get(&key)) {
+            return value;
+        }
+
+        let value = Self::pseudorandom_from_seed(numeric_seed, min, max);
+        if let Some(cache) = self.cache.as_mut() {
+            cache.insert(key, value);
+        }
+        value
+    }
+
+    /// Draw from `key` like the game's `pseudorandom(key, min, max)`
+    ///
+    /// Each call advances the key (as `G.GAME.pseudorandom[key]` does), so
+    /// consecutive draws on the same key differ while the whole sequence is
+    /// reproducible from the saved state. Bounds behave as in `pseudorandom`.
+    pub fn pseudorandom_keyed(&mut self, key: &str, min: Option<i32>, max: Option<i32>) -> f64 {
+        let seed = self.pseudoseed(key);
+        self.pseudorandom(SeedType::Numeric(seed), min, max)
+    }
+
+    fn pseudorandom_from_seed(numeric_seed: u64, min: Option<i32>, max: Option<i32>) -> f64 {
         // Create RNG from the seed
         let mut rng = ChaCha8Rng::seed_from_u64(numeric_seed);
 
@@ -203,8 +668,29 @@ impl BalatroRng {
         }
 
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
-        let index = rng.gen_range(0..collection.len());
-        collection.get(index)
+        let index = rng.gen_range(0..collection.len() as u64);
+        collection.get(index as usize)
+    }
+
+    /// Draw `count` distinct elements in one deterministic call
+    ///
+    /// A partial Fisher-Yates over the indices: position `i` swaps with a
+    /// random position in `i..len`, so no element is drawn twice. Returns the
+    /// whole collection, shuffled, when `count` exceeds its length.
+    pub fn sample_without_replacement<'a, T>(
+        &mut self,
+        collection: &'a [T],
+        count: usize,
+        seed: u64,
+    ) -> Vec<&'a T> {
+        let count = count.min(collection.len());
+        let mut indices: Vec<usize> = (0..collection.len()).collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        for i in 0..count {
+            let j = rng.gen_range(i as u64..collection.len() as u64);
+            indices.swap(i, j as usize);
+        }
+        indices[..count].iter().map(|&i| &collection[i]).collect()
     }
 
     /// Deterministic shuffle using Fisher-Yates algorithm
@@ -215,19 +701,115 @@ impl BalatroRng {
 
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
-        // Fisher-Yates shuffle
+        // Fisher-Yates shuffle; indices are drawn as u64 so 32-bit hosts
+        // consume the stream exactly like 64-bit ones
         for i in (1..list.len()).rev() {
-            let j = rng.gen_range(0..=i);
-            list.swap(i, j);
+            let j = rng.gen_range(0..=i as u64);
+            list.swap(i, j as usize);
         }
     }
 
-    /// Hash function for string-to-float conversion
-    /// This replicates Balatro's string hashing behavior
-    pub fn pseudohash(&self, s: &str) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        s.hash(&mut hasher);
-        hasher.finish()
+    /// Move `fixed_front` to the front in order, then shuffle the rest
+    ///
+    /// Fixed elements missing from `list` (or listed more times than they
+    /// occur) are skipped, so the front holds only those actually present.
+    /// Returns how many fixed elements were placed.
+    pub fn pseudoshuffle_fix<T: PartialEq>(
+        &mut self,
+        list: &mut Vec<T>,
+        fixed_front: &[T],
+        seed: u64,
+    ) -> usize {
+        let mut placed = 0;
+        for fixed in fixed_front {
+            if let Some(offset) = list[placed..].iter().position(|item| item == fixed) {
+                let item = list.remove(placed + offset);
+                list.insert(placed, item);
+                placed += 1;
+            }
+        }
+        self.pseudoshuffle(&mut list[placed..], seed);
+        placed
+    }
+
+    /// Deterministic shuffle seeded from a pseudoseed key
+    ///
+    /// Advances `key`, so repeated shuffles with the same key differ while a
+    /// fresh RNG with the same global seed reproduces the sequence.
+    pub fn pseudoshuffle_key<T>(&mut self, list: &mut [T], key: &str) {
+        let seed = self.pseudoseed(key);
+        self.pseudoshuffle(list, seed);
+    }
+
+    /// The hand drawn at the start of the first round of `ante`
+    ///
+    /// Shuffles a copy of `deck` with the game's round shuffle key (`nr` +
+    /// ante) and draws `hand_size` cards from the top, i.e. the end of the
+    /// list, in draw order. Cards are keys as returned by `starting_deck`.
+    pub fn opening_hand(&mut self, deck: &[String], ante: u8, hand_size: usize) -> Vec<String> {
+        let mut shuffled = deck.to_vec();
+        let seed = self.get_card_rng("nr", ante, None);
+        self.pseudoshuffle(&mut shuffled, seed);
+        shuffled.into_iter().rev().take(hand_size).collect()
+    }
+
+    /// The permutation of `0..n` that `pseudoshuffle` produces for `seed`
+    pub fn pseudoshuffle_indices(&mut self, n: usize, seed: u64) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..n).collect();
+        self.pseudoshuffle(&mut indices, seed);
+        indices
+    }
+
+    /// Lehmer-code rank of the permutation `pseudoshuffle_indices(n, seed)`
+    ///
+    /// Lets run recordings store a shuffle as a single integer; see
+    /// [`permutation_to_rank`] for the supported length.
+    pub fn pseudoshuffle_rank(&mut self, n: usize, seed: u64) -> u128 {
+        permutation_to_rank(&self.pseudoshuffle_indices(n, seed))
+    }
+
+    /// Balatro's `pseudohash`: a string to a float in [0, 1), or exactly 1.0
+    /// for the empty string
+    ///
+    /// Ports the game's loop exactly, walking the bytes from last to first
+    /// with 1-based indices:
+    /// `num = ((1.1239285023 / num) * byte * math.pi + math.pi * i) % 1`,
+    /// starting from `num = 1`, so the empty string hashes to 1.0. The
+    /// operations run in Lua's order so results match the game bit for bit.
+    pub fn pseudohash(&self, s: &str) -> f64 {
+        s.bytes()
+            .enumerate()
+            .rev()
+            .fold(1.0, |num: f64, (i, byte)| {
+                ((1.123_928_502_3 / num) * f64::from(byte) * PI + PI * (i + 1) as f64) % 1.0
+            })
+    }
+
+    /// `pseudohash` as an integer seed: the bit pattern of the float, so no
+    /// precision is lost
+    ///
+    /// String seeds passed to `pseudorandom` and the base seed of a string
+    /// `SeedType` use [`stable_hash`] instead, which predates this port and
+    /// must stay stable for saved runs.
+    pub fn pseudohash_u64(&self, s: &str) -> u64 {
+        self.pseudohash(s).to_bits()
+    }
+
+    /// The value Balatro's `pseudoseed(key)` hands to `math.randomseed` on
+    /// the `draw`th draw (1-based) from `key` in a run seeded `seed`
+    ///
+    /// Ports the game's float path: a key's stored value starts as
+    /// `pseudohash(key .. seed)`, and each draw advances it with
+    /// [`advance_game_seed`], including the `%.13f` rounding, before it is
+    /// averaged with `pseudohash(seed)`. This is for comparing against real
+    /// runs; the crate's own keyed streams (`pseudoseed`,
+    /// `pseudorandom_keyed`) stay on integer stable hashing so existing saved
+    /// runs keep reproducing.
+    pub fn game_pseudoseed(&self, key: &str, seed: &str, draw: u32) -> f64 {
+        let stored = (0..draw).fold(self.pseudohash(&format!("{key}{seed}")), |v, _| {
+            advance_game_seed(v)
+        });
+        (stored + self.pseudohash(seed)) / 2.0
     }
 
     /// Generate a starting seed string (for new games)
@@ -272,6 +854,417 @@ impl BalatroRng {
         let key = format!("joker_{joker_id}_{trigger_count}");
         self.pseudoseed(&key)
     }
+
+    /// Select the boss blind for an ante, avoiding recently seen bosses
+    ///
+    /// Narrows `pool` to the bosses eligible at `ante` (finishers only on
+    /// finisher antes, regular bosses once their `min_ante` is reached), then
+    /// draws uniformly from those not in `recent` using the `boss` + ante key.
+    /// If every eligible boss has been seen recently they are all used again,
+    /// as the game does once it runs out of fresh bosses; a pool with no
+    /// eligible boss at all is used as is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pool` is empty.
+    pub fn select_boss_blind(
+        &mut self,
+        ante: u8,
+        pool: &[BossBlind],
+        recent: &[BossBlind],
+    ) -> BossBlind {
+        assert!(!pool.is_empty(), "boss blind pool must not be empty");
+
+        let eligible: Vec<BossBlind> = pool
+            .iter()
+            .copied()
+            .filter(|boss| boss.is_eligible(ante))
+            .collect();
+        let eligible = if eligible.is_empty() { pool } else { &eligible };
+        let fresh: Vec<BossBlind> = eligible
+            .iter()
+            .copied()
+            .filter(|boss| !recent.contains(boss))
+            .collect();
+        let candidates = if fresh.is_empty() { eligible } else { &fresh };
+
+        let seed = self.get_card_rng("boss", ante, None);
+        *self
+            .pseudorandom_element(candidates, seed)
+            .expect("candidates are non-empty")
+    }
+
+    /// Roll enhancement, edition and seal for a generated playing card
+    ///
+    /// Each aspect draws from its own key (`stdset`, `Enhanced`,
+    /// `standard_edition`, `stdseal`, `stdsealtype`) + ante + `card_key`, so
+    /// aspects are independent of each other and of the order cards are rolled.
+    /// As in the game, `Enhanced` is only drawn for cards that are enhanced,
+    /// and picks the same enhancement `roll_enhancement` would.
+    /// Edition odds are scaled by any owned edition voucher (Hone, Glow Up).
+    pub fn roll_card_modifiers(
+        &mut self,
+        ante: u8,
+        card_key: &str,
+        odds: &ModifierOdds,
+    ) -> CardModifiers {
+        let odds = &self.vouchers.edition_odds(odds);
+        let enhancement_roll = self.get_card_rng("stdset", ante, Some(card_key));
+        let enhancement = self
+            .probability_check(odds.enhancement_chance, enhancement_roll)
+            .then(|| self.roll_enhancement(ante, card_key));
+
+        let edition_seed = self.get_card_rng("standard_edition", ante, Some(card_key));
+        let edition_roll = self.pseudorandom(SeedType::Numeric(edition_seed), None, None);
+        let edition = odds.edition_for_roll(edition_roll);
+
+        let seal_roll = self.get_card_rng("stdseal", ante, Some(card_key));
+        let seal_type = self.get_card_rng("stdsealtype", ante, Some(card_key));
+        let seal = if self.probability_check(odds.seal_chance, seal_roll) {
+            let roll = self.pseudorandom(SeedType::Numeric(seal_type), None, None);
+            Some(Seal::for_roll(roll))
+        } else {
+            None
+        };
+
+        CardModifiers {
+            enhancement,
+            edition,
+            seal,
+        }
+    }
+
+    /// Roll the enhancement of a card created from the `Enhanced` pool
+    ///
+    /// Draws uniformly, as the game's pool does, on `Enhanced` + ante +
+    /// `seed_append`. Whether a card is enhanced at all is a separate roll
+    /// (40% in Standard packs, see `roll_card_modifiers`).
+    pub fn roll_enhancement(&mut self, ante: u8, seed_append: &str) -> Enhancement {
+        let seed = self.get_card_rng("Enhanced", ante, Some(seed_append));
+        *self
+            .pseudorandom_element(&Enhancement::ALL, seed)
+            .expect("Enhancement::ALL is non-empty")
+    }
+
+    /// Roll a card's edition on `edi` + ante + `seed_append`
+    ///
+    /// Uses the game's base playing card odds (2% Foil, 1.4% Holographic,
+    /// 0.6% Polychrome, never Negative) scaled by owned edition vouchers; most
+    /// rolls give no edition.
+    pub fn roll_edition(&mut self, ante: u8, seed_append: &str) -> Option<Edition> {
+        let odds = self
+            .vouchers
+            .edition_odds(&ModifierOdds::default().with_base_editions());
+        let seed = self.get_card_rng("edi", ante, Some(seed_append));
+        odds.edition_for_roll(self.pseudorandom(SeedType::Numeric(seed), None, None))
+    }
+
+    /// Roll the type of a seal being applied on `stdsealtype` + ante +
+    /// `seed_append`, with the Standard pack's even split
+    pub fn roll_seal(&mut self, ante: u8, seed_append: &str) -> Seal {
+        let seed = self.get_card_rng("stdsealtype", ante, Some(seed_append));
+        Seal::for_roll(self.pseudorandom(SeedType::Numeric(seed), None, None))
+    }
+
+    /// Roll a joker's rarity on the `rarity` + ante + append key
+    ///
+    /// Thresholds come from the stake's rarity weights, which vanilla stakes
+    /// leave at 70/25/5.
+    pub fn roll_rarity(&mut self, ante: u8, append: Option<&str>) -> JokerRarity {
+        let seed = self.get_card_rng("rarity", ante, append);
+        let roll = self.pseudorandom(SeedType::Numeric(seed), None, None);
+        self.stake.rarity_for_roll(roll)
+    }
+
+    /// Roll Eternal, Perishable and Rental stickers for a generated joker
+    ///
+    /// Uses the game's keys: `etperpoll` and `ssjr` + ante in the shop,
+    /// `packetper` and `packssjr` + ante in booster packs. Both polls are
+    /// consumed at every stake so the streams stay aligned with the game.
+    pub fn roll_joker_stickers(&mut self, ante: u8, in_pack: bool) -> JokerStickers {
+        let (poll_key, rental_key) = if in_pack {
+            ("packetper", "packssjr")
+        } else {
+            ("etperpoll", "ssjr")
+        };
+        let poll_seed = self.get_card_rng(poll_key, ante, None);
+        let poll = self.pseudorandom(SeedType::Numeric(poll_seed), None, None);
+        let rental_seed = self.get_card_rng(rental_key, ante, None);
+        let rental_poll = self.pseudorandom(SeedType::Numeric(rental_seed), None, None);
+        self.stake.stickers_for_polls(poll, rental_poll)
+    }
+
+    /// Generate a consumable of `kind` for a given ante and source
+    ///
+    /// Uses the game's key pattern of set name + source append + ante (e.g.
+    /// `Tarotar11` for an Arcana pack in ante 1), so each source advances its
+    /// own stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool for `kind` is empty.
+    pub fn generate_consumable(
+        &mut self,
+        kind: ConsumableKind,
+        ante: u8,
+        source: &str,
+        pools: &ConsumablePools,
+    ) -> ConsumableId {
+        let pool = pools.pool(kind);
+        assert!(!pool.is_empty(), "{} pool must not be empty", kind.key());
+
+        let key = format!("{}{source}{ante}", kind.key());
+        let seed = self.pseudoseed(&key);
+        self.pseudorandom_element(pool, seed)
+            .expect("pool is non-empty")
+            .clone()
+    }
+
+    /// Fill a booster pack of `kind` with `size` consumables
+    ///
+    /// Cards use the pack sources `ar1` (Arcana), `pl1` (Celestial) and `spe`
+    /// (Spectral). With Telescope the first Celestial card is
+    /// `most_played_planet` without advancing the Planet key; with Omen Globe
+    /// each Arcana card first polls `omen_globe` and may become a Spectral
+    /// drawn from `ar2`.
+    pub fn generate_pack(
+        &mut self,
+        kind: ConsumableKind,
+        ante: u8,
+        size: usize,
+        pools: &ConsumablePools,
+        most_played_planet: Option<&ConsumableId>,
+    ) -> Vec<ConsumableId> {
+        let source = match kind {
+            ConsumableKind::Tarot => "ar1",
+            ConsumableKind::Planet => "pl1",
+            ConsumableKind::Spectral => "spe",
+        };
+        let telescope =
+            kind == ConsumableKind::Planet && self.vouchers.most_played_planet_in_celestial();
+        let omen_globe = match kind {
+            ConsumableKind::Tarot => self.vouchers.spectral_in_arcana(),
+            _ => None,
+        };
+
+        let mut pack = Vec::with_capacity(size);
+        for i in 0..size {
+            if let (true, 0, Some(planet)) = (telescope, i, most_played_planet) {
+                pack.push(planet.clone());
+                continue;
+            }
+            if let Some(chance) = omen_globe {
+                let seed = self.pseudoseed("omen_globe");
+                if self.pseudorandom(SeedType::Numeric(seed), None, None) > 1.0 - chance {
+                    pack.push(self.generate_consumable(
+                        ConsumableKind::Spectral,
+                        ante,
+                        "ar2",
+                        pools,
+                    ));
+                    continue;
+                }
+            }
+            pack.push(self.generate_consumable(kind, ante, source, pools));
+        }
+        pack
+    }
+
+    /// Draw from `pool` without repeating until it is exhausted
+    ///
+    /// Picks among the items not yet drawn in the current refresh window using
+    /// the `key` stream, refreshing the pool first when nothing is left.
+    /// Panics if the pool is empty.
+    pub fn draw_from_pool(&mut self, pool: &mut ConsumablePool, key: &str) -> ConsumableId {
+        assert!(!pool.is_empty(), "consumable pool must not be empty");
+
+        let mut remaining = pool.available().len();
+        if remaining == 0 {
+            pool.refresh();
+            remaining = pool.len();
+        }
+
+        let seed = self.pseudoseed(key);
+        let n = self.pseudorandom(SeedType::Numeric(seed), Some(0), Some(remaining as i32 - 1));
+        pool.take_available(n as usize)
+    }
+
+    /// Generate a seed from several key components (e.g. ante + blind + round)
+    ///
+    /// Each part is length-prefixed before joining so that `["a", "bc"]` and
+    /// `["ab", "c"]` map to different keys, unlike naive concatenation.
+    /// Advances the single combined key.
+    pub fn composite_seed(&mut self, parts: &[&str]) -> u64 {
+        let mut key = String::new();
+        for part in parts {
+            key.push_str(&part.len().to_string());
+            key.push(':');
+            key.push_str(part);
+        }
+        self.pseudoseed(&key)
+    }
+}
+
+/// Longest permutation whose rank fits in a `u128` (34! < 2^128 < 35!)
+pub const MAX_RANKED_PERMUTATION_LEN: usize = 34;
+
+/// One advance of a key's stored value in Balatro's `pseudoseed`
+///
+/// `math.abs(tonumber(string.format("%.13f", (2.134453429141 + v * 1.72431234) % 1)))`:
+/// the step is rounded to 13 decimal places through a string, as the game
+/// does. Rust's `{:.13}` and `parse` round exactly like C's `printf` and
+/// `strtod`, so the result matches bit for bit.
+pub fn advance_game_seed(value: f64) -> f64 {
+    let stepped = (2.134_453_429_141 + value * 1.724_312_34) % 1.0;
+    format!("{stepped:.13}")
+        .parse::<f64>()
+        .expect("a formatted float parses")
+        .abs()
+}
+
+fn factorial(n: usize) -> u128 {
+    (1..=n as u128).product()
+}
+
+/// Compute the Lehmer-code rank of a permutation of `0..perm.len()`
+///
+/// Panics if the permutation is longer than [`MAX_RANKED_PERMUTATION_LEN`]
+/// or is not a permutation of `0..len`.
+pub fn permutation_to_rank(perm: &[usize]) -> u128 {
+    let n = perm.len();
+    assert!(
+        n <= MAX_RANKED_PERMUTATION_LEN,
+        "permutation of length {n} does not fit in a u128 rank"
+    );
+
+    let mut used = vec![false; n];
+    let mut rank = 0u128;
+    for (i, &value) in perm.iter().enumerate() {
+        assert!(value < n && !used[value], "not a permutation of 0..{n}");
+        let smaller_unused = used[..value].iter().filter(|u| !**u).count() as u128;
+        rank += smaller_unused * factorial(n - 1 - i);
+        used[value] = true;
+    }
+    rank
+}
+
+/// Rebuild the permutation of `0..n` with the given Lehmer-code rank
+///
+/// Panics if `n` exceeds [`MAX_RANKED_PERMUTATION_LEN`] or `rank >= n!`.
+pub fn rank_to_permutation(rank: u128, n: usize) -> Vec<usize> {
+    assert!(
+        n <= MAX_RANKED_PERMUTATION_LEN,
+        "permutation of length {n} does not fit in a u128 rank"
+    );
+    assert!(rank < factorial(n), "rank {rank} out of range for n = {n}");
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut rank = rank;
+    let mut perm = Vec::with_capacity(n);
+    for i in 0..n {
+        let place = factorial(n - 1 - i);
+        let digit = (rank / place) as usize;
+        rank %= place;
+        perm.push(remaining.remove(digit));
+    }
+    perm
+}
+
+/// Cumulative-sum draw over float weights, clamping invalid ones to zero
+fn weighted_index(weights: impl Iterator<Item = f64> + Clone, seed: u64) -> Option<usize> {
+    let weights = weights.map(|weight| weight.max(0.0));
+    let total_weight: f64 = weights.clone().sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut target = rng.gen::<f64>() * total_weight;
+
+    let mut last_positive = None;
+    for (index, weight) in weights.enumerate() {
+        target -= weight;
+        if weight > 0.0 {
+            if target <= 0.0 {
+                return Some(index);
+            }
+            last_positive = Some(index);
+        }
+    }
+
+    // Rounding can leave a sliver of the target; it belongs to the last bucket
+    last_positive
+}
+
+/// Map a draw in `0..total` onto the choice whose cumulative weight covers it
+fn pick_cumulative<T>(choices: &[(T, u32)], target: u64) -> Option<&T> {
+    let mut cumulative = 0u64;
+    choices.iter().find_map(|(choice, weight)| {
+        cumulative += u64::from(*weight);
+        (target < cumulative).then_some(choice)
+    })
+}
+
+/// Precomputed table for O(1) weighted draws using Walker's alias method
+///
+/// Draws follow the same distribution as `BalatroRng::weighted_choice` with
+/// the same weights, though individual seeds map to different indices.
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Build a table from non-negative weights
+    ///
+    /// Returns `None` if there are no weights or they sum to zero or less,
+    /// matching the cases where `weighted_choice` returns `None`.
+    pub fn from_weights(weights: &[f64]) -> Option<Self> {
+        let n = weights.len();
+        let total: f64 = weights.iter().map(|w| w.max(0.0)).sum();
+        if n == 0 || total <= 0.0 {
+            return None;
+        }
+
+        // Scale so the average column height is exactly 1
+        let mut scaled: Vec<f64> = weights
+            .iter()
+            .map(|w| w.max(0.0) * n as f64 / total)
+            .collect();
+        let mut prob = vec![1.0; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| scaled[i] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Leftovers are full columns up to floating point error
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+
+        Some(Self { prob, alias })
+    }
+
+    /// Number of entries in the table
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    /// Always false; empty weight lists produce no table
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
 }
 
 /// Utility functions for common RNG operations
@@ -288,29 +1281,59 @@ impl BalatroRng {
         rng.gen::<f64>() < probability
     }
 
+    /// Roll "`numerator` in `denominator`" odds like the game
+    ///
+    /// Mirrors `pseudorandom(key) < numerator / denominator` with a single
+    /// division, so boosted numerators (Oops! All 6s doubles
+    /// `G.GAME.probabilities.normal`) compose without pre-divided rounding.
+    /// Odds of at least one always hit and a zero numerator never does.
+    pub fn probability_check_odds(&mut self, numerator: f64, denominator: f64, seed: u64) -> bool {
+        self.probability_check(numerator / denominator, seed)
+    }
+
     /// Generate a weighted random choice
+    ///
+    /// Weights behave as in `weighted_choice_index`.
     pub fn weighted_choice<'a, T>(&mut self, choices: &'a [(T, f64)], seed: u64) -> Option<&'a T> {
-        if choices.is_empty() {
-            return None;
-        }
+        weighted_index(choices.iter().map(|(_, weight)| *weight), seed).map(|i| &choices[i].0)
+    }
+
+    /// Pick an index with probability proportional to its weight
+    ///
+    /// Negative and NaN weights are invalid and clamped to zero, so they are
+    /// never picked. Returns `None` when no weight is positive.
+    pub fn weighted_choice_index(&mut self, weights: &[f64], seed: u64) -> Option<usize> {
+        weighted_index(weights.iter().copied(), seed)
+    }
 
-        let total_weight: f64 = choices.iter().map(|(_, weight)| weight).sum();
-        if total_weight <= 0.0 {
+    /// Generate a weighted random choice from integer weights
+    ///
+    /// Selection stays in integer arithmetic, so each choice wins exactly
+    /// `weight / total` of the draw range with no float rounding at the edges.
+    pub fn weighted_choice_int<'a, T>(
+        &mut self,
+        choices: &'a [(T, u32)],
+        seed: u64,
+    ) -> Option<&'a T> {
+        let total: u64 = choices.iter().map(|(_, weight)| u64::from(*weight)).sum();
+        if total == 0 {
             return None;
         }
 
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
-        let mut target = rng.gen::<f64>() * total_weight;
+        let target = rng.gen_range(0..total);
+        pick_cumulative(choices, target)
+    }
 
-        for (choice, weight) in choices {
-            target -= weight;
-            if target <= 0.0 {
-                return Some(choice);
-            }
+    /// Draw an index from an alias table in O(1)
+    pub fn alias_choice(&mut self, table: &AliasTable, seed: u64) -> usize {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let column = rng.gen_range(0..table.len() as u64) as usize;
+        if rng.gen::<f64>() < table.prob[column] {
+            column
+        } else {
+            table.alias[column]
         }
-
-        // Fallback to last choice if we somehow get here
-        choices.last().map(|(choice, _)| choice)
     }
 }
 
@@ -327,6 +1350,85 @@ mod tests {
         assert_eq!(state.global_seed(), &SeedType::Numeric(12345));
     }
 
+    #[test]
+    fn test_tutorial_base_seed_is_stable() {
+        // Drift here means saved runs no longer reproduce on this toolchain
+        let state = PseudorandomState::new(SeedType::String("TUTORIAL".into()));
+        assert_eq!(state.base_seed(), 2765539982484055707);
+        assert_eq!(stable_hash(&SeedType::Numeric(0)), 13646096770106105413);
+    }
+
+    #[test]
+    fn test_pseudohash_matches_game() {
+        // Not captured from a running game: computed from the game's Lua
+        // source by a separate reference script (Python doubles, with Lua's
+        // `a % b == a - floor(a / b) * b`), independent of this port
+        let rng = BalatroRng::new(SeedType::Numeric(0));
+        let known: [(&str, f64); 12] = [
+            ("TUTORIAL", 0.41795211369071694),
+            ("7LB2WVPK", 0.17691054639954018),
+            ("1ABCDEFG", 0.8145409627191498),
+            ("HIDDEN1", 0.7206543073860985),
+            ("ZZZZZZZZ", 0.5527514007890204),
+            ("A", 0.6517518426706488),
+            ("AAAAAAAA", 0.43257138351543745),
+            ("12345678", 0.5755036153639139),
+            ("K9X2M4QP", 0.2761380184688278),
+            ("BALATRO", 0.4458558592471036),
+            ("99999999", 0.37134043350215507),
+            ("Q", 0.14656025844436726),
+        ];
+        for (s, expected) in known {
+            assert_eq!(rng.pseudohash(s).to_bits(), expected.to_bits(), "{s:?}");
+            assert_eq!(rng.pseudohash_u64(s), expected.to_bits());
+        }
+        assert_eq!(rng.pseudohash(""), 1.0);
+    }
+
+    #[test]
+    fn test_game_pseudoseed_rounds_like_the_game() {
+        // From the same reference script as the pseudohash values
+        let rng = BalatroRng::new(SeedType::Numeric(0));
+        let known: [(&str, &str, u32, f64); 4] = [
+            ("boss", "TUTORIAL", 1, 0.42670187883250843),
+            ("boss", "TUTORIAL", 2, 0.6516300930049584),
+            ("lucky_mult", "7LB2WVPK", 1, 0.2528207024228701),
+            ("lucky_mult", "7LB2WVPK", 3, 0.2603018543562201),
+        ];
+        for (key, seed, draw, expected) in known {
+            let actual = rng.game_pseudoseed(key, seed, draw);
+            assert_eq!(actual.to_bits(), expected.to_bits(), "{key} {seed} {draw}");
+        }
+
+        // Every advanced value has at most 13 decimal places
+        let stepped = advance_game_seed(rng.pseudohash("TUTORIAL"));
+        assert_eq!(format!("{stepped:.13}").parse::<f64>().unwrap(), stepped);
+    }
+
+    #[test]
+    fn test_cached_base_hasher_matches_full_hash() {
+        // The seeds before the base hasher was cached, for a fixed seed
+        let mut state = PseudorandomState::new(SeedType::from("CACHED"));
+        let reference = |state: &PseudorandomState, key: &str, counter: u64| {
+            let mut hasher = StableHasher::new();
+            write_u64_le(&mut hasher, state.base_seed());
+            hasher.write_str(key);
+            write_u64_le(&mut hasher, counter);
+            hasher.finish()
+        };
+        for i in 0..1000u64 {
+            let key = format!("key{}", i % 7);
+            let expected = reference(&state, &key, i / 7);
+            assert_eq!(state.pseudoseed(&key), expected, "call {i}");
+        }
+
+        // Loaded states rebuild the cached hasher from their base seed
+        let json = serde_json::to_string(&state).unwrap();
+        let mut loaded: PseudorandomState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.pseudoseed("key0"), state.pseudoseed("key0"));
+        assert!(!json.contains("base_hasher"));
+    }
+
     #[test]
     fn test_pseudoseed_generation() {
         let mut state = PseudorandomState::new(SeedType::Numeric(12345));
@@ -356,6 +1458,125 @@ mod tests {
         assert_eq!(val1, val2);
     }
 
+    #[test]
+    fn test_pseudorandom_keyed_advances_key() {
+        let mut rng = BalatroRng::new(SeedType::from("KEYED"));
+        let first: Vec<f64> = (0..20)
+            .map(|_| rng.pseudorandom_keyed("x", Some(1), Some(6)))
+            .collect();
+        assert_eq!(rng.state().get_key_seed("x"), 20);
+        assert!(first.iter().any(|roll| *roll != first[0]));
+        assert!(first.iter().all(|roll| (1.0..=6.0).contains(roll)));
+
+        // Same as advancing the key by hand
+        let mut manual = BalatroRng::new(SeedType::from("KEYED"));
+        for roll in &first {
+            let seed = manual.pseudoseed("x");
+            assert_eq!(
+                manual.pseudorandom(SeedType::Numeric(seed), Some(1), Some(6)),
+                *roll
+            );
+        }
+
+        // Reproducible across a save/load in the middle of the sequence
+        let mut rng = BalatroRng::new(SeedType::from("KEYED"));
+        for roll in &first[..7] {
+            assert_eq!(rng.pseudorandom_keyed("x", Some(1), Some(6)), *roll);
+        }
+        let saved = serde_json::to_string(rng.state()).unwrap();
+        let mut loaded =
+            BalatroRng::from_state(PseudorandomState::from_json_versioned(&saved).unwrap());
+        for roll in &first[7..] {
+            assert_eq!(loaded.pseudorandom_keyed("x", Some(1), Some(6)), *roll);
+        }
+    }
+
+    #[test]
+    fn test_fork_reproduces_and_diverges() {
+        let mut rng = BalatroRng::new(SeedType::from("FORK"));
+        for key in ["rarity1", "front1", "rarity1"] {
+            rng.pseudoseed(key);
+        }
+
+        let mut left = rng.fork();
+        let mut right = rng.fork();
+        let mut replay = rng.fork();
+        left.pseudorandom_keyed("x", None, None);
+        right.pseudorandom_keyed("y", None, None);
+        replay.pseudorandom_keyed("y", None, None);
+
+        let right_draws: Vec<f64> = (0..5)
+            .map(|_| right.pseudorandom_keyed("x", None, None))
+            .collect();
+        let left_draws: Vec<f64> = (0..5)
+            .map(|_| left.pseudorandom_keyed("x", None, None))
+            .collect();
+        let replay_draws: Vec<f64> = (0..5)
+            .map(|_| replay.pseudorandom_keyed("x", None, None))
+            .collect();
+
+        assert_ne!(left_draws, right_draws);
+        assert_eq!(left_draws[..4], right_draws[1..]);
+        assert_eq!(replay_draws, right_draws);
+        // Forking leaves the original untouched
+        assert_eq!(rng.state().get_key_seed("x"), 0);
+        assert_eq!(rng.state().get_key_seed("rarity1"), 2);
+    }
+
+    #[test]
+    fn test_sample_without_replacement() {
+        let mut rng = BalatroRng::new(SeedType::from("SAMPLE"));
+        let deck: Vec<u32> = (0..52).collect();
+
+        let hand = rng.sample_without_replacement(&deck, 8, 42);
+        assert_eq!(hand.len(), 8);
+        let mut distinct = hand.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 8);
+        assert_eq!(rng.sample_without_replacement(&deck, 8, 42), hand);
+        assert_ne!(rng.sample_without_replacement(&deck, 8, 43), hand);
+
+        assert!(rng.sample_without_replacement(&deck, 0, 42).is_empty());
+        let small = [1, 2, 3];
+        let mut all = rng.sample_without_replacement(&small, 10, 42);
+        all.sort();
+        assert_eq!(all, [&1, &2, &3]);
+        assert!(rng.sample_without_replacement::<u32>(&[], 3, 42).is_empty());
+    }
+
+    #[test]
+    fn test_seed_strings_parse_like_the_game() {
+        assert_eq!("7LB2WVPK".parse(), Ok(SeedType::from("7LB2WVPK")));
+        assert_eq!(" 7lb2wvpk\n".parse(), Ok(SeedType::from("7LB2WVPK")));
+        assert_eq!("".parse::<SeedType>(), Err(SeedParseError::Empty));
+        assert_eq!("   ".parse::<SeedType>(), Err(SeedParseError::Empty));
+        assert_eq!(
+            "ABCDEFGHI".parse::<SeedType>(),
+            Err(SeedParseError::TooLong { len: 9 })
+        );
+        assert_eq!(
+            "AB CD".parse::<SeedType>(),
+            Err(SeedParseError::InvalidChar { ch: ' ', index: 2 })
+        );
+        assert_eq!(
+            "straße".parse::<SeedType>(),
+            Err(SeedParseError::InvalidChar { ch: 'ß', index: 4 })
+        );
+
+        assert!(SeedType::from("TUTORIAL").is_valid_balatro_seed());
+        assert!(!SeedType::from("tutorial").is_valid_balatro_seed());
+        assert!(SeedType::from("tutorial")
+            .normalize()
+            .is_valid_balatro_seed());
+        assert!(!SeedType::Numeric(7).is_valid_balatro_seed());
+
+        let seed: SeedType = "a1b2".parse().unwrap();
+        assert_eq!(seed.to_string(), "A1B2");
+        assert_eq!(seed.to_string().parse(), Ok(seed));
+        assert_eq!(SeedType::Numeric(12345).to_string(), "12345");
+    }
+
     #[test]
     fn test_pseudorandom_ranges() {
         let mut rng = BalatroRng::new(SeedType::Numeric(12345));
@@ -373,6 +1594,394 @@ mod tests {
         assert!((0.0..1.0).contains(&val));
     }
 
+    #[test]
+    fn test_pseudorandom_cache_matches_uncached() {
+        let mut uncached = BalatroRng::new(SeedType::Numeric(12345));
+        let mut cached = BalatroRng::new(SeedType::Numeric(12345));
+        cached.enable_pseudorandom_cache(4);
+
+        for round in 0..3 {
+            for seed in 0..8u64 {
+                let expected = uncached.pseudorandom(SeedType::Numeric(seed), Some(1), Some(100));
+                let actual = cached.pseudorandom(SeedType::Numeric(seed), Some(1), Some(100));
+                assert_eq!(expected, actual, "round {round}, seed {seed}");
+            }
+        }
+        assert_eq!(cached.pseudorandom_cache_len(), 4);
+
+        // Same seed with different bounds is a distinct entry
+        let a = cached.pseudorandom(SeedType::Numeric(1), None, None);
+        let b = cached.pseudorandom(SeedType::Numeric(1), Some(1), Some(6));
+        assert_eq!(a, uncached.pseudorandom(SeedType::Numeric(1), None, None));
+        assert_eq!(
+            b,
+            uncached.pseudorandom(SeedType::Numeric(1), Some(1), Some(6))
+        );
+
+        cached.disable_pseudorandom_cache();
+        assert_eq!(cached.pseudorandom_cache_len(), 0);
+    }
+
+    #[test]
+    fn test_pseudorandom_cache_evicts_least_recently_used() {
+        let mut cache = PseudorandomCache::new(3);
+        let key = |seed| (seed, None, None);
+        for seed in 0..3 {
+            cache.insert(key(seed), seed as f64);
+        }
+
+        // Touching 0 makes 1 the oldest, so 3 evicts it
+        assert_eq!(cache.get(&key(0)), Some(0.0));
+        cache.insert(key(3), 3.0);
+        assert_eq!(cache.get(&key(1)), None);
+        assert_eq!(cache.len(), 3);
+
+        // Then 2, then 0
+        cache.insert(key(4), 4.0);
+        cache.insert(key(5), 5.0);
+        assert_eq!(cache.get(&key(2)), None);
+        assert_eq!(cache.get(&key(0)), None);
+        for seed in 3..6 {
+            assert_eq!(cache.get(&key(seed)), Some(seed as f64));
+        }
+    }
+
+    #[test]
+    fn test_select_boss_blind() {
+        let pool = &BossBlind::ALL[..23];
+
+        let mut rng1 = BalatroRng::new(SeedType::String("BOSSES".to_string()));
+        let mut rng2 = BalatroRng::new(SeedType::String("BOSSES".to_string()));
+        for ante in 1..=8 {
+            assert_eq!(
+                rng1.select_boss_blind(ante, pool, &[]),
+                rng2.select_boss_blind(ante, pool, &[]),
+                "ante {ante}"
+            );
+        }
+
+        // Recent bosses are avoided while alternatives remain
+        let mut rng = BalatroRng::new(SeedType::String("BOSSES".to_string()));
+        let recent = &pool[..22];
+        for ante in 2..=7 {
+            assert_eq!(rng.select_boss_blind(ante, pool, recent), pool[22]);
+        }
+
+        // Falls back to every eligible boss once everything has been seen
+        let boss = rng.select_boss_blind(1, pool, pool);
+        assert!(boss.is_eligible(1));
+    }
+
+    #[test]
+    fn test_select_boss_blind_respects_ante_pool() {
+        let mut rng = BalatroRng::new(SeedType::String("BOSSES".to_string()));
+        for round in 0..50 {
+            for ante in [8, 16, 24] {
+                let boss = rng.select_boss_blind(ante, &BossBlind::ALL, &[]);
+                assert!(boss.is_finisher(), "ante {ante} round {round}: {boss:?}");
+            }
+            for ante in 1..=7 {
+                let boss = rng.select_boss_blind(ante, &BossBlind::ALL, &[]);
+                assert!(!boss.is_finisher() && boss.min_ante() <= ante, "{boss:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_roll_card_modifiers() {
+        let odds = ModifierOdds {
+            enhancement_chance: 0.5,
+            seal_chance: 0.5,
+            foil_chance: 0.3,
+            holographic_chance: 0.1,
+            polychrome_chance: 0.1,
+            negative_chance: 0.0,
+        };
+        let no_editions = ModifierOdds {
+            foil_chance: 0.0,
+            holographic_chance: 0.0,
+            polychrome_chance: 0.0,
+            ..odds
+        };
+
+        let roll_all = |odds: &ModifierOdds| {
+            let mut rng = BalatroRng::new(SeedType::String("MODIFIERS".to_string()));
+            (0..100)
+                .map(|i| rng.roll_card_modifiers(2, &format!("card_{i}"), odds))
+                .collect::<Vec<_>>()
+        };
+
+        let first = roll_all(&odds);
+        assert_eq!(first, roll_all(&odds), "rolls must be reproducible");
+        assert!(first.iter().any(|m| m.enhancement.is_some()));
+        assert!(first.iter().any(|m| m.edition.is_some()));
+        assert!(first.iter().any(|m| m.seal.is_some()));
+
+        // Changing edition odds leaves enhancements and seals untouched
+        let without = roll_all(&no_editions);
+        assert!(without.iter().all(|m| m.edition.is_none()));
+        for (a, b) in first.iter().zip(&without) {
+            assert_eq!(a.enhancement, b.enhancement);
+            assert_eq!(a.seal, b.seal);
+        }
+
+        // Results depend on the card key, not on roll order
+        let mut rng = BalatroRng::new(SeedType::String("MODIFIERS".to_string()));
+        assert_eq!(rng.roll_card_modifiers(2, "card_7", &odds), first[7]);
+
+        // Enhanced cards roll the same enhancement as roll_enhancement
+        for (i, modifiers) in first.iter().enumerate() {
+            if let Some(enhancement) = modifiers.enhancement {
+                let mut rng = BalatroRng::new(SeedType::String("MODIFIERS".to_string()));
+                assert_eq!(rng.roll_enhancement(2, &format!("card_{i}")), enhancement);
+            }
+        }
+    }
+
+    #[test]
+    fn test_challenge_context() {
+        let rng = BalatroRng::new(SeedType::String("EGGS".to_string()))
+            .with_challenge(Challenge::omelette());
+        assert_eq!(rng.starting_jokers(), vec!["j_egg"; 5]);
+        assert_eq!(rng.starting_deck().len(), 52);
+
+        let vanilla = BalatroRng::new(SeedType::String("EGGS".to_string()));
+        assert!(vanilla.starting_jokers().is_empty());
+        assert_eq!(vanilla.starting_deck(), standard_deck());
+
+        let challenge = Challenge {
+            deck: vec!["S_A".to_string(); 10],
+            seeded_keys: vec![("shuffle".to_string(), 7)],
+            fixed_seeds: vec![("boss".to_string(), 42)],
+            ..Challenge::jokerless()
+        };
+        let mut rng1 = BalatroRng::new(SeedType::String("FIXED".to_string()))
+            .with_challenge(challenge.clone());
+        let mut rng2 =
+            BalatroRng::new(SeedType::String("FIXED".to_string())).with_challenge(challenge);
+        assert_eq!(rng1.starting_deck(), vec!["S_A"; 10]);
+        assert!(rng1.starting_jokers().is_empty());
+
+        // Pre-seeded and pinned keys
+        assert_eq!(rng1.state().get_key_seed("shuffle"), 7);
+        assert_eq!(rng1.pseudoseed("boss"), 42);
+        assert_eq!(rng1.pseudoseed("boss"), 42);
+
+        // Everything else stays deterministic
+        for key in ["shuffle", "rarity1", "shuffle"] {
+            assert_eq!(rng1.pseudoseed(key), rng2.pseudoseed(key));
+        }
+        assert_ne!(
+            BalatroRng::new(SeedType::String("FIXED".to_string()))
+                .with_challenge(Challenge {
+                    seeded_keys: vec![("shuffle".to_string(), 7)],
+                    ..Challenge::jokerless()
+                })
+                .pseudoseed("shuffle"),
+            BalatroRng::new(SeedType::String("FIXED".to_string())).pseudoseed("shuffle"),
+            "pre-seeding shifts the key's stream"
+        );
+    }
+
+    #[test]
+    fn test_split_is_reproducible_and_independent() {
+        let parent = PseudorandomState::new(SeedType::String("SPLIT".to_string()));
+        let draws = |rng: &mut BalatroRng| {
+            (0..5)
+                .map(|_| rng.pseudoseed("rarity1"))
+                .collect::<Vec<_>>()
+        };
+
+        let (mut a1, mut b1) = BalatroRng::from_state(parent.clone()).split();
+        let (mut a2, mut b2) = BalatroRng::from_state(parent.clone()).split();
+        let left = draws(&mut a1);
+        let right = draws(&mut b1);
+        assert_eq!(left, draws(&mut a2));
+        assert_eq!(right, draws(&mut b2));
+        assert_ne!(left, right);
+
+        // The parent advances, so splitting again gives a different pair
+        let mut parent = BalatroRng::from_state(parent);
+        let (mut first, _) = parent.split();
+        let (mut second, _) = parent.split();
+        assert_ne!(draws(&mut first), draws(&mut second));
+    }
+
+    #[test]
+    fn test_nth_seed_matches_advancing() {
+        let mut state = PseudorandomState::new(SeedType::String("NTH".to_string()));
+        state.pseudoseed("shop_1_0");
+        state.pseudoseed("shop_1_0");
+
+        for n in 0..8 {
+            let mut advanced = state.clone();
+            for _ in 0..n {
+                advanced.pseudoseed("shop_1_0");
+            }
+            let peeked = advanced.clone().pseudoseed("shop_1_0");
+            assert_eq!(state.nth_seed("shop_1_0", n), peeked, "n = {n}");
+        }
+
+        // Peeking never advances the key
+        assert_eq!(state.get_key_seed("shop_1_0"), 2);
+        assert_eq!(
+            state.nth_seed("unused", 0),
+            state.clone().pseudoseed("unused")
+        );
+    }
+
+    /// Exact outputs for fixed seeds; these must never change on any host
+    /// (endianness or pointer width) or saved runs stop reproducing
+    #[test]
+    fn test_pinned_cross_platform_outputs() {
+        let mut rng = BalatroRng::new(SeedType::String("TUTORIAL".to_string()));
+        assert_eq!(rng.state().base_seed(), 2765539982484055707);
+
+        let seed = rng.pseudoseed("shuffle1");
+        assert_eq!(seed, 15563305463740807017);
+
+        let mut deck: Vec<u32> = (0..10).collect();
+        rng.pseudoshuffle(&mut deck, seed);
+        assert_eq!(deck, vec![0, 1, 7, 2, 8, 3, 6, 9, 4, 5]);
+
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let seed = rng.pseudoseed("rarity1");
+        assert_eq!(seed, 18394023325134570878);
+        assert_eq!(
+            rng.pseudorandom_element(&[0, 1, 2, 3, 4, 5, 6], seed),
+            Some(&4)
+        );
+    }
+
+    #[test]
+    fn test_draw_from_pool_avoids_repeats_until_exhausted() {
+        let items: Vec<ConsumableId> = ["c_fool", "c_magician", "c_empress", "c_emperor"]
+            .into_iter()
+            .map(ConsumableId::from)
+            .collect();
+
+        let draw_sequence = |seed: &str| {
+            let mut rng = BalatroRng::new(SeedType::String(seed.to_string()));
+            let mut pool = ConsumablePool::new(items.clone());
+            (0..12)
+                .map(|_| rng.draw_from_pool(&mut pool, "Tarotsho1"))
+                .collect::<Vec<_>>()
+        };
+
+        let sequence = draw_sequence("POOL");
+        for window in sequence.chunks(items.len()) {
+            let mut seen = window.to_vec();
+            seen.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+            seen.dedup();
+            assert_eq!(seen.len(), items.len(), "repeat within {window:?}");
+        }
+
+        assert_eq!(sequence, draw_sequence("POOL"));
+        assert_ne!(sequence, draw_sequence("OTHER"));
+    }
+
+    #[test]
+    fn test_generate_consumable() {
+        let pools = ConsumablePools::default();
+        let kinds = [
+            ConsumableKind::Tarot,
+            ConsumableKind::Planet,
+            ConsumableKind::Spectral,
+        ];
+
+        let mut rng1 = BalatroRng::new(SeedType::String("PACKS".to_string()));
+        let mut rng2 = BalatroRng::new(SeedType::String("PACKS".to_string()));
+        for kind in kinds {
+            for _ in 0..10 {
+                let card = rng1.generate_consumable(kind, 1, "ar1", &pools);
+                assert!(pools.pool(kind).contains(&card), "{card:?} not a {kind:?}");
+                assert_eq!(card, rng2.generate_consumable(kind, 1, "ar1", &pools));
+            }
+        }
+
+        // Different sources in the same ante use independent streams
+        let mut rng = BalatroRng::new(SeedType::String("PACKS".to_string()));
+        let from_pack: Vec<_> = (0..10)
+            .map(|_| rng.generate_consumable(ConsumableKind::Tarot, 1, "ar1", &pools))
+            .collect();
+        let from_shop: Vec<_> = (0..10)
+            .map(|_| rng.generate_consumable(ConsumableKind::Tarot, 1, "sho", &pools))
+            .collect();
+        assert_ne!(from_pack, from_shop);
+
+        // Modded pools are honoured
+        let modded = ConsumablePools {
+            tarot: vec![ConsumableId::from("c_mod_tarot")],
+            ..ConsumablePools::default()
+        };
+        assert_eq!(
+            rng.generate_consumable(ConsumableKind::Tarot, 2, "ar1", &modded),
+            ConsumableId::from("c_mod_tarot")
+        );
+    }
+
+    #[test]
+    fn test_alias_choice_matches_weighted_choice_distribution() {
+        let weights = [70.0, 20.0, 8.0, 1.8, 0.2, 0.0];
+        let choices: Vec<(usize, f64)> = weights.iter().copied().enumerate().collect();
+        let table = AliasTable::from_weights(&weights).unwrap();
+        assert_eq!(table.len(), weights.len());
+
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let draws = 100_000;
+        let mut alias_counts = [0usize; 6];
+        let mut linear_counts = [0usize; 6];
+        for seed in 0..draws as u64 {
+            alias_counts[rng.alias_choice(&table, seed)] += 1;
+            linear_counts[*rng.weighted_choice(&choices, seed).unwrap()] += 1;
+        }
+
+        assert_eq!(alias_counts[5], 0, "zero weight must never be drawn");
+        for (i, weight) in weights.iter().enumerate() {
+            let expected = weight / 100.0;
+            let alias_freq = alias_counts[i] as f64 / draws as f64;
+            let linear_freq = linear_counts[i] as f64 / draws as f64;
+            assert!(
+                (alias_freq - expected).abs() < 0.01,
+                "alias index {i}: {alias_freq}"
+            );
+            assert!((alias_freq - linear_freq).abs() < 0.01, "index {i}");
+        }
+
+        assert!(AliasTable::from_weights(&[]).is_none());
+        assert!(AliasTable::from_weights(&[0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_pseudoshuffle_fix() {
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let deck: Vec<u32> = (0..20).collect();
+
+        let mut forced = deck.clone();
+        let placed = rng.pseudoshuffle_fix(&mut forced, &[13, 2, 7], 999);
+        assert_eq!(placed, 3);
+        assert_eq!(&forced[..3], &[13, 2, 7]);
+
+        // The tail is the same shuffle of the remaining cards every time
+        let mut again = deck.clone();
+        rng.pseudoshuffle_fix(&mut again, &[13, 2, 7], 999);
+        assert_eq!(forced, again);
+
+        let mut rest: Vec<u32> = deck
+            .iter()
+            .copied()
+            .filter(|c| ![13, 2, 7].contains(c))
+            .collect();
+        rng.pseudoshuffle(&mut rest, 999);
+        assert_eq!(&forced[3..], rest.as_slice());
+
+        // Missing fixed cards are skipped rather than panicking
+        let mut partial = deck.clone();
+        assert_eq!(rng.pseudoshuffle_fix(&mut partial, &[5, 42, 5], 1), 1);
+        assert_eq!(partial[0], 5);
+        assert_eq!(partial.len(), deck.len());
+    }
+
     #[test]
     fn test_pseudoshuffle_deterministic() {
         let mut rng = BalatroRng::new(SeedType::Numeric(12345));
@@ -390,6 +1999,171 @@ mod tests {
         assert_ne!(vec1, vec![1, 2, 3, 4, 5]);
     }
 
+    #[test]
+    fn test_pseudoshuffle_key() {
+        let deck: Vec<u32> = (1..=52).collect();
+
+        let mut rng1 = BalatroRng::new(SeedType::Numeric(12345));
+        let mut rng2 = BalatroRng::new(SeedType::Numeric(12345));
+
+        let mut first1 = deck.clone();
+        let mut first2 = deck.clone();
+        rng1.pseudoshuffle_key(&mut first1, "shuffle");
+        rng2.pseudoshuffle_key(&mut first2, "shuffle");
+        assert_eq!(first1, first2);
+
+        // The key advances, so a second shuffle on the same RNG differs
+        let mut second = deck.clone();
+        rng1.pseudoshuffle_key(&mut second, "shuffle");
+        assert_ne!(first1, second);
+    }
+
+    #[test]
+    fn test_stake_rarity_and_stickers() {
+        use crate::utils::stake::Stake;
+
+        let rarities = |stake: StakeModifiers| {
+            let mut rng = BalatroRng::new(SeedType::from("TUTORIAL")).with_stake(stake);
+            (0..2000)
+                .map(|_| rng.roll_rarity(1, Some("sho")))
+                .collect::<Vec<_>>()
+        };
+        let share = |rolls: &[JokerRarity], rarity| {
+            rolls.iter().filter(|r| **r == rarity).count() as f64 / rolls.len() as f64
+        };
+
+        // Vanilla stakes never change rarity, so every stake sees the same jokers
+        let white = rarities(Stake::White.modifiers());
+        assert_eq!(white, rarities(Stake::Gold.modifiers()));
+        assert!((share(&white, JokerRarity::Rare) - 0.05).abs() < 0.02);
+
+        // A modded stake with fewer commons shifts the distribution reproducibly
+        let modded = StakeModifiers {
+            rarity_weights: [40, 40, 20],
+            ..Stake::Gold.modifiers()
+        };
+        let shifted = rarities(modded);
+        assert_eq!(shifted, rarities(modded));
+        assert!(share(&shifted, JokerRarity::Common) < share(&white, JokerRarity::Common));
+        assert!((share(&shifted, JokerRarity::Rare) - 0.2).abs() < 0.03);
+
+        let stickers = |stake: Stake| {
+            let mut rng = BalatroRng::new(SeedType::from("TUTORIAL")).with_stake(stake.modifiers());
+            (0..2000)
+                .map(|_| rng.roll_joker_stickers(1, false))
+                .collect::<Vec<_>>()
+        };
+        assert!(stickers(Stake::Green)
+            .iter()
+            .all(|s| *s == Default::default()));
+        let gold = stickers(Stake::Gold);
+        assert_eq!(gold, stickers(Stake::Gold));
+        let eternal = gold.iter().filter(|s| s.eternal).count() as f64 / 2000.0;
+        let rental = gold.iter().filter(|s| s.rental).count() as f64 / 2000.0;
+        assert!((eternal - 0.3).abs() < 0.04, "eternal share {eternal}");
+        assert!((rental - 0.3).abs() < 0.04, "rental share {rental}");
+        assert!(gold.iter().all(|s| !(s.eternal && s.perishable)));
+    }
+
+    #[test]
+    fn test_pack_generation_with_vouchers() {
+        let pools = ConsumablePools::default();
+        let pluto = ConsumableId::from("c_pluto");
+        let pack = |vouchers: &[&str], kind| {
+            let mut state = VoucherState::default();
+            for id in vouchers {
+                assert!(state.redeem(id));
+            }
+            let mut rng = BalatroRng::new(SeedType::from("TUTORIAL")).with_vouchers(state);
+            (0..20)
+                .map(|ante| rng.generate_pack(kind, ante % 8 + 1, 3, &pools, Some(&pluto)))
+                .collect::<Vec<_>>()
+        };
+
+        // Without vouchers a pack is plain generate_consumable draws
+        let mut rng = BalatroRng::new(SeedType::from("TUTORIAL"));
+        let vanilla: Vec<ConsumableId> = (0..3)
+            .map(|_| rng.generate_consumable(ConsumableKind::Planet, 1, "pl1", &pools))
+            .collect();
+        assert_eq!(pack(&[], ConsumableKind::Planet)[0], vanilla);
+
+        // Telescope pins the first card and leaves the rest of the stream alone
+        let telescope = pack(&["v_telescope"], ConsumableKind::Planet);
+        assert_eq!(telescope, pack(&["v_telescope"], ConsumableKind::Planet));
+        assert!(telescope.iter().all(|p| p[0] == pluto));
+        assert_eq!(telescope[0][1..], vanilla[..2]);
+
+        // Omen Globe swaps some Arcana cards for Spectrals, reproducibly
+        let arcana = pack(&[], ConsumableKind::Tarot);
+        let omen = pack(&["v_omen_globe"], ConsumableKind::Tarot);
+        assert_eq!(omen, pack(&["v_omen_globe"], ConsumableKind::Tarot));
+        assert!(arcana.iter().flatten().all(|c| pools.tarot.contains(c)));
+        assert!(omen.iter().flatten().any(|c| pools.spectral.contains(c)));
+    }
+
+    #[test]
+    fn test_opening_hand() {
+        let deck = standard_deck();
+        let hand = |seed: &str, ante: u8| {
+            BalatroRng::new(SeedType::from(seed)).opening_hand(&deck, ante, 8)
+        };
+
+        let first = hand("TUTORIAL", 1);
+        assert_eq!(first.len(), 8);
+        assert!(first.iter().all(|card| deck.contains(card)));
+        let mut unique = first.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 8);
+
+        assert_eq!(first, hand("TUTORIAL", 1));
+        assert_ne!(first, hand("TUTORIAL", 2));
+        assert_ne!(first, hand("ABCDEFG", 1));
+
+        // The hand is the top of the same shuffle the game applies
+        let mut rng = BalatroRng::new(SeedType::from("TUTORIAL"));
+        let mut shuffled = deck.clone();
+        rng.pseudoshuffle_key(&mut shuffled, "nr1");
+        assert_eq!(
+            first,
+            shuffled[44..].iter().rev().cloned().collect::<Vec<_>>()
+        );
+
+        let mut rng = BalatroRng::new(SeedType::from("TUTORIAL"));
+        assert_eq!(rng.opening_hand(&deck[..3], 1, 8).len(), 3);
+    }
+
+    #[test]
+    fn test_permutation_rank_round_trip() {
+        assert_eq!(permutation_to_rank(&[]), 0);
+        assert_eq!(permutation_to_rank(&[0, 1, 2]), 0);
+        assert_eq!(permutation_to_rank(&[2, 1, 0]), 5);
+
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        for n in 0..=20 {
+            for seed in 0..10 {
+                let perm = rng.pseudoshuffle_indices(n, seed);
+                let rank = permutation_to_rank(&perm);
+                assert_eq!(rank_to_permutation(rank, n), perm);
+            }
+        }
+
+        let max = rank_to_permutation(factorial(MAX_RANKED_PERMUTATION_LEN) - 1, 34);
+        assert_eq!(max, (0..34).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_pseudoshuffle_rank_matches_indices() {
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        for seed in 0..20 {
+            let rank = rng.pseudoshuffle_rank(20, seed);
+            assert_eq!(
+                rank_to_permutation(rank, 20),
+                rng.pseudoshuffle_indices(20, seed)
+            );
+        }
+    }
+
     #[test]
     fn test_pseudorandom_element() {
         let mut rng = BalatroRng::new(SeedType::Numeric(12345));
@@ -449,6 +2223,32 @@ mod tests {
         assert_ne!(soul_seed, front_seed);
     }
 
+    #[test]
+    fn test_composite_seed_is_unambiguous() {
+        let mut rng1 = BalatroRng::new(SeedType::Numeric(12345));
+        let mut rng2 = BalatroRng::new(SeedType::Numeric(12345));
+
+        // Naive concatenation would collide here
+        assert_eq!(
+            rng1.pseudoseed(&["a", "bc"].concat()),
+            rng2.pseudoseed(&["ab", "c"].concat())
+        );
+
+        let mut rng1 = BalatroRng::new(SeedType::Numeric(12345));
+        let mut rng2 = BalatroRng::new(SeedType::Numeric(12345));
+        assert_ne!(
+            rng1.composite_seed(&["a", "bc"]),
+            rng2.composite_seed(&["ab", "c"])
+        );
+
+        // Same parts on a fresh RNG reproduce, repeated calls advance
+        let mut rng3 = BalatroRng::new(SeedType::Numeric(12345));
+        let first = rng3.composite_seed(&["a", "bc"]);
+        let mut rng4 = BalatroRng::new(SeedType::Numeric(12345));
+        assert_eq!(first, rng4.composite_seed(&["a", "bc"]));
+        assert_ne!(first, rng3.composite_seed(&["a", "bc"]));
+    }
+
     #[test]
     fn test_state_serialization() {
         let mut rng = BalatroRng::new(SeedType::String("TEST".to_string()));
@@ -467,6 +2267,36 @@ mod tests {
         assert_eq!(state.key_seeds(), deserialized.key_seeds());
     }
 
+    #[test]
+    fn test_versioned_state_loading() {
+        let mut rng = BalatroRng::new(SeedType::String("TEST".to_string()));
+        rng.pseudoseed("test1");
+        let state = rng.state().clone();
+
+        let current = serde_json::to_value(&state).unwrap();
+        assert_eq!(current["version"], PSEUDORANDOM_STATE_VERSION);
+
+        // A v1 blob is the same layout without the version tag
+        let mut v1 = current.clone();
+        v1.as_object_mut().unwrap().remove("version");
+        let loaded = PseudorandomState::from_json_versioned(&v1.to_string()).unwrap();
+        assert_eq!(loaded.version(), PSEUDORANDOM_STATE_VERSION);
+        assert_eq!(loaded.base_seed(), state.base_seed());
+        assert_eq!(loaded.key_seeds(), state.key_seeds());
+
+        let mut future = current;
+        future["version"] = serde_json::json!(PSEUDORANDOM_STATE_VERSION + 1);
+        let err = PseudorandomState::from_json_versioned(&future.to_string()).unwrap_err();
+        assert!(matches!(
+            err,
+            StateLoadError::UnsupportedVersion { found, .. }
+                if found == u64::from(PSEUDORANDOM_STATE_VERSION + 1)
+        ));
+        assert!(err
+            .to_string()
+            .contains("unsupported PseudorandomState version"));
+    }
+
     #[test]
     fn test_probability_check() {
         let mut rng = BalatroRng::new(SeedType::Numeric(12345));
@@ -481,6 +2311,72 @@ mod tests {
         assert_eq!(result1, result2);
     }
 
+    #[test]
+    fn test_probability_check_odds() {
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let mut hits = 0;
+        for seed in 0..1000 {
+            assert!(rng.probability_check_odds(5.0, 4.0, seed));
+            assert!(rng.probability_check_odds(4.0, 4.0, seed));
+            assert!(!rng.probability_check_odds(0.0, 4.0, seed));
+            // A doubled "1 in 4" rolls exactly like "2 in 4"
+            assert_eq!(
+                rng.probability_check_odds(1.0 * 2.0, 4.0, seed),
+                rng.probability_check(0.5, seed)
+            );
+            hits += usize::from(rng.probability_check_odds(1.0, 4.0, seed));
+        }
+        assert!((200..300).contains(&hits), "{hits}");
+    }
+
+    #[test]
+    fn test_typed_modifier_rolls_are_pinned() {
+        let mut rng = BalatroRng::new(SeedType::from("MODS"));
+        let enhancements: Vec<Enhancement> =
+            (0..6).map(|_| rng.roll_enhancement(1, "sta")).collect();
+        let seals: Vec<Seal> = (0..6).map(|_| rng.roll_seal(1, "sta")).collect();
+        let editions: Vec<Option<Edition>> = (0..400).map(|_| rng.roll_edition(1, "sta")).collect();
+
+        {
+            use Enhancement::*;
+            assert_eq!(enhancements, [Mult, Wild, Mult, Steel, Mult, Stone]);
+        }
+        {
+            use Seal::*;
+            assert_eq!(seals, [Blue, Gold, Purple, Gold, Purple, Purple]);
+        }
+        let rolled: Vec<(usize, Edition)> = editions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, edition)| edition.map(|edition| (i, edition)))
+            .collect();
+        {
+            use Edition::*;
+            assert_eq!(
+                rolled,
+                [
+                    (2, Holographic),
+                    (58, Holographic),
+                    (80, Holographic),
+                    (85, Foil),
+                    (99, Foil),
+                    (127, Holographic),
+                    (179, Holographic),
+                    (183, Polychrome),
+                    (190, Holographic),
+                    (213, Polychrome),
+                    (239, Holographic),
+                    (272, Foil),
+                    (310, Foil),
+                    (335, Holographic),
+                    (364, Foil),
+                    (394, Foil),
+                ]
+            );
+        }
+        assert_eq!(rng.state().get_key_seed("Enhanced1sta"), 6);
+    }
+
     #[test]
     fn test_weighted_choice() {
         let mut rng = BalatroRng::new(SeedType::Numeric(12345));
@@ -493,4 +2389,72 @@ mod tests {
         let choice_val = choice.unwrap();
         assert!(choices.iter().any(|(item, _)| item == choice_val));
     }
+
+    #[test]
+    fn test_weighted_choice_index_distribution() {
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let weights = [50.0, 0.0, 30.0, 15.0, -4.0, 5.0];
+        let draws = 100_000;
+        let mut counts = [0usize; 6];
+        for seed in 0..draws as u64 {
+            counts[rng.weighted_choice_index(&weights, seed).unwrap()] += 1;
+        }
+
+        assert_eq!(counts[1], 0, "zero weight must never be drawn");
+        assert_eq!(counts[4], 0, "negative weight is clamped to zero");
+        for (i, weight) in weights.iter().enumerate() {
+            let share = weight.max(0.0) / 100.0;
+            let freq = counts[i] as f64 / draws as f64;
+            assert!((freq - share).abs() < 0.02, "index {i}: {freq} vs {share}");
+        }
+
+        let choices: Vec<(usize, f64)> = weights.iter().copied().enumerate().collect();
+        assert_eq!(
+            rng.weighted_choice(&choices, 7).copied(),
+            rng.weighted_choice_index(&weights, 7)
+        );
+        assert_eq!(rng.weighted_choice_index(&[], 7), None);
+        assert_eq!(rng.weighted_choice_index(&[0.0, -1.0, f64::NAN], 7), None);
+    }
+
+    #[test]
+    fn test_weighted_choice_int_distribution() {
+        let choices = [
+            ("common", 70u32),
+            ("uncommon", 25),
+            ("rare", 5),
+            ("none", 0),
+        ];
+        let total = 100u64;
+
+        // Every draw value maps to exactly one choice, so shares are exact
+        let mut exact = [0u64; 4];
+        for target in 0..total {
+            let picked = pick_cumulative(&choices, target).unwrap();
+            exact[choices.iter().position(|(c, _)| c == picked).unwrap()] += 1;
+        }
+        assert_eq!(exact, [70, 25, 5, 0]);
+        assert!(pick_cumulative(&choices, total).is_none());
+
+        let mut rng = BalatroRng::new(SeedType::Numeric(12345));
+        let draws = 200_000u64;
+        let mut counts = [0u64; 4];
+        for seed in 0..draws {
+            let picked = rng.weighted_choice_int(&choices, seed).unwrap();
+            counts[choices.iter().position(|(c, _)| c == picked).unwrap()] += 1;
+        }
+        assert_eq!(counts[3], 0, "zero weight must never be drawn");
+        for (i, (_, weight)) in choices.iter().enumerate() {
+            let expected = f64::from(*weight) / total as f64;
+            let freq = counts[i] as f64 / draws as f64;
+            assert!((freq - expected).abs() < 0.005, "index {i}: {freq}");
+        }
+
+        assert_eq!(
+            rng.weighted_choice_int(&choices, 42),
+            rng.weighted_choice_int(&choices, 42)
+        );
+        assert!(rng.weighted_choice_int::<&str>(&[], 1).is_none());
+        assert!(rng.weighted_choice_int(&[("a", 0u32)], 1).is_none());
+    }
 }
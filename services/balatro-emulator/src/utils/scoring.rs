@@ -0,0 +1,324 @@
+//! Hand scoring primitives: leveled hand base values and per-hand RNG
+//!
+//! `hand_base` turns planet levels into a hand's starting chips and mult
+//! using the vanilla tables.
+//!
+//! Every random scoring trigger (Lucky cards, Bloodstone, retrigger chances)
+//! is seeded from the hand's seed plus a `(card_index, joker_id,
+//! trigger_number)` key. Results therefore depend only on which trigger is
+//! asked for, never on the order the scoring loop visits cards and jokers.
+//!
+//! These seeds are the emulator's own derivation, not the game's: Balatro
+//! draws each trigger from a named stream (`lucky_mult`, `lucky_money`, ...)
+//! that advances in scoring order. Code that must consume RNG exactly like
+//! the game should call `BalatroRng::pseudorandom_keyed` with those keys in
+//! the game's order instead.
+
+use serde::{Deserialize, Serialize};
+
+use super::rng::{stable_hash, BalatroRng, SeedType};
+
+/// `joker_id` used for effects of the card itself rather than a joker
+pub const CARD_TRIGGER: &str = "card";
+
+/// Odds of a Lucky card's +20 Mult (1 in 5) before probability boosts
+pub const LUCKY_MULT_ODDS: f64 = 1.0 / 5.0;
+
+/// Odds of a Lucky card's $20 (1 in 15) before probability boosts
+pub const LUCKY_MONEY_ODDS: f64 = 1.0 / 15.0;
+
+/// Poker hand types, in the game's order from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HandType {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind,
+    FlushHouse,
+    FlushFive,
+}
+
+impl HandType {
+    pub const ALL: [HandType; 12] = [
+        HandType::HighCard,
+        HandType::Pair,
+        HandType::TwoPair,
+        HandType::ThreeOfAKind,
+        HandType::Straight,
+        HandType::Flush,
+        HandType::FullHouse,
+        HandType::FourOfAKind,
+        HandType::StraightFlush,
+        HandType::FiveOfAKind,
+        HandType::FlushHouse,
+        HandType::FlushFive,
+    ];
+
+    /// The game's name for the hand (`G.GAME.hands` key)
+    pub fn key(&self) -> &'static str {
+        match self {
+            HandType::HighCard => "High Card",
+            HandType::Pair => "Pair",
+            HandType::TwoPair => "Two Pair",
+            HandType::ThreeOfAKind => "Three of a Kind",
+            HandType::Straight => "Straight",
+            HandType::Flush => "Flush",
+            HandType::FullHouse => "Full House",
+            HandType::FourOfAKind => "Four of a Kind",
+            HandType::StraightFlush => "Straight Flush",
+            HandType::FiveOfAKind => "Five of a Kind",
+            HandType::FlushHouse => "Flush House",
+            HandType::FlushFive => "Flush Five",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Level-1 chips and mult of a hand and what each extra level adds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandBase {
+    pub chips: u64,
+    pub mult: u64,
+    pub level_chips: u64,
+    pub level_mult: u64,
+}
+
+impl HandBase {
+    pub const fn new(chips: u64, mult: u64, level_chips: u64, level_mult: u64) -> Self {
+        Self {
+            chips,
+            mult,
+            level_chips,
+            level_mult,
+        }
+    }
+}
+
+/// Vanilla hand values, indexed in `HandType::ALL` order
+pub const VANILLA_HAND_BASES: [HandBase; 12] = [
+    HandBase::new(5, 1, 10, 1),    // High Card
+    HandBase::new(10, 2, 15, 1),   // Pair
+    HandBase::new(20, 2, 20, 1),   // Two Pair
+    HandBase::new(30, 3, 20, 2),   // Three of a Kind
+    HandBase::new(30, 4, 30, 3),   // Straight
+    HandBase::new(35, 4, 15, 2),   // Flush
+    HandBase::new(40, 4, 25, 2),   // Full House
+    HandBase::new(60, 7, 30, 3),   // Four of a Kind
+    HandBase::new(100, 8, 40, 4),  // Straight Flush
+    HandBase::new(120, 12, 35, 3), // Five of a Kind
+    HandBase::new(140, 14, 40, 4), // Flush House
+    HandBase::new(160, 16, 50, 3), // Flush Five
+];
+
+/// Per-hand-type levels raised by planet cards; every hand starts at level 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanetLevels {
+    levels: [u32; 12],
+}
+
+impl Default for PlanetLevels {
+    fn default() -> Self {
+        Self { levels: [1; 12] }
+    }
+}
+
+impl PlanetLevels {
+    pub fn level(&self, hand: HandType) -> u32 {
+        self.levels[hand.index()]
+    }
+
+    /// Raise a hand by `amount` levels (a planet card is 1; Black Hole raises every hand)
+    pub fn level_up(&mut self, hand: HandType, amount: u32) {
+        let level = &mut self.levels[hand.index()];
+        *level = level.saturating_add(amount);
+    }
+
+    /// Lower a hand by `amount` levels, never below 1 (The Arm)
+    pub fn level_down(&mut self, hand: HandType, amount: u32) {
+        let level = &mut self.levels[hand.index()];
+        *level = level.saturating_sub(amount).max(1);
+    }
+}
+
+/// Leveled base `(chips, mult)` of a hand using the vanilla tables
+pub fn hand_base(hand: HandType, levels: &PlanetLevels) -> (u64, u64) {
+    let base = VANILLA_HAND_BASES[hand.index()];
+    // Levels below 1 only come from hand-edited saves; treat them as 1
+    let extra = u64::from(levels.level(hand).saturating_sub(1));
+    (
+        base.chips + extra * base.level_chips,
+        base.mult + extra * base.level_mult,
+    )
+}
+
+/// Which of a Lucky card's effects fired on one trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LuckyOutcome {
+    pub mult: bool,
+    pub money: bool,
+}
+
+/// RNG context for scoring one hand
+///
+/// Creating the context advances the run's RNG exactly once (the hand key);
+/// triggers are then derived from that seed without touching run state.
+pub struct ScoringContext<'a> {
+    rng: &'a mut BalatroRng,
+    hand_seed: u64,
+    rolls: u32,
+    /// Probability multiplier (2 per Oops! All 6s)
+    probability_boost: f64,
+}
+
+impl<'a> ScoringContext<'a> {
+    /// Start scoring a hand, consuming one seed from `hand_key`'s stream
+    pub fn new(rng: &'a mut BalatroRng, hand_key: &str) -> Self {
+        let hand_seed = rng.pseudoseed(hand_key);
+        Self {
+            rng,
+            hand_seed,
+            rolls: 0,
+            probability_boost: 1.0,
+        }
+    }
+
+    /// Multiply every trigger probability (Oops! All 6s doubles it)
+    pub fn with_probability_boost(mut self, boost: f64) -> Self {
+        self.probability_boost = boost;
+        self
+    }
+
+    /// Seed for one trigger, independent of the order triggers are rolled in
+    ///
+    /// A stable hash of the hand seed and the trigger's key; not a seed the
+    /// game itself would draw.
+    pub fn trigger_seed(&self, card_index: usize, joker_id: &str, trigger_number: u32) -> u64 {
+        stable_hash(&SeedType::String(format!(
+            "{}:{card_index}:{joker_id}:{trigger_number}",
+            self.hand_seed
+        )))
+    }
+
+    /// Roll a `probability` chance for one trigger
+    pub fn check(
+        &mut self,
+        card_index: usize,
+        joker_id: &str,
+        trigger_number: u32,
+        probability: f64,
+    ) -> bool {
+        let seed = self.trigger_seed(card_index, joker_id, trigger_number);
+        self.rolls += 1;
+        let probability = (probability * self.probability_boost).min(1.0);
+        self.rng.probability_check(probability, seed)
+    }
+
+    /// Roll both effects of a Lucky card scoring for the `trigger_number`th time
+    pub fn lucky_card(&mut self, card_index: usize, trigger_number: u32) -> LuckyOutcome {
+        LuckyOutcome {
+            mult: self.check(card_index, "lucky_mult", trigger_number, LUCKY_MULT_ODDS),
+            money: self.check(card_index, "lucky_money", trigger_number, LUCKY_MONEY_ODDS),
+        }
+    }
+
+    /// Number of triggers rolled so far in this hand
+    pub fn rolls(&self) -> u32 {
+        self.rolls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    type Results = BTreeMap<(usize, String, u32), bool>;
+
+    /// Score a hand of five Lucky cards (the middle one retriggered) plus a
+    /// Bloodstone roll per heart, visiting cards in `order`
+    fn score_hand(rng: &mut BalatroRng, order: &[usize]) -> (Results, u32) {
+        let mut ctx = ScoringContext::new(rng, "hand1");
+        let mut results = Results::new();
+        for &card in order {
+            let triggers = if card == 2 { 2 } else { 1 };
+            for trigger in 0..triggers {
+                let lucky = ctx.lucky_card(card, trigger);
+                results.insert((card, "lucky_mult".to_string(), trigger), lucky.mult);
+                results.insert((card, "lucky_money".to_string(), trigger), lucky.money);
+            }
+            let bloodstone = ctx.check(card, "j_bloodstone", 0, 0.5);
+            results.insert((card, "j_bloodstone".to_string(), 0), bloodstone);
+        }
+        (results, ctx.rolls())
+    }
+
+    #[test]
+    fn test_scoring_is_independent_of_iteration_order() {
+        let mut rng1 = BalatroRng::new(SeedType::String("SCORE".to_string()));
+        let mut rng2 = BalatroRng::new(SeedType::String("SCORE".to_string()));
+
+        let forward = score_hand(&mut rng1, &[0, 1, 2, 3, 4]);
+        let reversed = score_hand(&mut rng2, &[4, 3, 2, 1, 0]);
+        assert_eq!(forward, reversed);
+        assert_eq!(forward.1, 17);
+        assert_eq!(rng1.state().key_seeds(), rng2.state().key_seeds());
+
+        // The next hand rolls from a fresh seed
+        let next = score_hand(&mut rng1, &[0, 1, 2, 3, 4]);
+        assert_ne!(forward.0, next.0);
+    }
+
+    #[test]
+    fn test_unleveled_hands_match_base_values() {
+        let levels = PlanetLevels::default();
+        assert_eq!(hand_base(HandType::HighCard, &levels), (5, 1));
+        assert_eq!(hand_base(HandType::Flush, &levels), (35, 4));
+        assert_eq!(hand_base(HandType::FlushFive, &levels), (160, 16));
+        for hand in HandType::ALL {
+            let base = VANILLA_HAND_BASES[hand.index()];
+            assert_eq!(hand_base(hand, &levels), (base.chips, base.mult));
+        }
+    }
+
+    #[test]
+    fn test_leveling_adds_per_level_amounts() {
+        let mut levels = PlanetLevels::default();
+
+        // Pluto: High Card +10 chips, +1 mult per level
+        levels.level_up(HandType::HighCard, 2);
+        assert_eq!(levels.level(HandType::HighCard), 3);
+        assert_eq!(hand_base(HandType::HighCard, &levels), (25, 3));
+
+        // Jupiter: Flush +15 chips, +2 mult
+        levels.level_up(HandType::Flush, 1);
+        assert_eq!(hand_base(HandType::Flush, &levels), (50, 6));
+        assert_eq!(hand_base(HandType::Pair, &levels), (10, 2));
+
+        levels.level_down(HandType::Flush, 5);
+        assert_eq!(levels.level(HandType::Flush), 1);
+        assert_eq!(hand_base(HandType::Flush, &levels), (35, 4));
+    }
+
+    #[test]
+    fn test_level_zero_scores_as_level_one() {
+        let levels: PlanetLevels =
+            serde_json::from_str(&format!("{{\"levels\": {:?}}}", [0u32; 12])).unwrap();
+        assert_eq!(hand_base(HandType::Flush, &levels), (35, 4));
+    }
+
+    #[test]
+    fn test_probability_boost_caps_at_certain() {
+        let mut rng = BalatroRng::new(SeedType::String("SCORE".to_string()));
+        let mut ctx = ScoringContext::new(&mut rng, "hand1").with_probability_boost(4.0);
+        assert!((0..20).all(|i| ctx.check(i, "j_bloodstone", 0, 0.5)));
+    }
+}
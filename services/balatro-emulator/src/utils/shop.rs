@@ -0,0 +1,99 @@
+//! Shop pricing and sizing rules for the Balatro emulator
+//!
+//! Pure functions so every shop simulator shares the same reroll cost and
+//! slot count rules; `ShopModifiers` carries voucher/joker effects and lets
+//! mods swap the escalation formula.
+
+/// Reroll cost escalation: `(discounted_base, paid_rerolls) -> cost`
+pub type RerollEscalation = fn(u32, u32) -> u32;
+
+/// Vanilla escalation: each paid reroll in a shop costs $1 more
+pub fn linear_escalation(base: u32, paid_rerolls: u32) -> u32 {
+    base.saturating_add(paid_rerolls)
+}
+
+/// Effects that change reroll pricing and shop size
+#[derive(Debug, Clone, Copy)]
+pub struct ShopModifiers {
+    /// Flat reduction of the base reroll cost (Reroll Surplus/Glut: $2 each)
+    pub reroll_discount: u32,
+    /// Free rerolls available before paying (Chaos the Clown: 1)
+    pub free_rerolls: u32,
+    /// Joker/consumable slots in the shop before vouchers
+    pub base_slots: u32,
+    /// Extra slots from vouchers (Overstock, Overstock Plus: 1 each)
+    pub extra_slots: u32,
+    /// How cost grows with each paid reroll
+    pub escalation: RerollEscalation,
+}
+
+impl Default for ShopModifiers {
+    fn default() -> Self {
+        Self {
+            reroll_discount: 0,
+            free_rerolls: 0,
+            base_slots: 2,
+            extra_slots: 0,
+            escalation: linear_escalation,
+        }
+    }
+}
+
+/// Cost of the next reroll after `reroll_count` rerolls in the current shop
+pub fn reroll_cost(base: u32, reroll_count: u32, modifiers: &ShopModifiers) -> u32 {
+    if reroll_count < modifiers.free_rerolls {
+        return 0;
+    }
+    let base = base.saturating_sub(modifiers.reroll_discount);
+    (modifiers.escalation)(base, reroll_count - modifiers.free_rerolls)
+}
+
+/// Number of card slots in the shop for an ante
+///
+/// Vanilla shop size does not depend on the ante; the parameter is kept so
+/// modded rules can be layered on without changing callers.
+pub fn slot_count(_ante: u8, modifiers: &ShopModifiers) -> u32 {
+    modifiers.base_slots + modifiers.extra_slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reroll_cost_escalation() {
+        let vanilla = ShopModifiers::default();
+        let costs: Vec<u32> = (0..4).map(|n| reroll_cost(5, n, &vanilla)).collect();
+        assert_eq!(costs, vec![5, 6, 7, 8]);
+
+        let surplus_and_clown = ShopModifiers {
+            reroll_discount: 2,
+            free_rerolls: 1,
+            ..ShopModifiers::default()
+        };
+        let costs: Vec<u32> = (0..3)
+            .map(|n| reroll_cost(5, n, &surplus_and_clown))
+            .collect();
+        assert_eq!(costs, vec![0, 3, 4]);
+
+        let doubling = ShopModifiers {
+            escalation: |base, paid| base << paid.min(16),
+            ..ShopModifiers::default()
+        };
+        assert_eq!(reroll_cost(5, 3, &doubling), 40);
+    }
+
+    #[test]
+    fn test_slot_count() {
+        let vanilla = ShopModifiers::default();
+        for ante in [1, 2, 4, 8] {
+            assert_eq!(slot_count(ante, &vanilla), 2);
+        }
+
+        let overstock_plus = ShopModifiers {
+            extra_slots: 2,
+            ..ShopModifiers::default()
+        };
+        assert_eq!(slot_count(3, &overstock_plus), 4);
+    }
+}
@@ -0,0 +1,162 @@
+//! Vendored SipHash-1-3 for version-stable seed hashing
+//!
+//! `std`'s `DefaultHasher` is documented as free to change between Rust
+//! releases, which would silently change every seed. `StableHasher` pins the
+//! algorithm it happens to use today (SipHash-1-3 with both keys zero), so
+//! seeds hashed before the switch still reproduce and keep reproducing.
+//!
+//! The output depends only on the bytes written, never on how they were
+//! split across `write` calls. `with_keys` gives the keyed variant for
+//! callers that need a hash outsiders can't recompute.
+
+use std::hash::Hasher;
+
+const KEY0: u64 = 0;
+const KEY1: u64 = 0;
+
+/// SipHash-1-3 with pinned zero keys
+#[derive(Debug, Clone)]
+pub struct StableHasher {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    /// Pending bytes not yet forming a full 8-byte word, little-endian
+    tail: u64,
+    tail_len: usize,
+    /// Total bytes written
+    length: usize,
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self::with_keys(KEY0, KEY1)
+    }
+
+    /// SipHash-1-3 under a secret 128-bit key
+    pub fn with_keys(key0: u64, key1: u64) -> Self {
+        Self {
+            v0: key0 ^ 0x736f_6d65_7073_6575,
+            v1: key1 ^ 0x646f_7261_6e64_6f6d,
+            v2: key0 ^ 0x6c79_6765_6e65_7261,
+            v3: key1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            tail_len: 0,
+            length: 0,
+        }
+    }
+
+    /// Write a string as its UTF-8 bytes plus a `0xff` terminator
+    ///
+    /// The terminator is what `str`'s `Hash` impl appends, so this matches
+    /// the `DefaultHasher` seeds while no longer depending on that impl.
+    pub fn write_str(&mut self, s: &str) {
+        self.write(s.as_bytes());
+        self.write(&[0xff]);
+    }
+
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length += bytes.len();
+
+        // Top up a partial word first
+        while self.tail_len > 0 && self.tail_len < 8 {
+            let Some((&byte, rest)) = bytes.split_first() else {
+                return;
+            };
+            self.tail |= u64::from(byte) << (8 * self.tail_len);
+            self.tail_len += 1;
+            bytes = rest;
+        }
+        if self.tail_len == 8 {
+            self.compress(self.tail);
+            self.tail = 0;
+            self.tail_len = 0;
+        }
+
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.compress(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+        for (i, &byte) in words.remainder().iter().enumerate() {
+            self.tail |= u64::from(byte) << (8 * i);
+        }
+        self.tail_len = words.remainder().len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+        let last = ((self.length as u64 & 0xff) << 56) | self.tail;
+        state.compress(last);
+        state.v2 ^= 0xff;
+        for _ in 0..3 {
+            state.round();
+        }
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(chunks: &[&[u8]]) -> u64 {
+        let mut hasher = StableHasher::new();
+        for chunk in chunks {
+            hasher.write(chunk);
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_output_is_independent_of_write_splits() {
+        let bytes: Vec<u8> = (0u8..=40).collect();
+        let whole = hash(&[&bytes]);
+        for split in 0..bytes.len() {
+            let (left, right) = bytes.split_at(split);
+            assert_eq!(hash(&[left, right]), whole, "split at {split}");
+        }
+        let singles: Vec<&[u8]> = bytes.chunks(1).collect();
+        assert_eq!(hash(&singles), whole);
+    }
+
+    #[test]
+    fn test_pinned_outputs() {
+        assert_eq!(hash(&[]), 15130871412783076140);
+        let mut hasher = StableHasher::new();
+        hasher.write_str("TUTORIAL");
+        assert_eq!(hasher.finish(), 2765539982484055707);
+
+        let mut keyed = StableHasher::with_keys(1, 2);
+        keyed.write_str("TUTORIAL");
+        assert_ne!(keyed.finish(), 2765539982484055707);
+    }
+}
@@ -0,0 +1,185 @@
+//! Stake levels and the generation odds they change
+//!
+//! Stakes are cumulative: each level keeps every effect of the ones below it.
+//! In vanilla only the joker stickers (Eternal, Perishable, Rental) change how
+//! cards are generated; joker rarity odds are the same at every stake. Mods
+//! that alter rarity or sticker odds per stake supply their own
+//! `StakeModifiers`.
+
+use serde::{Deserialize, Serialize};
+
+/// A vanilla stake, from White (1) to Gold (8)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Stake {
+    White = 1,
+    Red,
+    Green,
+    Black,
+    Blue,
+    Purple,
+    Orange,
+    Gold,
+}
+
+impl Stake {
+    pub const ALL: [Stake; 8] = [
+        Stake::White,
+        Stake::Red,
+        Stake::Green,
+        Stake::Black,
+        Stake::Blue,
+        Stake::Purple,
+        Stake::Orange,
+        Stake::Gold,
+    ];
+
+    /// Stake level as used by the game (`G.GAME.stake`)
+    pub fn level(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Vanilla generation odds at this stake
+    ///
+    /// Black adds Eternal jokers, Orange Perishable and Gold Rental, each on
+    /// 30% of shop jokers. Red, Green, Blue and Purple only change blind
+    /// rewards, discards and score scaling, which do not touch the RNG.
+    pub fn modifiers(&self) -> StakeModifiers {
+        let from = |stake: Stake| *self >= stake;
+        StakeModifiers {
+            eternal_above: from(Stake::Black).then_some(0.7),
+            perishable_range: from(Stake::Orange).then_some((0.4, 0.7)),
+            rental_above: from(Stake::Gold).then_some(0.7),
+            ..StakeModifiers::default()
+        }
+    }
+}
+
+/// Joker rarity as rolled on the `rarity` key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JokerRarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+/// Generation odds that depend on the stake
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StakeModifiers {
+    /// Integer weights of common, uncommon and rare jokers (vanilla 70/25/5)
+    pub rarity_weights: [u32; 3],
+    /// A joker is Eternal when its eternal/perishable poll exceeds this
+    pub eternal_above: Option<f64>,
+    /// A joker not made Eternal is Perishable when the same poll is in
+    /// `(low, high]`, so a joker is never both
+    pub perishable_range: Option<(f64, f64)>,
+    /// A joker is Rental when its separate rental poll exceeds this
+    pub rental_above: Option<f64>,
+}
+
+impl Default for StakeModifiers {
+    /// White stake
+    fn default() -> Self {
+        Self {
+            rarity_weights: [70, 25, 5],
+            eternal_above: None,
+            perishable_range: None,
+            rental_above: None,
+        }
+    }
+}
+
+impl StakeModifiers {
+    /// Map a uniform roll in [0, 1) to a rarity, rarest first from the top
+    ///
+    /// With vanilla weights this is the game's `> 0.95` rare, `> 0.7`
+    /// uncommon split; thresholds are single divisions of integer sums so
+    /// they land on exactly those literals.
+    pub fn rarity_for_roll(&self, roll: f64) -> JokerRarity {
+        let [common, uncommon, rare] = self.rarity_weights.map(u64::from);
+        let total = (common + uncommon + rare) as f64;
+        let rare_threshold = (common + uncommon) as f64 / total;
+        let uncommon_threshold = common as f64 / total;
+        if roll > rare_threshold {
+            JokerRarity::Rare
+        } else if roll > uncommon_threshold {
+            JokerRarity::Uncommon
+        } else {
+            JokerRarity::Common
+        }
+    }
+
+    /// Stickers for a joker given its eternal/perishable and rental polls
+    pub fn stickers_for_polls(
+        &self,
+        eternal_perishable_poll: f64,
+        rental_poll: f64,
+    ) -> JokerStickers {
+        let poll = eternal_perishable_poll;
+        let eternal = self.eternal_above.is_some_and(|above| poll > above);
+        let perishable = !eternal
+            && self
+                .perishable_range
+                .is_some_and(|(low, high)| poll > low && poll <= high);
+        JokerStickers {
+            eternal,
+            perishable,
+            rental: self.rental_above.is_some_and(|above| rental_poll > above),
+        }
+    }
+}
+
+/// Stickers applied to a generated joker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JokerStickers {
+    pub eternal: bool,
+    pub perishable: bool,
+    pub rental: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vanilla_stakes_are_cumulative() {
+        assert_eq!(Stake::White.modifiers(), StakeModifiers::default());
+        assert_eq!(Stake::Green.modifiers(), StakeModifiers::default());
+        assert_eq!(Stake::Black.modifiers().eternal_above, Some(0.7));
+        assert_eq!(Stake::Black.modifiers().perishable_range, None);
+        let gold = Stake::Gold.modifiers();
+        assert_eq!(gold.eternal_above, Some(0.7));
+        assert_eq!(gold.perishable_range, Some((0.4, 0.7)));
+        assert_eq!(gold.rental_above, Some(0.7));
+        assert_eq!(Stake::Gold.level(), 8);
+        for stake in Stake::ALL {
+            assert_eq!(stake.modifiers().rarity_weights, [70, 25, 5]);
+        }
+    }
+
+    #[test]
+    fn test_rarity_thresholds_match_game() {
+        let vanilla = StakeModifiers::default();
+        assert_eq!(vanilla.rarity_for_roll(0.7), JokerRarity::Common);
+        assert_eq!(vanilla.rarity_for_roll(0.71), JokerRarity::Uncommon);
+        assert_eq!(vanilla.rarity_for_roll(0.95), JokerRarity::Uncommon);
+        assert_eq!(vanilla.rarity_for_roll(0.951), JokerRarity::Rare);
+    }
+
+    #[test]
+    fn test_sticker_polls() {
+        let orange = Stake::Orange.modifiers();
+        let stickers = |poll, rental| orange.stickers_for_polls(poll, rental);
+        assert!(stickers(0.71, 0.0).eternal && !stickers(0.71, 0.0).perishable);
+        assert!(stickers(0.7, 0.0).perishable && !stickers(0.7, 0.0).eternal);
+        assert_eq!(stickers(0.4, 0.99), JokerStickers::default());
+
+        let gold = Stake::Gold.modifiers();
+        assert!(gold.stickers_for_polls(0.0, 0.71).rental);
+        assert!(
+            !Stake::White
+                .modifiers()
+                .stickers_for_polls(0.99, 0.99)
+                .eternal
+        );
+    }
+}
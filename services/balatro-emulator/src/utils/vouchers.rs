@@ -0,0 +1,209 @@
+//! Voucher effects for the Balatro emulator
+//!
+//! Vouchers are data: each `VoucherDef` lists the `VoucherEffect`s it grants,
+//! and a `VoucherState` tracks which ones the run has redeemed. Generation
+//! helpers query the state, so an empty state always behaves like vanilla and
+//! mods can add vouchers by supplying their own definitions.
+
+use serde::{Deserialize, Serialize};
+
+use super::cards::ModifierOdds;
+use super::shop::ShopModifiers;
+
+/// A single effect a voucher has on generation or the shop
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VoucherEffect {
+    /// Extra card slots in the shop (Overstock, Overstock Plus)
+    ExtraShopSlots(u32),
+    /// Flat reduction of the reroll cost (Reroll Surplus, Reroll Glut)
+    RerollDiscount(u32),
+    /// Edition rate; the game sets rather than stacks it (Hone 2, Glow Up 4)
+    EditionRate(f64),
+    /// The first card of a Celestial pack is the most played hand's planet
+    /// (Telescope)
+    MostPlayedPlanetInCelestial,
+    /// Chance each Arcana pack card is a Spectral instead (Omen Globe)
+    SpectralInArcana(f64),
+}
+
+/// A voucher and the effects it grants once redeemed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoucherDef {
+    /// The game's center key, e.g. `v_telescope`
+    pub id: String,
+    pub effects: Vec<VoucherEffect>,
+}
+
+impl VoucherDef {
+    pub fn new(id: &str, effects: Vec<VoucherEffect>) -> Self {
+        Self {
+            id: id.to_string(),
+            effects,
+        }
+    }
+}
+
+/// Vanilla vouchers whose effects touch generation or shop pricing
+///
+/// Observatory is listed so it can be redeemed alongside Telescope, but it
+/// only changes scoring and so carries no effects here.
+pub fn vanilla_vouchers() -> Vec<VoucherDef> {
+    use VoucherEffect::*;
+    vec![
+        VoucherDef::new("v_overstock_norm", vec![ExtraShopSlots(1)]),
+        VoucherDef::new("v_overstock_plus", vec![ExtraShopSlots(1)]),
+        VoucherDef::new("v_reroll_surplus", vec![RerollDiscount(2)]),
+        VoucherDef::new("v_reroll_glut", vec![RerollDiscount(2)]),
+        VoucherDef::new("v_hone", vec![EditionRate(2.0)]),
+        VoucherDef::new("v_glow_up", vec![EditionRate(4.0)]),
+        VoucherDef::new("v_telescope", vec![MostPlayedPlanetInCelestial]),
+        VoucherDef::new("v_observatory", Vec::new()),
+        VoucherDef::new("v_omen_globe", vec![SpectralInArcana(0.2)]),
+    ]
+}
+
+/// Vouchers redeemed during a run, resolved against a set of definitions
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoucherState {
+    defs: Vec<VoucherDef>,
+    owned: Vec<String>,
+}
+
+impl Default for VoucherState {
+    /// No vouchers owned, vanilla definitions
+    fn default() -> Self {
+        Self::new(vanilla_vouchers())
+    }
+}
+
+impl VoucherState {
+    /// An empty state resolving voucher ids against `defs`
+    pub fn new(defs: Vec<VoucherDef>) -> Self {
+        Self {
+            defs,
+            owned: Vec::new(),
+        }
+    }
+
+    /// Redeem a voucher; false if it is unknown or already owned
+    pub fn redeem(&mut self, id: &str) -> bool {
+        if self.owns(id) || !self.defs.iter().any(|def| def.id == id) {
+            return false;
+        }
+        self.owned.push(id.to_string());
+        true
+    }
+
+    /// Whether the voucher has been redeemed
+    pub fn owns(&self, id: &str) -> bool {
+        self.owned.iter().any(|owned| owned == id)
+    }
+
+    /// Redeemed voucher ids in redemption order
+    pub fn owned(&self) -> &[String] {
+        &self.owned
+    }
+
+    /// Effects of every owned voucher, in redemption order
+    pub fn effects(&self) -> impl Iterator<Item = &VoucherEffect> {
+        self.owned.iter().flat_map(|id| {
+            self.defs
+                .iter()
+                .filter(move |def| &def.id == id)
+                .flat_map(|def| def.effects.iter())
+        })
+    }
+
+    /// `base` with owned slot and reroll vouchers applied
+    pub fn shop_modifiers(&self, base: ShopModifiers) -> ShopModifiers {
+        self.effects().fold(base, |mut modifiers, effect| {
+            match *effect {
+                VoucherEffect::ExtraShopSlots(n) => modifiers.extra_slots += n,
+                VoucherEffect::RerollDiscount(n) => modifiers.reroll_discount += n,
+                _ => {}
+            }
+            modifiers
+        })
+    }
+
+    /// Highest owned edition rate, or 1.0 without an edition voucher
+    pub fn edition_rate(&self) -> f64 {
+        self.effects()
+            .filter_map(|effect| match effect {
+                VoucherEffect::EditionRate(rate) => Some(*rate),
+                _ => None,
+            })
+            .fold(1.0, f64::max)
+    }
+
+    /// `odds` with Foil, Holographic and Polychrome scaled by the edition rate
+    ///
+    /// Negative is unaffected, as in the game.
+    pub fn edition_odds(&self, odds: &ModifierOdds) -> ModifierOdds {
+        let rate = self.edition_rate();
+        ModifierOdds {
+            foil_chance: odds.foil_chance * rate,
+            holographic_chance: odds.holographic_chance * rate,
+            polychrome_chance: odds.polychrome_chance * rate,
+            ..*odds
+        }
+    }
+
+    /// Whether Celestial packs lead with the most played hand's planet
+    pub fn most_played_planet_in_celestial(&self) -> bool {
+        self.effects()
+            .any(|effect| *effect == VoucherEffect::MostPlayedPlanetInCelestial)
+    }
+
+    /// Chance an Arcana pack card is replaced by a Spectral, if any
+    pub fn spectral_in_arcana(&self) -> Option<f64> {
+        self.effects().find_map(|effect| match effect {
+            VoucherEffect::SpectralInArcana(chance) => Some(*chance),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::shop::{reroll_cost, slot_count};
+
+    #[test]
+    fn test_shop_and_edition_effects() {
+        let mut vouchers = VoucherState::default();
+        assert_eq!(vouchers.edition_rate(), 1.0);
+        assert!(!vouchers.redeem("v_unknown"));
+
+        assert!(vouchers.redeem("v_overstock_norm"));
+        assert!(!vouchers.redeem("v_overstock_norm"));
+        assert!(vouchers.redeem("v_reroll_surplus"));
+        assert!(vouchers.redeem("v_hone"));
+        assert!(vouchers.redeem("v_glow_up"));
+
+        let shop = vouchers.shop_modifiers(ShopModifiers::default());
+        assert_eq!(slot_count(1, &shop), 3);
+        assert_eq!(reroll_cost(5, 0, &shop), 3);
+
+        assert_eq!(vouchers.edition_rate(), 4.0);
+        let odds = vouchers.edition_odds(&ModifierOdds::default().with_base_editions());
+        assert_eq!(odds.foil_chance, 0.08);
+        assert_eq!(odds.negative_chance, 0.0);
+    }
+
+    #[test]
+    fn test_modded_definitions() {
+        let mut vouchers = VoucherState::new(vec![VoucherDef::new(
+            "v_mod_warehouse",
+            vec![VoucherEffect::ExtraShopSlots(3)],
+        )]);
+        assert!(!vouchers.redeem("v_overstock_norm"));
+        assert!(vouchers.redeem("v_mod_warehouse"));
+        assert_eq!(
+            vouchers
+                .shop_modifiers(ShopModifiers::default())
+                .extra_slots,
+            3
+        );
+    }
+}
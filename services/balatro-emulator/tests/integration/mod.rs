@@ -1,3 +1,4 @@
 //! Integration tests for the Balatro emulator
 
 pub mod test_rng_integration;
+pub mod test_seed_stability;
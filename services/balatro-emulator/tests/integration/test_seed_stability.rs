@@ -0,0 +1,143 @@
+//! Pinned outputs locking the RNG determinism contract
+//!
+//! Saved runs, replays and RNG dumps all depend on `hash_seed`,
+//! `stable_hash` and the way `pseudoseed` combines the base seed with a
+//! key. These values must NEVER change without bumping
+//! `PSEUDORANDOM_STATE_VERSION` and noting the break: a failure here means
+//! old seeds no longer reproduce their runs.
+
+use balatro_emulator::utils::{stable_hash, BalatroRng, SeedType};
+
+/// Seed, its base seed, then in order: `pseudoseed("shuffle")` twice,
+/// `get_card_rng("rarity", 1, None)`, `get_card_rng("front", 2, Some("sho"))`,
+/// `get_shop_rng(1, 0)` and `get_joker_rng("j_joker", 1)`
+type Pinned = (SeedType, u64, [u64; 6]);
+
+fn pinned() -> Vec<Pinned> {
+    vec![
+        (
+            SeedType::Numeric(0),
+            13646096770106105413,
+            [
+                4959706530366828229,
+                6410317496748402177,
+                13553536514299268028,
+                458165213983755881,
+                14426220948772763987,
+                13954114404196393175,
+            ],
+        ),
+        (
+            SeedType::Numeric(u64::MAX),
+            3395815149532668813,
+            [
+                18266030349563960335,
+                2650869278340508803,
+                7646935844070370822,
+                13905254753690663364,
+                16122983783533845745,
+                9967214554971141976,
+            ],
+        ),
+        (
+            SeedType::Numeric(12345),
+            11114430376347968748,
+            [
+                2137096670766628344,
+                11529246818542936699,
+                18394023325134570878,
+                4464907441946331053,
+                13179238730585186354,
+                3980870208223398048,
+            ],
+        ),
+        (
+            SeedType::from(""),
+            3476900567878811119,
+            [
+                1236686728575674167,
+                2983189441638485526,
+                17595302059703154069,
+                13251866466746430247,
+                211521465203175648,
+                4536943162077080722,
+            ],
+        ),
+        (
+            SeedType::from("TUTORIAL"),
+            2765539982484055707,
+            [
+                1590411042931114701,
+                7327503800963628922,
+                2047644989146224219,
+                17357919178472833506,
+                12423612313725814553,
+                13583083457699546078,
+            ],
+        ),
+        (
+            SeedType::from("🃏🎲"),
+            2186889026424833443,
+            [
+                12316681536717969122,
+                3150302642350149842,
+                938995362605683683,
+                1631616918505867798,
+                18438043866031211237,
+                10037372673984201945,
+            ],
+        ),
+        (
+            SeedType::from("Ñandú ζ 牌"),
+            102456787218693626,
+            [
+                17910125504417504233,
+                10410294459309393432,
+                13761978730546897304,
+                11372827973674638083,
+                14092676137463120471,
+                16223708437782396593,
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_base_seeds_are_pinned() {
+    for (seed, base_seed, _) in pinned() {
+        let rng = BalatroRng::new(seed.clone());
+        assert_eq!(rng.state().base_seed(), base_seed, "base seed of {seed:?}");
+    }
+}
+
+#[test]
+fn test_seed_derivations_are_pinned() {
+    for (seed, _, expected) in pinned() {
+        let mut rng = BalatroRng::new(seed.clone());
+        let actual = [
+            rng.pseudoseed("shuffle"),
+            rng.pseudoseed("shuffle"),
+            rng.get_card_rng("rarity", 1, None),
+            rng.get_card_rng("front", 2, Some("sho")),
+            rng.get_shop_rng(1, 0),
+            rng.get_joker_rng("j_joker", 1),
+        ];
+        assert_eq!(actual, expected, "derived seeds of {seed:?}");
+    }
+}
+
+#[test]
+fn test_pseudohash_is_pinned() {
+    let rng = BalatroRng::new(SeedType::Numeric(0));
+    // A string seed's base seed is its stable hash
+    assert_eq!(
+        stable_hash(&SeedType::from("TUTORIAL")),
+        2765539982484055707
+    );
+    assert_eq!(stable_hash(&SeedType::from("")), 3476900567878811119);
+    assert_eq!(rng.pseudohash("TUTORIAL"), 0.41795211369071694);
+    assert_eq!(
+        rng.pseudohash_u64("TUTORIAL"),
+        0.41795211369071694f64.to_bits()
+    );
+}
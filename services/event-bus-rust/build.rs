@@ -47,6 +47,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Verify the proto files exist
     let balatro_proto = proto_root.join("balatro_events.proto");
     let resource_proto = proto_root.join("resource_coordinator.proto");
+    let event_bus_proto = proto_root.join("event_bus.proto");
 
     if !balatro_proto.exists() {
         eprintln!(
@@ -64,10 +65,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if !resource_proto.exists() {
         panic!("Proto file not found: {resource_proto:?}");
     }
+    if !event_bus_proto.exists() {
+        panic!("Proto file not found: {event_bus_proto:?}");
+    }
 
     // Tell cargo to recompile if proto files change
     println!("cargo:rerun-if-changed={}", balatro_proto.display());
     println!("cargo:rerun-if-changed={}", resource_proto.display());
+    println!("cargo:rerun-if-changed={}", event_bus_proto.display());
+
+    // Descriptor set served by gRPC reflection
+    let descriptor_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("jimbot_descriptor.bin");
 
     // Compile protocol buffers
     tonic_build::configure()
@@ -75,10 +83,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build_server(true)
         // Disable clippy warnings for generated code
         .emit_rerun_if_changed(false)
+        .file_descriptor_set_path(descriptor_path)
         .compile(
             &[
                 balatro_proto.to_str().unwrap(),
                 resource_proto.to_str().unwrap(),
+                event_bus_proto.to_str().unwrap(),
             ],
             &[proto_root.to_str().unwrap()],
         )?;
@@ -0,0 +1,276 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use balatro_emulator::utils::siphash::StableHasher;
+use std::{
+    hash::Hasher,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::{AccessLogConfig, AccessLogField};
+
+/// Header carrying the request id; one is generated if the client sends none
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Access log settings shared by every request
+#[derive(Clone)]
+pub struct AccessLog {
+    config: Arc<AccessLogConfig>,
+    api_key_header: Option<String>,
+    /// `key_id_secret`, or a random one for this process when unset
+    key_id_secret: Arc<str>,
+    seen: Arc<AtomicU64>,
+}
+
+impl AccessLog {
+    pub fn new(config: &AccessLogConfig, api_key_header: Option<String>) -> Self {
+        let key_id_secret = config
+            .key_id_secret
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        Self {
+            config: Arc::new(config.clone()),
+            api_key_header,
+            key_id_secret: key_id_secret.into(),
+            seen: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn includes(&self, field: AccessLogField) -> bool {
+        self.config.fields.contains(&field)
+    }
+
+    /// Whether this request is logged under `sample_rate`
+    ///
+    /// Counter-based rather than random, so logged requests are spread evenly
+    /// and a rate of 1.0 never skips one.
+    fn sampled(&self) -> bool {
+        let n = self.seen.fetch_add(1, Ordering::Relaxed) as f64;
+        let rate = self.config.sample_rate;
+        ((n + 1.0) * rate).floor() > (n * rate).floor()
+    }
+}
+
+/// Identifier for an API key that is safe to log
+///
+/// A SipHash of the key keyed by `secret`, so lines from the same client can
+/// be correlated without the key reaching the logs, and without the secret
+/// the ids can't be used to brute-force short keys offline. The algorithm is
+/// pinned, so ids stay the same across Rust releases.
+pub fn api_key_id(secret: &str, key: &str) -> String {
+    let derive = |half: u8| {
+        let mut hasher = StableHasher::new();
+        hasher.write_u8(half);
+        hasher.write_str(secret);
+        hasher.finish()
+    };
+    let mut hasher = StableHasher::with_keys(derive(0), derive(1));
+    hasher.write_str(key);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Middleware writing one structured `access_log` line per request
+///
+/// Also ensures every request carries an `x-request-id`, echoed on the
+/// response. The client IP is the peer address, which requires serving with
+/// `into_make_service_with_connect_info`.
+pub async fn access_log(
+    State(log): State<AccessLog>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = match request.headers().get(REQUEST_ID_HEADER) {
+        Some(value) => value.clone(),
+        None => {
+            let generated = HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .expect("UUIDs are valid header values");
+            request
+                .headers_mut()
+                .insert(REQUEST_ID_HEADER, generated.clone());
+            generated
+        }
+    };
+
+    if !log.config.enabled {
+        let mut response = next.run(request).await;
+        response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+        return response;
+    }
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let key_id = log
+        .api_key_header
+        .as_deref()
+        .and_then(|header| request.headers().get(header))
+        .and_then(|value| value.to_str().ok())
+        .map(|key| api_key_id(&log.key_id_secret, key));
+    let start = Instant::now();
+
+    let mut response = next.run(request).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    if log.sampled() {
+        let field = |f: AccessLogField| log.includes(f);
+        info!(
+            target: "access_log",
+            method = field(AccessLogField::Method).then_some(method.as_str()),
+            path = field(AccessLogField::Path).then_some(path.as_str()),
+            status = field(AccessLogField::Status).then_some(response.status().as_u16()),
+            latency_ms = field(AccessLogField::LatencyMs).then_some(latency_ms),
+            client_ip = client_ip.as_deref().filter(|_| field(AccessLogField::ClientIp)),
+            request_id = request_id
+                .to_str()
+                .ok()
+                .filter(|_| field(AccessLogField::RequestId)),
+            api_key_id = key_id.as_deref().filter(|_| field(AccessLogField::ApiKeyId)),
+            "request completed"
+        );
+    }
+
+    response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use std::{io::Write, sync::Mutex};
+    use tower::ServiceExt;
+
+    /// Shared buffer the test subscriber writes log lines into
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            self.raw()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+
+        fn raw(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    const SECRET: &str = "log-secret";
+
+    fn app(config: AccessLogConfig) -> Router {
+        Router::new()
+            .route("/api/v1/events", post(|| async {}))
+            .layer(axum::middleware::from_fn_with_state(
+                AccessLog::new(&config, Some("X-API-Key".to_string())),
+                access_log,
+            ))
+    }
+
+    fn request() -> Request {
+        let mut request = Request::post("/api/v1/events")
+            .header("X-API-Key", "secret-key-123")
+            .header(REQUEST_ID_HEADER, "req-42")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 7], 4321))));
+        request
+    }
+
+    fn capture() -> (Captured, tracing::subscriber::DefaultGuard) {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+        (captured, tracing::subscriber::set_default(subscriber))
+    }
+
+    #[tokio::test]
+    async fn test_request_logged_with_fields_and_redacted_key() {
+        let (captured, _guard) = capture();
+
+        let response = app(AccessLogConfig {
+            key_id_secret: Some(SECRET.to_string()),
+            ..AccessLogConfig::default()
+        })
+        .oneshot(request())
+        .await
+        .unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+
+        let lines = captured.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["target"], "access_log");
+        let fields = &lines[0]["fields"];
+        assert_eq!(fields["method"], "POST");
+        assert_eq!(fields["path"], "/api/v1/events");
+        assert_eq!(fields["status"], 200);
+        assert!(fields["latency_ms"].is_u64());
+        assert_eq!(fields["client_ip"], "10.0.0.7");
+        assert_eq!(fields["request_id"], "req-42");
+        assert_eq!(fields["api_key_id"], api_key_id(SECRET, "secret-key-123"));
+        assert!(!captured.raw().contains("secret-key-123"));
+    }
+
+    #[tokio::test]
+    async fn test_field_selection_and_sampling() {
+        let (captured, _guard) = capture();
+        let app = app(AccessLogConfig {
+            enabled: true,
+            fields: vec![AccessLogField::Method, AccessLogField::Status],
+            sample_rate: 0.5,
+            key_id_secret: None,
+        });
+
+        for _ in 0..4 {
+            app.clone().oneshot(request()).await.unwrap();
+        }
+
+        let lines = captured.lines();
+        assert_eq!(lines.len(), 2);
+        let fields = lines[0]["fields"].as_object().unwrap();
+        assert_eq!(fields["method"], "POST");
+        assert_eq!(fields["status"], 200);
+        for absent in ["path", "client_ip", "request_id", "api_key_id"] {
+            assert!(!fields.contains_key(absent), "{absent} should be omitted");
+        }
+    }
+
+    #[test]
+    fn test_api_key_id_depends_on_secret() {
+        // Pinned: ids must keep correlating across toolchain upgrades
+        let id = api_key_id(SECRET, "secret-key-123");
+        assert_eq!(id, "2a165e4aef888924");
+        assert_ne!(api_key_id("other", "secret-key-123"), id);
+        assert_ne!(api_key_id(SECRET, "secret-key-124"), id);
+    }
+}
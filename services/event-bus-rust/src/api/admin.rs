@@ -0,0 +1,188 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use std::sync::atomic::Ordering;
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+use crate::{
+    api::models::{ApiResponse, ReprocessResponse},
+    AppState,
+};
+
+/// Whether the request carries the configured admin API key
+///
+/// Admin endpoints are disabled entirely when no admin key is configured. The
+/// key is compared in constant time so response timing doesn't reveal how
+/// much of a guess was right.
+fn is_admin(state: &AppState, headers: &HeaderMap) -> bool {
+    let security = &state.config.security;
+    let Some(expected) = security.admin_api_key.as_deref() else {
+        return false;
+    };
+    let header = security.api_key_header.as_deref().unwrap_or("X-API-Key");
+
+    headers
+        .get(header)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|key| bool::from(key.as_bytes().ct_eq(expected.as_bytes())))
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ApiResponse::error_with_code(
+            "unauthorized",
+            "A valid admin API key is required".to_string(),
+        )),
+    )
+        .into_response()
+}
+
+/// Put the bus into drain mode: reject new publishes, keep delivering queued events
+pub async fn drain(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_admin(&state, &headers) {
+        warn!("Rejected unauthorized drain request");
+        return unauthorized();
+    }
+
+    if !state.draining.swap(true, Ordering::SeqCst) {
+        info!("Entering drain mode: new publishes will be rejected");
+    }
+
+    Json(ApiResponse {
+        message: Some("Draining".to_string()),
+        ..ApiResponse::ok()
+    })
+    .into_response()
+}
+
+/// Leave drain mode and accept new publishes again
+pub async fn resume(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_admin(&state, &headers) {
+        warn!("Rejected unauthorized resume request");
+        return unauthorized();
+    }
+
+    if state.draining.swap(false, Ordering::SeqCst) {
+        info!("Leaving drain mode: accepting new publishes");
+    }
+
+    Json(ApiResponse {
+        message: Some("Accepting events".to_string()),
+        ..ApiResponse::ok()
+    })
+    .into_response()
+}
+
+/// Route the oldest dead letters again, within the configured retry budget
+pub async fn reprocess_dead_letters(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_admin(&state, &headers) {
+        warn!("Rejected unauthorized dead-letter reprocess request");
+        return unauthorized();
+    }
+
+    let outcome = state.router.reprocess_dead_letters().await;
+    Json(ReprocessResponse {
+        reprocessed: outcome.reprocessed,
+        failed: outcome.failed,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::handlers::handle_single_event;
+    use crate::api::health_aggregate::aggregate_health;
+    use crate::api::models::JsonEvent;
+    use crate::config::AppConfig;
+    use crate::routing::EventRouter;
+    use axum::http::HeaderValue;
+    use std::sync::Arc;
+
+    fn heartbeat() -> Json<JsonEvent> {
+        Json(JsonEvent {
+            event_type: "HEARTBEAT".to_string(),
+            source: "test".to_string(),
+            timestamp: None,
+            version: None,
+            payload: serde_json::json!({}),
+            headers: None,
+            correlation_id: None,
+            causation_id: None,
+        })
+    }
+
+    fn admin_state() -> AppState {
+        let mut config = AppConfig::default();
+        config.security.admin_api_key = Some("secret".to_string());
+        AppState {
+            router: Arc::new(EventRouter::new()),
+            config: Arc::new(config),
+            draining: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_rejects_new_publishes_but_delivers_queued() {
+        let state = admin_state();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        state
+            .router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+
+        let response = handle_single_event(State(state.clone()), Ok(heartbeat())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-API-Key", HeaderValue::from_static("secret"));
+        let response = drain(State(state.clone()), headers).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = handle_single_event(State(state.clone()), Ok(heartbeat())).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // The event queued before draining is still delivered, and nothing after it
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+
+        // Load balancers on the aggregate probe stop sending traffic too
+        let (status, Json(health)) = aggregate_health(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health.overall, "unhealthy");
+        assert_eq!(health.components[0].status, "draining");
+    }
+
+    #[tokio::test]
+    async fn test_drain_requires_admin_key() {
+        let state = admin_state();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-API-Key", HeaderValue::from_static("wrong"));
+        let response = drain(State(state.clone()), headers).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(!state.draining.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_resume_accepts_publishes_again() {
+        let state = admin_state();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-API-Key", HeaderValue::from_static("secret"));
+        drain(State(state.clone()), headers.clone()).await;
+
+        let mut wrong = HeaderMap::new();
+        wrong.insert("X-API-Key", HeaderValue::from_static("wrong"));
+        let response = resume(State(state.clone()), wrong).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(state.draining.load(Ordering::SeqCst));
+
+        let response = resume(State(state.clone()), headers).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = handle_single_event(State(state), Ok(heartbeat())).await;
+        assert_ne!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::{api::models::ApiResponse, metrics::EventMetrics};
+
+/// Bound on concurrently processing REST requests
+#[derive(Clone)]
+pub struct InFlightLimit {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+}
+
+impl InFlightLimit {
+    pub fn new(max: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max)),
+            max,
+        }
+    }
+
+    fn in_flight(&self) -> usize {
+        self.max - self.semaphore.available_permits()
+    }
+}
+
+/// Middleware shedding requests beyond the in-flight limit with 503
+///
+/// Requests are rejected immediately rather than queued, so a spike can't
+/// pile up unbounded work behind the limit.
+pub async fn limit_in_flight(
+    State(limit): State<InFlightLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Ok(permit) = limit.semaphore.clone().try_acquire_owned() else {
+        warn!(
+            "Rejecting request: {} requests already in flight",
+            limit.max
+        );
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, "1")],
+            Json(ApiResponse::error_with_code(
+                "overloaded",
+                "Too many requests in flight, retry shortly".to_string(),
+            )),
+        )
+            .into_response();
+    };
+
+    EventMetrics::update_in_flight_requests(limit.in_flight() as f64);
+    let response = next.run(request).await;
+    drop(permit);
+    EventMetrics::update_in_flight_requests(limit.in_flight() as f64);
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_excess_requests_shed_until_capacity_recovers() {
+        let limit = InFlightLimit::new(1);
+        let release = Arc::new(Notify::new());
+        let handler_release = release.clone();
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(move || {
+                    let release = handler_release.clone();
+                    async move { release.notified().await }
+                }),
+            )
+            .route("/fast", get(|| async {}))
+            .layer(axum::middleware::from_fn_with_state(
+                limit.clone(),
+                limit_in_flight,
+            ));
+
+        let request = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+
+        let slow = tokio::spawn(app.clone().oneshot(request("/slow")));
+        // Let the slow request take the only permit
+        while limit.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let response = app.clone().oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        release.notify_one();
+        assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+
+        let response = app.oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -1,26 +1,91 @@
 use axum::{
     extract::{rejection::JsonRejection, State},
-    response::Json,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
 };
-use tracing::{debug, error, info};
+use std::sync::atomic::Ordering;
+use tracing::{debug, error, info, warn};
 
 use crate::{
-    api::models::{ApiResponse, BatchEventRequest, JsonEvent},
-    proto::converter::json_to_proto_event,
+    api::models::{ApiResponse, BatchEventRequest, DeadLetterEntry, DeadLetterResponse, JsonEvent},
+    proto::Event,
+    proto::{converter::json_to_proto_event, EventType},
+    routing::dead_letter::{self, DEAD_LETTER_REASON_KEY},
     AppState,
 };
 
+/// 200 with the delivery count, or 202 when nobody was subscribed
+fn routed_response(routed: usize) -> Response {
+    let status = if routed == 0 {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(ApiResponse::routed(routed))).into_response()
+}
+
+/// 503 response while the bus is draining, telling publishers to go elsewhere
+fn draining_rejection(state: &AppState) -> Option<Response> {
+    if !state.draining.load(Ordering::SeqCst) {
+        return None;
+    }
+
+    Some(
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::error_with_code(
+                "draining",
+                "Event bus is draining and not accepting new events".to_string(),
+            )),
+        )
+            .into_response(),
+    )
+}
+
+/// 403 response for an event whose source may not publish its type
+fn source_rule_rejection(state: &AppState, event: &JsonEvent) -> Option<Response> {
+    if state
+        .config
+        .security
+        .is_source_allowed(&event.source, &event.event_type)
+    {
+        return None;
+    }
+
+    warn!(
+        "Rejected event: source '{}' may not publish {}",
+        event.source, event.event_type
+    );
+    Some(
+        (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::error_with_code(
+                "source_not_allowed",
+                format!(
+                    "Source '{}' is not allowed to publish {}",
+                    event.source, event.event_type
+                ),
+            )),
+        )
+            .into_response(),
+    )
+}
+
 /// Handle single event endpoint with custom JSON extraction
 pub async fn handle_single_event(
     State(state): State<AppState>,
     event_result: Result<Json<JsonEvent>, JsonRejection>,
-) -> Json<ApiResponse> {
+) -> Response {
+    if let Some(rejection) = draining_rejection(&state) {
+        return rejection;
+    }
+
     // Handle JSON parsing errors (including missing required fields)
     let event = match event_result {
         Ok(Json(event)) => event,
         Err(err) => {
             error!("Failed to parse event JSON: {}", err);
-            return Json(ApiResponse::error(format!("Invalid JSON: {err}")));
+            return Json(ApiResponse::error(format!("Invalid JSON: {err}"))).into_response();
         }
     };
 
@@ -29,21 +94,31 @@ pub async fn handle_single_event(
         event.event_type, event.source
     );
 
+    if let Some(rejection) = source_rule_rejection(&state, &event) {
+        return rejection;
+    }
+
     // Convert JSON to Protocol Buffer
     match json_to_proto_event(event) {
         Ok(proto_event) => {
             // Route the event
-            if let Err(e) = state.router.route_event(proto_event).await {
-                error!("Failed to route event: {}", e);
-                return Json(ApiResponse::error(format!("Routing failed: {e}")));
+            match state.router.try_route_event(proto_event).await {
+                Ok(outcome) => {
+                    info!(
+                        "Successfully processed single event ({} subscribers)",
+                        outcome.routed
+                    );
+                    routed_response(outcome.routed)
+                }
+                Err(e) => {
+                    error!("Failed to route event: {}", e);
+                    Json(ApiResponse::error(format!("Routing failed: {e}"))).into_response()
+                }
             }
-
-            info!("Successfully processed single event");
-            Json(ApiResponse::ok())
         }
         Err(e) => {
             error!("Failed to convert JSON to protobuf: {}", e);
-            Json(ApiResponse::error(format!("Invalid event format: {e}")))
+            Json(ApiResponse::error(format!("Invalid event format: {e}"))).into_response()
         }
     }
 }
@@ -52,31 +127,45 @@ pub async fn handle_single_event(
 pub async fn handle_batch_events(
     State(state): State<AppState>,
     batch_result: Result<Json<BatchEventRequest>, JsonRejection>,
-) -> Json<ApiResponse> {
+) -> Response {
+    if let Some(rejection) = draining_rejection(&state) {
+        return rejection;
+    }
+
     // Handle JSON parsing errors
     let batch = match batch_result {
         Ok(Json(batch)) => batch,
         Err(err) => {
             error!("Failed to parse batch JSON: {}", err);
-            return Json(ApiResponse::error(format!("Invalid JSON: {err}")));
+            return Json(ApiResponse::error(format!("Invalid JSON: {err}"))).into_response();
         }
     };
     let event_count = batch.events.len();
     info!("Received batch with {} events", event_count);
 
+    // A single spoofed event rejects the whole batch
+    for event in &batch.events {
+        if let Some(rejection) = source_rule_rejection(&state, event) {
+            return rejection;
+        }
+    }
+
     let mut processed = 0;
+    let mut routed = 0;
     let mut errors = Vec::new();
 
     for (idx, event) in batch.events.into_iter().enumerate() {
         match json_to_proto_event(event) {
-            Ok(proto_event) => {
-                if let Err(e) = state.router.route_event(proto_event).await {
+            Ok(proto_event) => match state.router.try_route_event(proto_event).await {
+                Ok(outcome) => {
+                    processed += 1;
+                    routed += outcome.routed;
+                }
+                Err(e) => {
                     error!("Failed to route event {}: {}", idx, e);
                     errors.push(format!("Event {idx}: {e}"));
-                } else {
-                    processed += 1;
                 }
-            }
+            },
             Err(e) => {
                 error!("Failed to convert event {} to protobuf: {}", idx, e);
                 errors.push(format!("Event {idx}: Invalid format - {e}"));
@@ -86,7 +175,7 @@ pub async fn handle_batch_events(
 
     if errors.is_empty() {
         info!("Successfully processed all {} events", processed);
-        Json(ApiResponse::ok())
+        routed_response(routed)
     } else {
         let error_msg = format!(
             "Processed {}/{} events. Errors: {}",
@@ -94,6 +183,184 @@ pub async fn handle_batch_events(
             event_count,
             errors.join(", ")
         );
-        Json(ApiResponse::error(error_msg))
+        Json(ApiResponse::error(error_msg)).into_response()
+    }
+}
+
+/// List dead-lettered events without removing them
+pub async fn list_dead_letters(State(state): State<AppState>) -> Json<DeadLetterResponse> {
+    dead_letter_listing(state.router.dead_letters())
+}
+
+/// List dead letters that failed every reprocess attempt
+pub async fn list_permanent_failures(State(state): State<AppState>) -> Json<DeadLetterResponse> {
+    dead_letter_listing(state.router.permanent_failures())
+}
+
+fn dead_letter_listing(events: Vec<Event>) -> Json<DeadLetterResponse> {
+    let events: Vec<DeadLetterEntry> = events
+        .into_iter()
+        .map(|mut event| {
+            let event_type = match EventType::try_from(event.r#type) {
+                // Same names as the ingest `type` field, e.g. `HEARTBEAT`
+                Ok(event_type) if event_type != EventType::Unspecified => event_type
+                    .as_str_name()
+                    .trim_start_matches("EVENT_TYPE_")
+                    .to_string(),
+                _ => event.raw_type.clone(),
+            };
+            DeadLetterEntry {
+                attempts: dead_letter::attempts(&event),
+                reason: event
+                    .metadata
+                    .remove(DEAD_LETTER_REASON_KEY)
+                    .unwrap_or_default(),
+                event_id: event.event_id,
+                event_type,
+                source: event.source,
+                timestamp: event.timestamp,
+                correlation_id: event.correlation_id,
+            }
+        })
+        .collect();
+
+    Json(DeadLetterResponse {
+        count: events.len(),
+        events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AppConfig, SourceRule};
+    use crate::routing::EventRouter;
+    use std::sync::Arc;
+
+    fn state_with_rules(rules: Vec<SourceRule>) -> AppState {
+        let mut config = AppConfig::default();
+        config.security.source_rules = rules;
+        AppState {
+            router: Arc::new(EventRouter::new()),
+            config: Arc::new(config),
+            draining: Default::default(),
+        }
+    }
+
+    fn event(event_type: &str, source: &str) -> JsonEvent {
+        JsonEvent {
+            event_type: event_type.to_string(),
+            source: source.to_string(),
+            timestamp: None,
+            version: None,
+            payload: serde_json::json!({}),
+            headers: None,
+            correlation_id: None,
+            causation_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_rules_enforced_at_ingest() {
+        let state = state_with_rules(vec![SourceRule {
+            source: "monitor".to_string(),
+            event_types: vec!["HEARTBEAT".to_string()],
+        }]);
+
+        let response = handle_single_event(
+            State(state.clone()),
+            Ok(Json(event("HEARTBEAT", "monitor"))),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = handle_single_event(
+            State(state.clone()),
+            Ok(Json(event("MONEY_CHANGED", "monitor"))),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let batch = BatchEventRequest {
+            events: vec![
+                event("HEARTBEAT", "monitor"),
+                event("MONEY_CHANGED", "monitor"),
+            ],
+        };
+        let response = handle_batch_events(State(state), Ok(Json(batch))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_empty_source_rules_allow_everything() {
+        let state = state_with_rules(Vec::new());
+        let mut money = event("MONEY_CHANGED", "anyone");
+        money.payload = serde_json::json!({ "old_value": 4, "new_value": 9 });
+        let response = handle_single_event(State(state), Ok(Json(money))).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_event_is_accepted_with_zero_count() {
+        let state = state_with_rules(Vec::new());
+
+        let response =
+            handle_single_event(State(state.clone()), Ok(Json(event("HEARTBEAT", "test")))).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["routed"], 0);
+
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        state
+            .router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+        let response =
+            handle_single_event(State(state), Ok(Json(event("HEARTBEAT", "test")))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["routed"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_lists_unrouted_events() {
+        let mut config = AppConfig::default();
+        config.routing.dead_letter_enabled = true;
+        config.routing.dead_letter_max_reprocess_attempts = 1;
+        let state = AppState {
+            router: Arc::new(EventRouter::new_with_config(config.routing.clone())),
+            config: Arc::new(config),
+            draining: Default::default(),
+        };
+
+        let response =
+            handle_single_event(State(state.clone()), Ok(Json(event("HEARTBEAT", "test")))).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let Json(listing) = list_dead_letters(State(state.clone())).await;
+        assert_eq!(listing.count, 1);
+        assert_eq!(listing.events[0].event_type, "HEARTBEAT");
+        assert_eq!(listing.events[0].reason, "no_subscribers");
+
+        // Listing does not consume the queue
+        assert_eq!(state.router.dead_letters().len(), 1);
+        assert_eq!(listing.events[0].attempts, 0);
+
+        // Still undeliverable, so one reprocess uses up the budget
+        state.router.reprocess_dead_letters().await;
+
+        let Json(listing) = list_dead_letters(State(state.clone())).await;
+        assert_eq!(listing.count, 0);
+        let Json(failed) = list_permanent_failures(State(state)).await;
+        assert_eq!(failed.count, 1);
+        assert_eq!(failed.events[0].attempts, 1);
+        assert_eq!(failed.events[0].reason, "no_subscribers");
     }
 }
@@ -1,12 +1,14 @@
-use axum::response::Json;
+use axum::{extract::State, http::StatusCode, response::Json};
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::api::models::HealthResponse;
+use crate::{api::models::HealthResponse, AppState};
 
 static START_TIME: std::sync::OnceLock<u64> = std::sync::OnceLock::new();
 
-pub async fn health_check() -> Json<HealthResponse> {
+/// Liveness check; reports 503 "draining" once drain mode is on
+pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
     let start_time = START_TIME.get_or_init(|| {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -23,12 +25,19 @@ pub async fn health_check() -> Json<HealthResponse> {
     metadata.insert("service".to_string(), "event-bus-rust".to_string());
     metadata.insert("protocol_version".to_string(), "1.0".to_string());
 
-    Json(HealthResponse {
-        status: "healthy".to_string(),
+    let (code, status) = if state.draining.load(Ordering::SeqCst) {
+        (StatusCode::SERVICE_UNAVAILABLE, "draining")
+    } else {
+        (StatusCode::OK, "healthy")
+    };
+
+    let response = Json(HealthResponse {
+        status: status.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime_seconds: current_time - start_time,
         metadata,
-    })
+    });
+    (code, response)
 }
 
 pub async fn metrics() -> String {
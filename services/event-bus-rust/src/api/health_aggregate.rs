@@ -0,0 +1,198 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use futures::future::join_all;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::{config::ComponentHealthConfig, AppState};
+
+/// Health of a single probed component
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub status: String,
+    pub critical: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ComponentHealth {
+    fn is_up(&self) -> bool {
+        self.status == "up"
+    }
+}
+
+/// Aggregated health across the event bus and its configured components
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregateHealthResponse {
+    pub overall: String,
+    pub components: Vec<ComponentHealth>,
+}
+
+impl AggregateHealthResponse {
+    /// Whether a critical component is down
+    pub fn is_critical_down(&self) -> bool {
+        self.components.iter().any(|c| c.critical && !c.is_up())
+    }
+}
+
+/// Aggregate health endpoint: 503 if any critical component is down
+pub async fn aggregate_health(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<AggregateHealthResponse>) {
+    let timeout = Duration::from_millis(state.config.health.probe_timeout_ms);
+    let draining = state.draining.load(Ordering::SeqCst);
+    let response = probe_components(&state.config.health.components, timeout, draining).await;
+
+    let status = if response.is_critical_down() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (status, Json(response))
+}
+
+/// Probe every component concurrently and aggregate the results
+///
+/// The event bus itself is always reported first as a critical component,
+/// with status "draining" while drain mode is on so the aggregate reports 503.
+pub async fn probe_components(
+    components: &[ComponentHealthConfig],
+    timeout: Duration,
+    draining: bool,
+) -> AggregateHealthResponse {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .unwrap_or_default();
+
+    let probes = components.iter().map(|c| probe_component(&client, c));
+    let mut results = vec![ComponentHealth {
+        name: "event-bus".to_string(),
+        status: if draining { "draining" } else { "up" }.to_string(),
+        critical: true,
+        latency_ms: 0,
+        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        error: None,
+    }];
+    results.extend(join_all(probes).await);
+
+    let overall = if results.iter().all(ComponentHealth::is_up) {
+        "healthy"
+    } else if results.iter().any(|c| c.critical && !c.is_up()) {
+        "unhealthy"
+    } else {
+        "degraded"
+    };
+
+    AggregateHealthResponse {
+        overall: overall.to_string(),
+        components: results,
+    }
+}
+
+async fn probe_component(
+    client: &reqwest::Client,
+    component: &ComponentHealthConfig,
+) -> ComponentHealth {
+    let start = Instant::now();
+    let result = client.get(&component.url).send().await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let (status, version, error) = match result {
+        Ok(resp) if resp.status().is_success() => {
+            let version = resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body.get("version")?.as_str().map(str::to_string));
+            ("up", version, None)
+        }
+        Ok(resp) => ("down", None, Some(format!("HTTP {}", resp.status()))),
+        Err(e) => {
+            warn!("Health probe for {} failed: {}", component.name, e);
+            ("down", None, Some(e.to_string()))
+        }
+    };
+
+    ComponentHealth {
+        name: component.name.clone(),
+        status: status.to_string(),
+        critical: component.critical,
+        latency_ms,
+        version,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+
+    async fn spawn_health_server() -> String {
+        let app = Router::new().route(
+            "/health",
+            get(|| async { Json(serde_json::json!({"status": "healthy", "version": "9.9.9"})) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}/health")
+    }
+
+    async fn closed_port_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{addr}/health")
+    }
+
+    fn component(name: &str, url: String, critical: bool) -> ComponentHealthConfig {
+        ComponentHealthConfig {
+            name: name.to_string(),
+            url,
+            critical,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_reports_degraded_when_component_down() {
+        let up = spawn_health_server().await;
+        let down = closed_port_url().await;
+
+        let components = vec![
+            component("resource-coordinator", up, true),
+            component("analytics", down.clone(), false),
+        ];
+        let health = probe_components(&components, Duration::from_millis(500), false).await;
+
+        assert_eq!(health.overall, "degraded");
+        assert!(!health.is_critical_down());
+        assert_eq!(health.components.len(), 3);
+        assert_eq!(health.components[1].status, "up");
+        assert_eq!(health.components[1].version.as_deref(), Some("9.9.9"));
+        assert_eq!(health.components[2].status, "down");
+        assert!(health.components[2].error.is_some());
+
+        // A critical component being down makes the aggregate unhealthy
+        let components = vec![component("resource-coordinator", down, true)];
+        let health = probe_components(&components, Duration::from_millis(500), false).await;
+        assert_eq!(health.overall, "unhealthy");
+        assert!(health.is_critical_down());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_healthy_with_no_components() {
+        let health = probe_components(&[], Duration::from_millis(500), false).await;
+        assert_eq!(health.overall, "healthy");
+        assert_eq!(health.components.len(), 1);
+    }
+}
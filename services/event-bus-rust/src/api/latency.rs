@@ -0,0 +1,64 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+use crate::metrics::EventMetrics;
+
+/// Middleware recording request latency per route template and status class
+///
+/// Must be installed with `Router::route_layer` so `MatchedPath` is set;
+/// labelling by template rather than raw path keeps cardinality bounded.
+pub async fn track_latency(matched: Option<MatchedPath>, request: Request, next: Next) -> Response {
+    let route = matched
+        .as_ref()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let status_class = format!("{}xx", response.status().as_u16() / 100);
+    EventMetrics::record_http_request(&route, &status_class, start.elapsed().as_secs_f64());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_latency_recorded_per_route_template() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        metrics::with_local_recorder(&recorder, || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let app = Router::new()
+                    .route("/api/v1/events", post(|| async {}))
+                    .route_layer(axum::middleware::from_fn(track_latency));
+
+                for _ in 0..3 {
+                    let request = Request::post("/api/v1/events").body(Body::empty()).unwrap();
+                    app.clone().oneshot(request).await.unwrap();
+                }
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains(
+                r#"event_bus_http_request_duration_seconds_count{route="/api/v1/events",status_class="2xx"} 3"#
+            ),
+            "{rendered}"
+        );
+    }
+}
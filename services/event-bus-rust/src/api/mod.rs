@@ -1,3 +1,107 @@
+pub mod access_log;
+pub mod admin;
+pub mod concurrency;
 pub mod handlers;
 pub mod health;
+pub mod health_aggregate;
+pub mod latency;
 pub mod models;
+
+use axum::{http::StatusCode, routing::post, Router};
+use std::time::Duration;
+use tower_http::{
+    cors::{Any, CorsLayer},
+    limit::RequestBodyLimitLayer,
+    timeout::TimeoutLayer,
+    trace::TraceLayer,
+};
+
+use crate::AppState;
+use access_log::AccessLog;
+
+/// The REST API with every route and middleware layer, configured from
+/// `state.config`
+///
+/// Serve it with `into_make_service_with_connect_info::<SocketAddr>()` so the
+/// access log can record client IPs.
+pub fn rest_app(state: AppState) -> Router {
+    let config = state.config.clone();
+    let mut rest_app = Router::new()
+        .route("/api/v1/events", post(handlers::handle_single_event))
+        .route("/api/v1/events/batch", post(handlers::handle_batch_events))
+        .route(
+            "/api/v1/dead-letters",
+            axum::routing::get(handlers::list_dead_letters),
+        )
+        .route(
+            "/api/v1/dead-letters/permanent",
+            axum::routing::get(handlers::list_permanent_failures),
+        )
+        .route("/admin/drain", post(admin::drain))
+        .route("/admin/resume", post(admin::resume))
+        .route(
+            "/admin/dead-letters/reprocess",
+            post(admin::reprocess_dead_letters),
+        )
+        .route("/health", axum::routing::get(health::health_check))
+        .route(
+            "/health/aggregate",
+            axum::routing::get(health_aggregate::aggregate_health),
+        );
+
+    // Add metrics endpoint if enabled
+    if config.metrics.enabled {
+        rest_app = rest_app.route(
+            &config.metrics.prometheus_path,
+            axum::routing::get(health::metrics),
+        );
+    }
+
+    // Configure CORS based on settings
+    let cors_layer = if config.server.rest.cors_enabled {
+        if config
+            .server
+            .rest
+            .cors_allowed_origins
+            .contains(&"*".to_string())
+        {
+            CorsLayer::permissive()
+        } else {
+            let origins: Vec<_> = config
+                .server
+                .rest
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| origin.parse().ok())
+                .collect();
+            CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods(Any)
+                .allow_headers(Any)
+        }
+    } else {
+        CorsLayer::new()
+    };
+
+    rest_app
+        .route_layer(axum::middleware::from_fn(latency::track_latency))
+        .layer(axum::middleware::from_fn_with_state(
+            concurrency::InFlightLimit::new(config.server.rest.max_in_flight),
+            concurrency::limit_in_flight,
+        ))
+        .layer(RequestBodyLimitLayer::new(config.server.rest.max_body_size))
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            Duration::from_secs(config.server.rest.request_timeout_secs),
+        ))
+        .layer(cors_layer)
+        .layer(axum::middleware::from_fn_with_state(
+            AccessLog::new(
+                &config.logging.access_log,
+                config.security.api_key_header.clone(),
+            ),
+            access_log::access_log,
+        ))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
@@ -7,11 +7,61 @@ pub struct JsonEvent {
     #[serde(rename = "type")]
     pub event_type: String,
     pub source: String,
+    /// Milliseconds since the epoch; accepted as a number or an RFC 3339
+    /// string and written as RFC 3339
+    #[serde(default, with = "rfc3339_millis")]
     pub timestamp: Option<i64>,
     pub version: Option<i32>,
     pub payload: serde_json::Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
+    /// Groups related events (e.g. one game); defaults to `source`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Id of the event that triggered this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub causation_id: Option<String>,
+}
+
+/// Epoch-millisecond timestamps in JSON, written as RFC 3339
+mod rfc3339_millis {
+    use chrono::{DateTime, SecondsFormat};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        millis: &Option<i64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match millis.map(DateTime::from_timestamp_millis) {
+            None => serializer.serialize_none(),
+            Some(Some(time)) => {
+                serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
+            }
+            // Outside chrono's range; keep the raw value rather than lose it
+            Some(None) => serializer.serialize_i64(millis.unwrap_or_default()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Timestamp {
+            Millis(i64),
+            Rfc3339(String),
+        }
+
+        match Option::<Timestamp>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(Timestamp::Millis(millis)) => Ok(Some(millis)),
+            Some(Timestamp::Rfc3339(text)) => DateTime::parse_from_rfc3339(&text)
+                .map(|time| Some(time.timestamp_millis()))
+                .map_err(|e| {
+                    de::Error::custom(format!("invalid RFC 3339 timestamp `{text}`: {e}"))
+                }),
+        }
+    }
 }
 
 /// Batch event request
@@ -28,6 +78,12 @@ pub struct ApiResponse {
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Machine-readable error code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Number of subscribers the event(s) were delivered to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routed: Option<usize>,
 }
 
 impl ApiResponse {
@@ -36,6 +92,16 @@ impl ApiResponse {
             status: "ok".to_string(),
             message: None,
             error: None,
+            code: None,
+            routed: None,
+        }
+    }
+
+    /// Accepted response carrying the delivery count
+    pub fn routed(routed: usize) -> Self {
+        Self {
+            routed: Some(routed),
+            ..Self::ok()
         }
     }
 
@@ -44,8 +110,50 @@ impl ApiResponse {
             status: "error".to_string(),
             message: None,
             error: Some(msg),
+            code: None,
+            routed: None,
         }
     }
+
+    /// Error response with a machine-readable code
+    pub fn error_with_code(code: &str, msg: String) -> Self {
+        Self {
+            code: Some(code.to_string()),
+            ..Self::error(msg)
+        }
+    }
+}
+
+/// An event held in the dead-letter queue, as listed by `/api/v1/dead-letters`
+/// and `/api/v1/dead-letters/permanent`
+#[derive(Debug, Serialize)]
+pub struct DeadLetterEntry {
+    pub event_id: String,
+    /// Event type name, or the original type string for unknown events
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub source: String,
+    pub timestamp: i64,
+    pub correlation_id: String,
+    /// `no_subscribers`, `max_retries_exhausted`, `retry_backlog_full` or `expired`
+    pub reason: String,
+    /// Times the event has been reprocessed
+    pub attempts: u32,
+}
+
+/// Dead-letter listing response
+#[derive(Debug, Serialize)]
+pub struct DeadLetterResponse {
+    pub count: usize,
+    pub events: Vec<DeadLetterEntry>,
+}
+
+/// Result of `/admin/dead-letters/reprocess`
+#[derive(Debug, Serialize)]
+pub struct ReprocessResponse {
+    pub reprocessed: usize,
+    /// Reprocessed events that were dead-lettered again
+    pub failed: usize,
 }
 
 /// Health check response
@@ -59,6 +167,7 @@ pub struct HealthResponse {
 
 /// Metrics response
 #[derive(Debug, Serialize)]
+#[allow(dead_code)]
 pub struct MetricsResponse {
     pub events_received: u64,
     pub events_processed: u64,
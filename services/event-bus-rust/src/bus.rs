@@ -0,0 +1,130 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::config::RoutingConfig;
+use crate::proto::Event;
+use crate::routing::overflow::{BoundedReceiver, OverflowStrategy};
+use crate::routing::{EventRouter, RouteOutcome, SubscribeError};
+
+/// In-process event bus: the router without the REST or gRPC layers
+///
+/// Components running in the same process publish and subscribe directly,
+/// with the same pattern matching, TTL and interceptor handling as events
+/// arriving over the network. Clones share one router.
+#[derive(Clone)]
+pub struct EventBus {
+    router: Arc<EventRouter>,
+}
+
+impl EventBus {
+    pub fn new(config: RoutingConfig) -> Self {
+        Self::from_router(Arc::new(EventRouter::new_with_config(config)))
+    }
+
+    /// Share a router that network servers may also be routing into
+    pub fn from_router(router: Arc<EventRouter>) -> Self {
+        Self { router }
+    }
+
+    /// Route an event to every matching subscriber
+    pub async fn publish(&self, event: Event) -> Result<RouteOutcome> {
+        self.router.try_route_event(event).await
+    }
+
+    /// Receive every event whose topic matches `pattern`
+    ///
+    /// The subscriber gets a queue of `routing.event_buffer_size` events and
+    /// the `routing.subscriber_overflow` strategy, like a gRPC stream that
+    /// doesn't pick its own. Dropping the receiver ends the subscription.
+    pub fn subscribe(&self, pattern: &str) -> Result<BoundedReceiver, SubscribeError> {
+        self.subscribe_with(pattern, self.router.subscriber_overflow())
+    }
+
+    /// Like `subscribe`, with `strategy` deciding what happens when the
+    /// subscriber falls behind
+    pub fn subscribe_with(
+        &self,
+        pattern: &str,
+        strategy: OverflowStrategy,
+    ) -> Result<BoundedReceiver, SubscribeError> {
+        let (_, rx) = self
+            .router
+            .subscribe_bounded(pattern.to_string(), strategy)?;
+        Ok(rx)
+    }
+
+    pub fn router(&self) -> &Arc<EventRouter> {
+        &self.router
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(RoutingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::EventType;
+
+    #[tokio::test]
+    async fn test_in_process_subscriber_receives_published_events() {
+        let config = RoutingConfig {
+            event_ttl_secs: 60,
+            ..RoutingConfig::default()
+        };
+        let bus = EventBus::new(config);
+        let mut game = bus.subscribe("game.*.*").unwrap();
+        let mut heartbeats = bus.subscribe("system.heartbeat").unwrap();
+
+        let outcome = bus
+            .publish(Event {
+                event_id: "money-1".to_string(),
+                r#type: EventType::MoneyChanged as i32,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(outcome.routed, 1);
+        assert_eq!(game.try_recv().unwrap().event_id, "money-1");
+        assert!(heartbeats.try_recv().is_none());
+
+        // TTL handling is the router's: a stale event is not delivered
+        let outcome = bus
+            .clone()
+            .publish(Event {
+                r#type: EventType::Heartbeat as i32,
+                timestamp: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(outcome.routed, 0);
+        assert!(heartbeats.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stalled_subscriber_queue_is_bounded() {
+        let bus = EventBus::new(RoutingConfig {
+            event_buffer_size: 10,
+            ..RoutingConfig::default()
+        });
+        let mut stalled = bus.subscribe("system.heartbeat").unwrap();
+
+        for i in 0..25 {
+            bus.publish(Event {
+                event_id: i.to_string(),
+                r#type: EventType::Heartbeat as i32,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        // Only the newest `event_buffer_size` events are kept
+        assert_eq!(stalled.len(), 10);
+        assert_eq!(stalled.try_recv().unwrap().event_id, "15");
+    }
+}
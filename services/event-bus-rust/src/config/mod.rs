@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use config::{Config, Environment, File};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 use tokio::sync::mpsc;
@@ -31,6 +32,21 @@ pub struct AppConfig {
     #[validate(nested)]
     pub security: SecurityConfig,
 
+    /// Aggregate health probe configuration
+    #[serde(default)]
+    #[validate(nested)]
+    pub health: HealthAggregateConfig,
+
+    /// On-disk event journal configuration
+    #[serde(default)]
+    #[validate(nested)]
+    pub journal: JournalConfig,
+
+    /// Deterministic fault injection for chaos testing (ignored in prod)
+    #[serde(default)]
+    #[validate(nested)]
+    pub chaos: ChaosConfig,
+
     /// Environment name (dev, staging, prod)
     #[validate(length(min = 1))]
     pub environment: String,
@@ -79,6 +95,15 @@ pub struct RestConfig {
 
     /// Allowed CORS origins
     pub cors_allowed_origins: Vec<String>,
+
+    /// Maximum requests processed concurrently; excess requests get 503
+    #[serde(default = "default_max_in_flight")]
+    #[validate(range(min = 1, max = 100000))]
+    pub max_in_flight: usize,
+}
+
+fn default_max_in_flight() -> usize {
+    1024
 }
 
 /// gRPC configuration
@@ -114,13 +139,59 @@ pub struct RoutingConfig {
     #[validate(range(min = 1, max = 10000))]
     pub max_subscribers_per_topic: usize,
 
+    /// What happens to a streaming subscriber whose `event_buffer_size`
+    /// queue is full, unless it asked for its own overflow strategy
+    #[serde(default)]
+    pub subscriber_overflow: SubscriberOverflow,
+
+    /// Longest `block_timeout_ms` a subscriber may ask for; routing waits
+    /// this long on a full blocking subscriber before giving up on it
+    #[serde(default = "default_max_block_timeout_ms")]
+    #[validate(range(min = 1, max = 60000))]
+    pub max_block_timeout_ms: u64,
+
+    /// Maximum distinct subscribed patterns; new patterns beyond it are rejected
+    #[serde(default = "default_max_topics")]
+    #[validate(range(min = 1))]
+    pub max_topics: usize,
+
+    /// Maximum events waiting on `publish_after` at once
+    #[serde(default = "default_max_scheduled_events")]
+    #[validate(range(min = 1))]
+    pub max_scheduled_events: usize,
+
     /// Event TTL in seconds (0 = no expiry)
     #[validate(range(min = 0, max = 86400))] // Max 24 hours
     pub event_ttl_secs: u64,
 
+    /// Per-topic TTL overrides in seconds, keyed by resolved topic
+    /// (e.g. "system.heartbeat"); topics not listed use `event_ttl_secs`
+    #[serde(default)]
+    #[validate(custom(function = "validate_topic_ttl_overrides"))]
+    pub topic_ttl_overrides: HashMap<String, u64>,
+
     /// Dead letter queue settings
     pub dead_letter_enabled: bool,
 
+    /// Also dead-letter events dropped for exceeding their TTL
+    #[serde(default)]
+    pub dead_letter_expired: bool,
+
+    /// Maximum dead-lettered events kept; the oldest is dropped when full
+    #[serde(default = "default_dead_letter_capacity")]
+    #[validate(range(min = 1))]
+    pub dead_letter_capacity: usize,
+
+    /// Times a dead letter is reprocessed before it is failed permanently
+    #[serde(default = "default_dead_letter_max_reprocess_attempts")]
+    #[validate(range(min = 1, max = 100))]
+    pub dead_letter_max_reprocess_attempts: u32,
+
+    /// Dead letters reprocessed per second across the whole queue
+    #[serde(default = "default_dead_letter_reprocess_rate")]
+    #[validate(range(min = 1))]
+    pub dead_letter_reprocess_rate: u32,
+
     /// Maximum retry attempts for failed events
     #[validate(range(min = 0, max = 10))]
     pub max_retry_attempts: u32,
@@ -130,6 +201,17 @@ pub struct RoutingConfig {
     pub retry_backoff: BackoffConfig,
 }
 
+/// Overflow policy for streaming subscribers that fall behind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriberOverflow {
+    /// Discard the subscriber's oldest queued event to make room
+    #[default]
+    DropOldest,
+    /// Close the subscription; the client has to resubscribe
+    Disconnect,
+}
+
 /// Backoff configuration for retries
 #[derive(Debug, Clone, Deserialize, Serialize, Validate)]
 pub struct BackoffConfig {
@@ -170,6 +252,56 @@ pub struct LoggingConfig {
     /// Number of log files to keep
     #[validate(range(min = 1, max = 100))]
     pub rotation_keep: Option<u32>,
+
+    /// Per-request access log configuration
+    #[serde(default)]
+    #[validate(nested)]
+    pub access_log: AccessLogConfig,
+}
+
+/// Structured per-request access log configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct AccessLogConfig {
+    /// Emit one access log line per REST request
+    pub enabled: bool,
+
+    /// Fields included in each line
+    pub fields: Vec<AccessLogField>,
+
+    /// Fraction of requests logged (1.0 logs every request)
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub sample_rate: f64,
+
+    /// Secret keying the `api_key_id` hash; when unset a random one is
+    /// generated at startup, so ids only correlate within one process
+    #[serde(default)]
+    pub key_id_secret: Option<String>,
+}
+
+/// A field of an access log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogField {
+    Method,
+    Path,
+    Status,
+    LatencyMs,
+    ClientIp,
+    RequestId,
+    /// Hash identifying the API key; the key itself is never logged
+    ApiKeyId,
+}
+
+impl AccessLogField {
+    pub const ALL: [AccessLogField; 7] = [
+        AccessLogField::Method,
+        AccessLogField::Path,
+        AccessLogField::Status,
+        AccessLogField::LatencyMs,
+        AccessLogField::ClientIp,
+        AccessLogField::RequestId,
+        AccessLogField::ApiKeyId,
+    ];
 }
 
 /// Metrics configuration
@@ -184,6 +316,11 @@ pub struct MetricsConfig {
 
     /// Prometheus endpoint path
     pub prometheus_path: String,
+
+    /// Endpoint the metrics snapshot is pushed to every `export_interval_secs`,
+    /// alongside the scrape endpoint (e.g. a Prometheus pushgateway job URL)
+    #[serde(default)]
+    pub push_endpoint: Option<String>,
 }
 
 /// Security configuration
@@ -195,6 +332,10 @@ pub struct SecurityConfig {
     /// API key header name
     pub api_key_header: Option<String>,
 
+    /// Key required on admin endpoints (admin endpoints are disabled if unset)
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
+
     /// Rate limiting configuration
     #[validate(nested)]
     pub rate_limit: Option<RateLimitConfig>,
@@ -202,6 +343,58 @@ pub struct SecurityConfig {
     /// TLS configuration
     #[validate(nested)]
     pub tls: Option<TlsConfig>,
+
+    /// Which sources may publish which event types (empty = allow all)
+    #[serde(default)]
+    #[validate(nested)]
+    pub source_rules: Vec<SourceRule>,
+
+    /// Event metadata keys whose values are redacted before routing
+    #[serde(default)]
+    pub redact_metadata_keys: Vec<String>,
+
+    /// Keys whose values are redacted from free-text payload fields before
+    /// routing
+    #[serde(default)]
+    pub redact_payload_keys: Vec<String>,
+}
+
+/// Event types a source (or source pattern) is allowed to publish
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct SourceRule {
+    /// Exact source name, or a prefix ending in `*` (a lone `*` matches any source)
+    #[validate(length(min = 1))]
+    pub source: String,
+
+    /// Allowed event type names (e.g. "HEARTBEAT"), or "*" for all types
+    pub event_types: Vec<String>,
+}
+
+impl SourceRule {
+    fn matches_source(&self, source: &str) -> bool {
+        match self.source.strip_suffix('*') {
+            Some(prefix) => source.starts_with(prefix),
+            None => self.source == source,
+        }
+    }
+
+    fn allows_type(&self, event_type: &str) -> bool {
+        self.event_types.iter().any(|t| t == "*" || t == event_type)
+    }
+}
+
+impl SecurityConfig {
+    /// Whether `source` may publish events of `event_type`
+    ///
+    /// With no rules configured everything is allowed; otherwise some rule
+    /// matching the source must list the type.
+    pub fn is_source_allowed(&self, source: &str, event_type: &str) -> bool {
+        self.source_rules.is_empty()
+            || self
+                .source_rules
+                .iter()
+                .any(|rule| rule.matches_source(source) && rule.allows_type(event_type))
+    }
 }
 
 /// Rate limiting configuration
@@ -237,6 +430,95 @@ pub struct TlsConfig {
     pub mutual_tls: bool,
 }
 
+/// Aggregate health probe configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct HealthAggregateConfig {
+    /// Components whose `/health` endpoints are probed
+    #[validate(nested)]
+    pub components: Vec<ComponentHealthConfig>,
+
+    /// Per-component probe timeout in milliseconds
+    #[validate(range(min = 10, max = 60000))]
+    pub probe_timeout_ms: u64,
+}
+
+/// A single component probed by the aggregate health endpoint
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct ComponentHealthConfig {
+    /// Component name reported in the aggregate response
+    #[validate(length(min = 1))]
+    pub name: String,
+
+    /// Full URL of the component health endpoint
+    #[validate(length(min = 1))]
+    pub url: String,
+
+    /// Whether this component being down makes the aggregate unhealthy
+    pub critical: bool,
+}
+
+/// On-disk event journal configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct JournalConfig {
+    /// Append every routed event to the journal
+    pub enabled: bool,
+
+    /// Journal file path
+    #[validate(length(min = 1))]
+    pub path: String,
+
+    /// Events buffered before a write+fsync; events routed since the last
+    /// fsync can be lost on a crash
+    #[validate(range(min = 1, max = 100000))]
+    pub buffer_events: usize,
+}
+
+/// Chaos-mode fault injection configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct ChaosConfig {
+    /// Inject faults into routing
+    pub enabled: bool,
+
+    /// Seed making the injected faults reproducible
+    pub seed: u64,
+
+    /// Fraction of events whose delivery is dropped
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub drop_rate: f64,
+
+    /// Fraction of events whose delivery is delayed
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub delay_rate: f64,
+
+    /// Upper bound of an injected delay in milliseconds
+    #[validate(range(min = 0, max = 60000))]
+    pub max_delay_ms: u64,
+}
+
+fn default_max_topics() -> usize {
+    10_000
+}
+
+fn default_max_scheduled_events() -> usize {
+    10_000
+}
+
+fn default_max_block_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_dead_letter_capacity() -> usize {
+    1_000
+}
+
+fn default_dead_letter_max_reprocess_attempts() -> u32 {
+    3
+}
+
+fn default_dead_letter_reprocess_rate() -> u32 {
+    100
+}
+
 /// Validation helpers
 fn validate_log_format(format: &str) -> Result<(), ValidationError> {
     match format {
@@ -245,6 +527,16 @@ fn validate_log_format(format: &str) -> Result<(), ValidationError> {
     }
 }
 
+fn validate_topic_ttl_overrides(overrides: &HashMap<String, u64>) -> Result<(), ValidationError> {
+    if overrides
+        .iter()
+        .any(|(topic, ttl)| topic.is_empty() || *ttl > 86400)
+    {
+        return Err(ValidationError::new("invalid_topic_ttl_override"));
+    }
+    Ok(())
+}
+
 /// Configuration manager with hot-reload support
 pub struct ConfigManager {
     config: Arc<RwLock<AppConfig>>,
@@ -370,6 +662,9 @@ impl Default for AppConfig {
             logging: LoggingConfig::default(),
             metrics: MetricsConfig::default(),
             security: SecurityConfig::default(),
+            health: HealthAggregateConfig::default(),
+            journal: JournalConfig::default(),
+            chaos: ChaosConfig::default(),
             environment: "dev".to_string(),
         }
     }
@@ -395,6 +690,7 @@ impl Default for RestConfig {
             max_body_size: 10 * 1024 * 1024, // 10MB
             cors_enabled: true,
             cors_allowed_origins: vec!["*".to_string()],
+            max_in_flight: default_max_in_flight(),
         }
     }
 }
@@ -416,8 +712,17 @@ impl Default for RoutingConfig {
         Self {
             event_buffer_size: 1000,
             max_subscribers_per_topic: 100,
+            subscriber_overflow: SubscriberOverflow::default(),
+            max_block_timeout_ms: default_max_block_timeout_ms(),
+            max_topics: default_max_topics(),
+            max_scheduled_events: default_max_scheduled_events(),
             event_ttl_secs: 0,
+            topic_ttl_overrides: HashMap::new(),
             dead_letter_enabled: false,
+            dead_letter_expired: false,
+            dead_letter_capacity: default_dead_letter_capacity(),
+            dead_letter_max_reprocess_attempts: default_dead_letter_max_reprocess_attempts(),
+            dead_letter_reprocess_rate: default_dead_letter_reprocess_rate(),
             max_retry_attempts: 3,
             retry_backoff: BackoffConfig::default(),
         }
@@ -443,6 +748,18 @@ impl Default for LoggingConfig {
             file_path: None,
             rotation_size_mb: Some(100),
             rotation_keep: Some(5),
+            access_log: AccessLogConfig::default(),
+        }
+    }
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fields: AccessLogField::ALL.to_vec(),
+            sample_rate: 1.0,
+            key_id_secret: None,
         }
     }
 }
@@ -453,6 +770,7 @@ impl Default for MetricsConfig {
             enabled: true,
             export_interval_secs: 60,
             prometheus_path: "/metrics".to_string(),
+            push_endpoint: None,
         }
     }
 }
@@ -462,8 +780,43 @@ impl Default for SecurityConfig {
         Self {
             auth_enabled: false,
             api_key_header: Some("X-API-Key".to_string()),
+            admin_api_key: None,
             rate_limit: None,
             tls: None,
+            source_rules: Vec::new(),
+            redact_metadata_keys: Vec::new(),
+            redact_payload_keys: Vec::new(),
+        }
+    }
+}
+
+impl Default for HealthAggregateConfig {
+    fn default() -> Self {
+        Self {
+            components: Vec::new(),
+            probe_timeout_ms: 2000,
+        }
+    }
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/events.journal".to_string(),
+            buffer_events: 256,
+        }
+    }
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seed: 0,
+            drop_rate: 0.0,
+            delay_rate: 0.0,
+            max_delay_ms: 0,
         }
     }
 }
@@ -485,6 +838,43 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_invalid_topic_ttl_override() {
+        let mut config = AppConfig::default();
+        config
+            .routing
+            .topic_ttl_overrides
+            .insert("system.heartbeat".to_string(), 5);
+        assert!(config.validate().is_ok());
+
+        config
+            .routing
+            .topic_ttl_overrides
+            .insert("game.round.complete".to_string(), 100_000);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_source_rules() {
+        let mut security = SecurityConfig::default();
+        assert!(security.is_source_allowed("anything", "MONEY_CHANGED"));
+
+        security.source_rules = vec![
+            SourceRule {
+                source: "monitor".to_string(),
+                event_types: vec!["HEARTBEAT".to_string()],
+            },
+            SourceRule {
+                source: "BalatroMCP*".to_string(),
+                event_types: vec!["*".to_string()],
+            },
+        ];
+        assert!(security.is_source_allowed("monitor", "HEARTBEAT"));
+        assert!(!security.is_source_allowed("monitor", "MONEY_CHANGED"));
+        assert!(security.is_source_allowed("BalatroMCP-2", "MONEY_CHANGED"));
+        assert!(!security.is_source_allowed("unknown", "HEARTBEAT"));
+    }
+
     #[test]
     fn test_invalid_log_format() {
         let mut config = AppConfig::default();
@@ -1,14 +1,31 @@
-use std::sync::Arc;
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::UnboundedReceiverStream;
-use tonic::{Request, Response, Status};
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use std::{future::Future, net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
+use tokio::net::TcpListener;
+use tonic::{
+    transport::{server::TcpIncoming, Server},
+    Request, Response, Status,
+};
 use tracing::{error, info};
+use uuid::Uuid;
 
 use crate::{
-    proto::{Event, EventBatch, EventBusGrpc, PublishResponse, SubscribeRequest},
-    routing::EventRouter,
+    config::GrpcConfig,
+    proto::{
+        eventbus::{self, event_bus_server::EventBusServer, Overflow},
+        Event, EventBatch, EventBusGrpc, EventType, PublishResponse, SubscribeRequest,
+        FILE_DESCRIPTOR_SET,
+    },
+    routing::{overflow::OverflowStrategy, EventRouter, SubscriptionId, JOURNAL_OFFSET_KEY},
+    tls::TlsIdentity,
 };
 
+/// Topic of the acknowledgement sent as the first message of every subscription
+pub const SUBSCRIPTION_ACK_TOPIC: &str = "system.subscription.ack";
+
+/// Subscription protocol version negotiated in the ack
+pub const SUBSCRIPTION_PROTOCOL_VERSION: &str = "1";
+
 pub struct EventBusService {
     router: Arc<EventRouter>,
 }
@@ -28,16 +45,23 @@ impl EventBusGrpc for EventBusService {
         let event = request.into_inner();
         info!("gRPC: Received event from {}", event.source);
 
-        match self.router.route_event(event).await {
-            Ok(_) => Ok(Response::new(PublishResponse {
+        match self.router.try_route_event(event).await {
+            Ok(outcome) if outcome.routed == 0 => Ok(Response::new(PublishResponse {
+                success: true,
+                message: "Event accepted but not routed (no subscribers)".to_string(),
+                routed: 0,
+            })),
+            Ok(outcome) => Ok(Response::new(PublishResponse {
                 success: true,
                 message: "Event published successfully".to_string(),
+                routed: outcome.routed,
             })),
             Err(e) => {
                 error!("Failed to route event: {}", e);
                 Ok(Response::new(PublishResponse {
                     success: false,
                     message: format!("Failed to route event: {e}"),
+                    routed: 0,
                 }))
             }
         }
@@ -55,9 +79,11 @@ impl EventBusGrpc for EventBusService {
         );
 
         let mut errors = Vec::new();
+        let mut routed = 0;
         for (idx, event) in batch.events.into_iter().enumerate() {
-            if let Err(e) = self.router.route_event(event).await {
-                errors.push(format!("Event {idx}: {e}"));
+            match self.router.try_route_event(event).await {
+                Ok(outcome) => routed += outcome.routed,
+                Err(e) => errors.push(format!("Event {idx}: {e}")),
             }
         }
 
@@ -65,11 +91,13 @@ impl EventBusGrpc for EventBusService {
             Ok(Response::new(PublishResponse {
                 success: true,
                 message: format!("All {event_count} events published successfully"),
+                routed,
             }))
         } else {
             Ok(Response::new(PublishResponse {
                 success: false,
                 message: format!("Failed to publish some events: {}", errors.join(", ")),
+                routed,
             }))
         }
     }
@@ -87,15 +115,452 @@ impl EventBusGrpc for EventBusService {
             req.topic_pattern, req.subscriber_id
         );
 
-        // Create channel for this subscriber
-        let (tx, rx) = mpsc::unbounded_channel();
+        let pattern = normalize_pattern(&req.topic_pattern);
 
-        // Register the channel with the router
-        self.router.subscribe_channel(req.topic_pattern.clone(), tx);
+        // Every stream gets a bounded queue, so a stalled client can't make
+        // the bus buffer without limit
+        let strategy = req
+            .overflow
+            .unwrap_or_else(|| self.router.subscriber_overflow());
+        // Routing waits on each blocking subscriber in turn, so one that
+        // stops reading stalls every publisher for up to its timeout
+        if let OverflowStrategy::Block { timeout } = strategy {
+            let max = self.router.max_block_timeout();
+            if timeout > max {
+                return Err(Status::invalid_argument(format!(
+                    "block timeout of {}ms exceeds routing.max_block_timeout_ms ({}ms)",
+                    timeout.as_millis(),
+                    max.as_millis()
+                )));
+            }
+        }
 
-        // Convert to streaming response
-        let stream = UnboundedReceiverStream::new(rx);
+        // Register before replaying so nothing falls between the two
+        let (subscription_id, rx) = self
+            .router
+            .subscribe_bounded(pattern.clone(), strategy)
+            .map_err(|e| Status::resource_exhausted(e.to_string()))?;
+        let live = futures::stream::unfold(rx, |mut rx| async move {
+            let event = rx.recv().await?;
+            Some((event, rx))
+        });
+
+        let (replayed, replay_end) = match req.cursor {
+            Some(cursor) => self
+                .router
+                .replay_from(&pattern, cursor)
+                .await
+                .map_err(|e| Status::internal(format!("Journal replay failed: {e}")))?
+                .ok_or_else(|| {
+                    Status::failed_precondition("Replay cursor requires the event journal")
+                })?,
+            None => (Vec::new(), 0),
+        };
+        if let Some(cursor) = req.cursor {
+            info!(
+                "gRPC: Replaying {} events from offset {} for '{}'",
+                replayed.len(),
+                cursor,
+                req.subscriber_id
+            );
+        }
+
+        // Live events already covered by the replay are dropped
+        let live = live.filter(move |event| {
+            let duplicate = journal_offset(event).is_some_and(|offset| offset < replay_end);
+            futures::future::ready(!duplicate)
+        });
+
+        // The ack always comes first, then the replay, then live events
+        let stream = futures::stream::iter(
+            std::iter::once(subscription_ack(&pattern, subscription_id)).chain(replayed),
+        )
+        .chain(live);
 
         Ok(Response::new(Box::pin(stream)))
     }
 }
+
+/// Serve `service` over gRPC on `addr` until the server fails
+///
+/// `max_message_size` caps encoded messages both ways. Connections whose
+/// peer stops answering HTTP/2 keepalive pings for `connection_timeout_secs`
+/// are closed; streaming subscriptions are otherwise unbounded in time.
+/// Reflection is only served when `reflection_enabled` is set. With `tls`,
+/// only TLS connections are accepted.
+pub async fn serve(
+    service: EventBusService,
+    addr: SocketAddr,
+    config: &GrpcConfig,
+    tls: Option<&TlsIdentity>,
+) -> anyhow::Result<()> {
+    grpc_server(service, config, tls)?.serve(addr).await?;
+    Ok(())
+}
+
+/// `serve` on an already bound listener, stopping once `shutdown` resolves
+pub async fn serve_with_listener(
+    service: EventBusService,
+    listener: TcpListener,
+    config: &GrpcConfig,
+    tls: Option<&TlsIdentity>,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()> {
+    let incoming =
+        TcpIncoming::from_listener(listener, true, None).map_err(|e| anyhow::anyhow!(e))?;
+    grpc_server(service, config, tls)?
+        .serve_with_incoming_shutdown(incoming, shutdown)
+        .await?;
+    Ok(())
+}
+
+fn grpc_server(
+    service: EventBusService,
+    config: &GrpcConfig,
+    tls: Option<&TlsIdentity>,
+) -> anyhow::Result<tonic::transport::server::Router> {
+    let keepalive = Duration::from_secs(config.connection_timeout_secs);
+    let event_bus = EventBusServer::new(service)
+        .max_decoding_message_size(config.max_message_size)
+        .max_encoding_message_size(config.max_message_size);
+    let reflection = if config.reflection_enabled {
+        Some(
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+                .build()?,
+        )
+    } else {
+        None
+    };
+
+    let mut server = Server::builder();
+    if let Some(tls) = tls {
+        server = server.tls_config(tls.grpc_config())?;
+    }
+    Ok(server
+        .http2_keepalive_interval(Some(keepalive))
+        .http2_keepalive_timeout(Some(keepalive))
+        .add_service(event_bus)
+        .add_optional_service(reflection))
+}
+
+/// The generated `EventBus` service, delegating to `EventBusGrpc`
+#[tonic::async_trait]
+impl eventbus::event_bus_server::EventBus for EventBusService {
+    async fn publish_event(
+        &self,
+        request: Request<Event>,
+    ) -> Result<Response<eventbus::PublishResponse>, Status> {
+        EventBusGrpc::publish_event(self, request)
+            .await
+            .map(|response| response.map(Into::into))
+    }
+
+    async fn publish_batch(
+        &self,
+        request: Request<EventBatch>,
+    ) -> Result<Response<eventbus::PublishResponse>, Status> {
+        EventBusGrpc::publish_batch(self, request)
+            .await
+            .map(|response| response.map(Into::into))
+    }
+
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send + 'static>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<eventbus::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let request = request.into_inner();
+        let overflow = match request.overflow() {
+            Overflow::Unspecified => None,
+            Overflow::DropOldest => Some(OverflowStrategy::DropOldest),
+            Overflow::DropNewest => Some(OverflowStrategy::DropNewest),
+            Overflow::Block if request.block_timeout_ms == 0 => {
+                return Err(Status::invalid_argument(
+                    "OVERFLOW_BLOCK requires a non-zero block_timeout_ms",
+                ))
+            }
+            Overflow::Block => Some(OverflowStrategy::Block {
+                timeout: Duration::from_millis(request.block_timeout_ms.into()),
+            }),
+            Overflow::Disconnect => Some(OverflowStrategy::Disconnect),
+        };
+        let request = Request::new(SubscribeRequest {
+            topic_pattern: request.topic_pattern,
+            subscriber_id: request.subscriber_id,
+            cursor: request.cursor,
+            overflow,
+        });
+
+        let stream = EventBusGrpc::subscribe(self, request).await?.into_inner();
+        Ok(Response::new(Box::pin(stream.map(Ok))))
+    }
+}
+
+impl From<PublishResponse> for eventbus::PublishResponse {
+    fn from(response: PublishResponse) -> Self {
+        Self {
+            success: response.success,
+            message: response.message,
+            routed: response.routed as u64,
+        }
+    }
+}
+
+/// Journal offset stamped on an event by the router, if any
+fn journal_offset(event: &Event) -> Option<u64> {
+    event.metadata.get(JOURNAL_OFFSET_KEY)?.parse().ok()
+}
+
+/// Trim whitespace and empty segments so `" game..state "` registers as `game.state`
+fn normalize_pattern(pattern: &str) -> String {
+    pattern
+        .trim()
+        .split('.')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Build the ack event confirming which pattern a subscription registered
+fn subscription_ack(pattern: &str, subscription_id: SubscriptionId) -> Event {
+    let mut metadata = std::collections::HashMap::new();
+    metadata.insert("topic".to_string(), SUBSCRIPTION_ACK_TOPIC.to_string());
+    metadata.insert("topic_pattern".to_string(), pattern.to_string());
+    metadata.insert("subscription_id".to_string(), subscription_id.to_string());
+    metadata.insert(
+        "protocol_version".to_string(),
+        SUBSCRIPTION_PROTOCOL_VERSION.to_string(),
+    );
+
+    Event {
+        event_id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().timestamp_millis(),
+        r#type: EventType::Custom as i32,
+        source: "event-bus".to_string(),
+        version: 1,
+        metadata,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{RoutingConfig, SubscriberOverflow};
+    use crate::journal::EventJournal;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_subscribe_sends_ack_first() {
+        let router = Arc::new(EventRouter::new());
+        let service = EventBusService::new(router.clone());
+
+        let response = service
+            .subscribe(Request::new(SubscribeRequest {
+                topic_pattern: " game.*.update ".to_string(),
+                subscriber_id: "test-subscriber".to_string(),
+                cursor: None,
+                overflow: None,
+            }))
+            .await
+            .unwrap();
+        let mut stream = response.into_inner();
+
+        // A routed event must arrive after the ack
+        router
+            .route_event(Event {
+                r#type: EventType::GameState as i32,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let ack = stream.next().await.unwrap();
+        assert_eq!(ack.metadata["topic"], SUBSCRIPTION_ACK_TOPIC);
+        assert_eq!(ack.metadata["topic_pattern"], "game.*.update");
+        assert_eq!(
+            ack.metadata["protocol_version"],
+            SUBSCRIPTION_PROTOCOL_VERSION
+        );
+        // The router's own id for the subscription
+        assert!(ack.metadata["subscription_id"].parse::<u64>().is_ok());
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.r#type, EventType::GameState as i32);
+    }
+
+    fn heartbeat(id: &str) -> Event {
+        Event {
+            event_id: id.to_string(),
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        }
+    }
+
+    async fn subscribe(
+        service: &EventBusService,
+        cursor: Option<u64>,
+    ) -> std::pin::Pin<Box<dyn futures::Stream<Item = Event> + Send + 'static>> {
+        let mut stream = service
+            .subscribe(Request::new(SubscribeRequest {
+                topic_pattern: "system.heartbeat".to_string(),
+                subscriber_id: "durable".to_string(),
+                cursor,
+                overflow: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let ack = stream.next().await.unwrap();
+        assert_eq!(ack.metadata["topic"], SUBSCRIPTION_ACK_TOPIC);
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_with_cursor_replays_missed_events_once() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", Uuid::new_v4()));
+        let journal = Arc::new(EventJournal::open(&path, 1000).unwrap());
+        let router = Arc::new(EventRouter::new().with_journal(journal));
+        let service = EventBusService::new(router.clone());
+
+        let mut stream = subscribe(&service, None).await;
+        router.route_event(heartbeat("e0")).await.unwrap();
+        let seen = stream.next().await.unwrap();
+        assert_eq!(seen.event_id, "e0");
+        let cursor: u64 = seen.metadata[JOURNAL_OFFSET_KEY].parse::<u64>().unwrap() + 1;
+        drop(stream);
+
+        // Missed while disconnected
+        router.route_event(heartbeat("e1")).await.unwrap();
+        router.route_event(heartbeat("e2")).await.unwrap();
+
+        let mut stream = subscribe(&service, Some(cursor)).await;
+        router.route_event(heartbeat("e3")).await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) =
+            tokio::time::timeout(Duration::from_millis(100), stream.next()).await
+        {
+            received.push(event.event_id);
+        }
+        assert_eq!(received, vec!["e1", "e2", "e3"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Publish five heartbeats with one stalled and one reading subscriber;
+    /// returns what each stream yields afterwards
+    async fn stall_one_subscriber(policy: SubscriberOverflow) -> (Vec<String>, Vec<String>) {
+        let router = Arc::new(EventRouter::new_with_config(RoutingConfig {
+            event_buffer_size: 2,
+            subscriber_overflow: policy,
+            ..RoutingConfig::default()
+        }));
+        let service = EventBusService::new(router.clone());
+        let mut stalled = subscribe(&service, None).await;
+        let mut reader = subscribe(&service, None).await;
+
+        let mut read = Vec::new();
+        let start = std::time::Instant::now();
+        for i in 0..5 {
+            router
+                .route_event(heartbeat(&format!("e{i}")))
+                .await
+                .unwrap();
+            read.push(reader.next().await.unwrap().event_id);
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "publisher was held up by the stalled subscriber"
+        );
+
+        let mut stalled_ids = Vec::new();
+        while let Ok(Some(event)) =
+            tokio::time::timeout(Duration::from_millis(100), stalled.next()).await
+        {
+            stalled_ids.push(event.event_id);
+        }
+        (read, stalled_ids)
+    }
+
+    #[tokio::test]
+    async fn test_stalled_subscriber_overflow_policy() {
+        let all: Vec<String> = (0..5).map(|i| format!("e{i}")).collect();
+
+        let (read, stalled) = stall_one_subscriber(SubscriberOverflow::DropOldest).await;
+        assert_eq!(read, all);
+        assert_eq!(stalled, vec!["e3", "e4"]);
+
+        // Disconnected: the queued events arrive, then the stream ends
+        let (read, stalled) = stall_one_subscriber(SubscriberOverflow::Disconnect).await;
+        assert_eq!(read, all);
+        assert_eq!(stalled, vec!["e0", "e1"]);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_without_journal_is_rejected() {
+        let service = EventBusService::new(Arc::new(EventRouter::new()));
+        let result = service
+            .subscribe(Request::new(SubscribeRequest {
+                topic_pattern: "system.heartbeat".to_string(),
+                subscriber_id: "durable".to_string(),
+                cursor: Some(0),
+                overflow: None,
+            }))
+            .await;
+        assert_eq!(
+            result.err().unwrap().code(),
+            tonic::Code::FailedPrecondition
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_topic_is_resource_exhausted() {
+        let router = Arc::new(EventRouter::new_with_config(RoutingConfig {
+            max_subscribers_per_topic: 1,
+            ..RoutingConfig::default()
+        }));
+        let service = EventBusService::new(router);
+        let request = || {
+            Request::new(SubscribeRequest {
+                topic_pattern: "system.heartbeat".to_string(),
+                subscriber_id: "crowd".to_string(),
+                cursor: None,
+                overflow: None,
+            })
+        };
+
+        let first = service.subscribe(request()).await.unwrap();
+        let result = service.subscribe(request()).await;
+        assert_eq!(result.err().unwrap().code(), tonic::Code::ResourceExhausted);
+
+        // Ending the first stream frees its slot
+        drop(first);
+        assert!(service.subscribe(request()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_block_timeout_is_capped() {
+        let service = EventBusService::new(Arc::new(EventRouter::new_with_config(RoutingConfig {
+            max_block_timeout_ms: 500,
+            ..RoutingConfig::default()
+        })));
+        let request = |timeout_ms| {
+            Request::new(SubscribeRequest {
+                topic_pattern: "system.heartbeat".to_string(),
+                subscriber_id: "blocker".to_string(),
+                cursor: None,
+                overflow: Some(OverflowStrategy::Block {
+                    timeout: Duration::from_millis(timeout_ms),
+                }),
+            })
+        };
+
+        let result = service.subscribe(request(501)).await;
+        assert_eq!(result.err().unwrap().code(), tonic::Code::InvalidArgument);
+        assert!(service.subscribe(request(500)).await.is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::{Context, Result};
+use prost::Message;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::proto::Event;
+
+/// Append-only on-disk log of routed events
+///
+/// Records are length-delimited protobuf `Event`s. Appends are buffered and
+/// only written and fsynced once `buffer_events` records are pending or
+/// `flush` is called, so a crash can lose at most the events appended since
+/// the last fsync. Shutdown must call `flush` to close that window.
+///
+/// Each record's zero-based position in the file is its offset, which
+/// subscribers use as a replay cursor. The byte position of every record is
+/// kept in memory (8 bytes per event) so a replay seeks straight to its
+/// cursor instead of decoding the whole file.
+pub struct EventJournal {
+    path: PathBuf,
+    writer: Mutex<JournalWriter>,
+    buffer_events: usize,
+}
+
+struct JournalWriter {
+    file: BufWriter<File>,
+    pending: usize,
+    /// Byte position of each record, indexed by offset
+    positions: Vec<u64>,
+    /// File length once the buffer is written
+    len: u64,
+}
+
+impl JournalWriter {
+    fn sync(&mut self) -> Result<()> {
+        self.file
+            .flush()
+            .context("Failed to write journal buffer")?;
+        self.file
+            .get_ref()
+            .sync_data()
+            .context("Failed to fsync journal")?;
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+impl EventJournal {
+    /// Open (or create) the journal at `path` for appending
+    pub fn open(path: impl AsRef<Path>, buffer_events: usize) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal directory {parent:?}"))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open journal {path:?}"))?;
+
+        // Drop any torn record left by a crash mid-write, so new records are
+        // appended right after the last complete one and stay readable
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read journal {path:?}"))?;
+        let (records, complete_len) = decode_records(&bytes);
+        if complete_len < bytes.len() {
+            file.set_len(complete_len as u64)
+                .with_context(|| format!("Failed to truncate journal {path:?}"))?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Mutex::new(JournalWriter {
+                file: BufWriter::new(file),
+                pending: 0,
+                positions: records.iter().map(|(position, _)| *position).collect(),
+                len: complete_len as u64,
+            }),
+            buffer_events: buffer_events.max(1),
+        })
+    }
+
+    /// Buffer an event, writing and fsyncing once the buffer is full
+    ///
+    /// Returns the offset assigned to the event.
+    pub fn append(&self, event: &Event) -> Result<u64> {
+        let record = event.encode_length_delimited_to_vec();
+        let mut writer = self.writer.lock().unwrap();
+        writer
+            .file
+            .write_all(&record)
+            .context("Failed to append to journal")?;
+        writer.pending += 1;
+        let offset = writer.positions.len() as u64;
+        let position = writer.len;
+        writer.positions.push(position);
+        writer.len += record.len() as u64;
+        if writer.pending >= self.buffer_events {
+            writer.sync()?;
+        }
+        Ok(offset)
+    }
+
+    /// Force all buffered events to disk (write + fsync)
+    pub fn flush(&self) -> Result<()> {
+        self.writer.lock().unwrap().sync()
+    }
+
+    /// Read the records from offset `cursor` onwards, with their offsets
+    ///
+    /// Also returns the offset the read ends at: record `i` of the journal is
+    /// exactly the event that was assigned offset `i`, and events appended
+    /// while reading get offsets from the end onwards. Appends are only held
+    /// up while the buffer is written out; the read itself is plain blocking
+    /// file I/O, so async callers should run it on a blocking thread.
+    pub fn read_from(&self, cursor: u64) -> Result<(Vec<(u64, Event)>, u64)> {
+        let (start, len, end) = {
+            let mut writer = self.writer.lock().unwrap();
+            writer
+                .file
+                .flush()
+                .context("Failed to write journal buffer")?;
+            let end = writer.positions.len() as u64;
+            let start = match usize::try_from(cursor) {
+                Ok(cursor) if cursor < writer.positions.len() => writer.positions[cursor],
+                _ => writer.len,
+            };
+            (start, writer.len, end)
+        };
+
+        let mut bytes = vec![0; (len - start) as usize];
+        let mut file = File::open(&self.path)
+            .with_context(|| format!("Failed to open journal {:?}", self.path))?;
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .with_context(|| format!("Failed to read journal {:?}", self.path))?;
+
+        let events = decode_records(&bytes)
+            .0
+            .into_iter()
+            .zip(cursor.min(end)..)
+            .map(|((_, event), offset)| (offset, event))
+            .collect();
+        Ok((events, end))
+    }
+
+    /// Read every complete record from the journal at `path`
+    ///
+    /// A truncated trailing record (e.g. from a crash mid-write) is ignored.
+    pub fn read_all(path: impl AsRef<Path>) -> Result<Vec<Event>> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read journal {path:?}"))?;
+        Ok(decode_records(&bytes)
+            .0
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect())
+    }
+}
+
+/// Decode the complete records at the start of `bytes`, with the byte
+/// position of each
+///
+/// Also returns the length of the bytes they span; anything after that is
+/// a truncated record.
+fn decode_records(bytes: &[u8]) -> (Vec<(u64, Event)>, usize) {
+    let mut buf = bytes;
+    let mut records = Vec::new();
+    let mut complete_len = 0;
+    while !buf.is_empty() {
+        match Event::decode_length_delimited(&mut buf) {
+            Ok(event) => {
+                records.push((complete_len as u64, event));
+                complete_len = bytes.len() - buf.len();
+            }
+            Err(_) => break,
+        }
+    }
+    (records, complete_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::EventType;
+    use crate::routing::EventRouter;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_flush_makes_buffered_events_readable() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", uuid::Uuid::new_v4()));
+        let journal = Arc::new(EventJournal::open(&path, 1000).unwrap());
+        let router = EventRouter::new().with_journal(journal.clone());
+
+        for i in 0..10 {
+            router
+                .route_event(Event {
+                    event_id: format!("evt-{i}"),
+                    r#type: EventType::Heartbeat as i32,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        // Still buffered: nothing has reached the file yet
+        assert!(EventJournal::read_all(&path).unwrap().is_empty());
+
+        journal.flush().unwrap();
+        let events = EventJournal::read_all(&path).unwrap();
+        let ids: Vec<_> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, (0..10).map(|i| format!("evt-{i}")).collect::<Vec<_>>());
+
+        drop(router);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reopen_drops_torn_trailing_record() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", uuid::Uuid::new_v4()));
+        let event = |id: &str| Event {
+            event_id: id.to_string(),
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+
+        let journal = EventJournal::open(&path, 1).unwrap();
+        assert_eq!(journal.append(&event("before-crash")).unwrap(), 0);
+        drop(journal);
+
+        // A crash halfway through writing the next record
+        let torn = event("torn").encode_length_delimited_to_vec();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&torn[..torn.len() / 2]).unwrap();
+        drop(file);
+
+        let journal = EventJournal::open(&path, 1).unwrap();
+        assert_eq!(journal.append(&event("after-crash")).unwrap(), 1);
+        let ids: Vec<_> = EventJournal::read_all(&path)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["before-crash", "after-crash"]);
+
+        drop(journal);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_from_seeks_to_cursor() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", uuid::Uuid::new_v4()));
+        let journal = EventJournal::open(&path, 1000).unwrap();
+        for i in 0..5 {
+            journal
+                .append(&Event {
+                    event_id: format!("evt-{i}"),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        // Buffered events are included
+        let (events, end) = journal.read_from(3).unwrap();
+        let read: Vec<_> = events
+            .iter()
+            .map(|(o, e)| (*o, e.event_id.as_str()))
+            .collect();
+        assert_eq!(read, vec![(3, "evt-3"), (4, "evt-4")]);
+        assert_eq!(end, 5);
+
+        // A cursor past the end replays nothing
+        let (events, end) = journal.read_from(9).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(end, 5);
+
+        // The index survives reopening
+        drop(journal);
+        let journal = EventJournal::open(&path, 1000).unwrap();
+        let (events, _) = journal.read_from(4).unwrap();
+        assert_eq!(events[0].1.event_id, "evt-4");
+        assert_eq!(journal.append(&Event::default()).unwrap(), 5);
+
+        drop(journal);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
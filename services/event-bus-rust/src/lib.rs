@@ -0,0 +1,29 @@
+//! Event bus service: topic routing with REST and gRPC ingestion
+//!
+//! The binary wires these modules into servers; [`bus::EventBus`] exposes the
+//! same routing in-process for embedders that don't need the network layers.
+
+pub mod api;
+pub mod bus;
+pub mod config;
+pub mod grpc;
+pub mod journal;
+pub mod metrics;
+pub mod proto;
+pub mod routing;
+pub mod tls;
+pub mod tracing_config;
+
+use std::sync::{atomic::AtomicBool, Arc};
+
+use crate::{config::AppConfig, routing::EventRouter};
+
+/// Shared state handed to every REST handler
+#[derive(Clone)]
+pub struct AppState {
+    pub router: Arc<EventRouter>,
+    pub config: Arc<AppConfig>,
+    /// Set by `/admin/drain` and cleared by `/admin/resume`; new publishes are
+    /// rejected while true
+    pub draining: Arc<AtomicBool>,
+}
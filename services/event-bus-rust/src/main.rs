@@ -1,37 +1,29 @@
-mod api;
-mod config;
-mod grpc;
-mod metrics;
-mod proto;
-mod routing;
-mod tracing_config;
-
 use anyhow::Result;
-use axum::{routing::post, Router};
-use std::{net::SocketAddr, sync::Arc, time::Duration};
-use tokio::signal;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    limit::RequestBodyLimitLayer,
-    timeout::TimeoutLayer,
-    trace::TraceLayer,
+use axum::serve::ListenerExt;
+use std::{
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
 };
+use tokio::signal;
 use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use crate::{
-    api::{handlers, health},
-    config::{AppConfig, ConfigManager},
-    grpc::EventBusService,
-    routing::EventRouter,
+use event_bus_rust::{
+    api,
+    config::ConfigManager,
+    grpc::{self, EventBusService},
+    journal::EventJournal,
+    metrics,
+    routing::{
+        fault::FaultInjector,
+        interceptor::{RedactMetadata, RedactPayload},
+        EventRouter,
+    },
+    tls::{TlsIdentity, TlsListener},
+    tracing_config, AppState,
 };
 
-#[derive(Clone)]
-pub struct AppState {
-    pub router: Arc<EventRouter>,
-    pub config: Arc<AppConfig>,
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load configuration first
@@ -39,7 +31,7 @@ async fn main() -> Result<()> {
     let config = Arc::new(config_manager.get());
 
     // Initialize metrics subsystem
-    metrics::init_metrics();
+    metrics::init_metrics(&config.metrics);
 
     // Initialize tracing based on configuration
     let filter =
@@ -78,89 +70,102 @@ async fn main() -> Result<()> {
         config.environment
     );
 
-    // Initialize event router
-    let router = Arc::new(EventRouter::new());
-    let app_state = AppState {
-        router: router.clone(),
-        config: config.clone(),
+    // Open the event journal if enabled
+    let journal = if config.journal.enabled {
+        info!("Journaling events to {}", config.journal.path);
+        Some(Arc::new(EventJournal::open(
+            &config.journal.path,
+            config.journal.buffer_events,
+        )?))
+    } else {
+        None
     };
 
-    // Build REST API with configuration
-    let mut rest_app = Router::new()
-        .route("/api/v1/events", post(handlers::handle_single_event))
-        .route("/api/v1/events/batch", post(handlers::handle_batch_events))
-        .route("/health", axum::routing::get(health::health_check));
-
-    // Add metrics endpoint if enabled
-    if config.metrics.enabled {
-        rest_app = rest_app.route(
-            &config.metrics.prometheus_path,
-            axum::routing::get(health::metrics),
-        );
+    // Initialize event router
+    let mut router = EventRouter::new_with_config(config.routing.clone());
+    if let Some(journal) = &journal {
+        router = router.with_journal(journal.clone());
     }
-
-    // Configure CORS based on settings
-    let cors_layer = if config.server.rest.cors_enabled {
-        if config
-            .server
-            .rest
-            .cors_allowed_origins
-            .contains(&"*".to_string())
-        {
-            CorsLayer::permissive()
+    if config.chaos.enabled {
+        if config.environment == "prod" {
+            warn!("Ignoring chaos fault injection in prod environment");
         } else {
-            let origins: Vec<_> = config
-                .server
-                .rest
-                .cors_allowed_origins
-                .iter()
-                .filter_map(|origin| origin.parse().ok())
-                .collect();
-            CorsLayer::new()
-                .allow_origin(origins)
-                .allow_methods(Any)
-                .allow_headers(Any)
+            warn!(
+                "Chaos mode enabled: drop_rate={}, delay_rate={}, seed={}",
+                config.chaos.drop_rate, config.chaos.delay_rate, config.chaos.seed
+            );
+            router = router.with_fault_injector(Arc::new(FaultInjector::new(&config.chaos)));
         }
-    } else {
-        CorsLayer::new()
+    }
+    if !config.security.redact_metadata_keys.is_empty() {
+        router = router.with_interceptor(Box::new(RedactMetadata::new(
+            &config.security.redact_metadata_keys,
+        )));
+    }
+    if !config.security.redact_payload_keys.is_empty() {
+        router = router.with_interceptor(Box::new(RedactPayload::new(
+            &config.security.redact_payload_keys,
+        )));
+    }
+    let router = Arc::new(router);
+    let app_state = AppState {
+        router: router.clone(),
+        config: config.clone(),
+        draining: Arc::new(AtomicBool::new(false)),
     };
 
-    let rest_app = rest_app
-        .layer(RequestBodyLimitLayer::new(config.server.rest.max_body_size))
-        .layer(TimeoutLayer::new(Duration::from_secs(
-            config.server.rest.request_timeout_secs,
-        )))
-        .layer(cors_layer)
-        .layer(TraceLayer::new_for_http())
-        .with_state(app_state);
+    let rest_app = api::rest_app(app_state);
+
+    // Load TLS material before binding anything, so bad certificate paths
+    // fail startup instead of leaving the servers on plaintext
+    let tls = config
+        .security
+        .tls
+        .as_ref()
+        .map(TlsIdentity::load)
+        .transpose()?;
+    let rest_tls = tls.as_ref().map(TlsIdentity::rustls_config).transpose()?;
+    let scheme = match &tls {
+        Some(tls) if tls.mutual() => "TLS, client certificates required",
+        Some(_) => "TLS",
+        None => "plaintext",
+    };
 
     // Start REST API server with configured address
     let rest_addr: SocketAddr =
         format!("{}:{}", config.server.rest.host, config.server.rest.port).parse()?;
-    info!("REST API listening on {}", rest_addr);
+    info!("REST API listening on {} ({})", rest_addr, scheme);
 
     let rest_server = tokio::spawn(async move {
         let listener = tokio::net::TcpListener::bind(&rest_addr)
             .await
             .expect("Failed to bind to address");
-        axum::serve(listener, rest_app)
-            .await
-            .expect("REST server failed");
+        let app = rest_app.into_make_service_with_connect_info::<SocketAddr>();
+        match rest_tls {
+            Some(rest_tls) => {
+                // The no-op tap gives the TLS listener axum's `ConnectInfo` support
+                let listener = TlsListener::new(listener, rest_tls)
+                    .expect("Failed to start TLS listener")
+                    .tap_io(|_| {});
+                axum::serve(listener, app).await
+            }
+            None => axum::serve(listener, app).await,
+        }
+        .expect("REST server failed");
     });
 
     // Start gRPC server with configured address
     let grpc_addr: SocketAddr =
         format!("{}:{}", config.server.grpc.host, config.server.grpc.port).parse()?;
-    let _grpc_service = EventBusService::new(router);
+    let grpc_service = EventBusService::new(router);
+    let grpc_config = config.server.grpc.clone();
 
-    info!("gRPC server listening on {}", grpc_addr);
+    info!("gRPC server listening on {} ({})", grpc_addr, scheme);
 
-    // Note: For now, we'll just have the gRPC service ready but not start a separate server
-    // The actual gRPC service would need a proper proto definition file
-    // This is a placeholder for the gRPC functionality
     let grpc_server = tokio::spawn(async move {
-        // TODO: Implement proper gRPC server when proto service is defined
-        tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+        if let Err(e) = grpc::serve(grpc_service, grpc_addr, &grpc_config, tls.as_ref()).await {
+            error!("gRPC server failed: {}", e);
+        }
     });
 
     // Enable hot-reload if not in production
@@ -199,6 +204,13 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Make sure every journaled event is on disk before exiting
+    if let Some(journal) = journal {
+        if let Err(e) = journal.flush() {
+            error!("Failed to flush event journal: {}", e);
+        }
+    }
+
     // Shutdown OpenTelemetry
     if let Some(provider) = tracer_provider {
         if let Err(e) = tracing_config::shutdown_tracing(provider) {
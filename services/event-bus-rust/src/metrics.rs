@@ -1,5 +1,10 @@
 use metrics::{counter, gauge, histogram};
-use std::time::Instant;
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::time::{Duration, Instant};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::warn;
+
+use crate::config::MetricsConfig;
 
 #[allow(dead_code)]
 pub struct EventMetrics;
@@ -42,6 +47,79 @@ impl EventMetrics {
         counter!("event_bus_events_routed_total", "topic" => topic.to_string()).increment(count);
     }
 
+    /// Record an event that reached no subscriber on a topic
+    pub fn record_events_dropped(topic: &str) {
+        counter!("event_bus_events_dropped_total", "topic" => topic.to_string()).increment(1);
+    }
+
+    /// Update the number of live subscribers matching a topic
+    pub fn update_topic_subscribers(topic: &str, count: f64) {
+        gauge!("event_bus_topic_subscribers", "topic" => topic.to_string()).set(count);
+    }
+
+    /// Update the number of REST requests currently being processed
+    pub fn update_in_flight_requests(count: f64) {
+        gauge!("event_bus_in_flight_requests").set(count);
+    }
+
+    /// Record an HTTP request's duration by route template and status class
+    pub fn record_http_request(route: &str, status_class: &str, duration_secs: f64) {
+        histogram!("event_bus_http_request_duration_seconds", "route" => route.to_string(), "status_class" => status_class.to_string())
+            .record(duration_secs);
+    }
+
+    /// Record a subscription rejected because `routing.max_topics` was reached
+    pub fn record_topic_limit_rejection() {
+        counter!("event_bus_topic_limit_rejections_total").increment(1);
+    }
+
+    /// Record a subscription rejected because its pattern reached
+    /// `routing.max_subscribers_per_topic`
+    pub fn record_subscriber_limit_rejection() {
+        counter!("event_bus_subscriber_limit_rejections_total").increment(1);
+    }
+
+    /// Record a background redelivery outcome (delivered, exhausted, closed,
+    /// or backlog_full when the subscriber already had a full retry backlog)
+    pub fn record_delivery_retry(outcome: &str) {
+        counter!("event_bus_delivery_retries_total", "outcome" => outcome.to_string()).increment(1);
+    }
+
+    /// Record an event dropped for exceeding its topic's TTL
+    pub fn record_event_expired(topic: &str) {
+        counter!("event_bus_events_expired_total", "topic" => topic.to_string()).increment(1);
+    }
+
+    /// Record an event added to the dead-letter queue, by reason
+    pub fn record_dead_letter(reason: &str) {
+        counter!("event_bus_dead_letters_total", "reason" => reason.to_string()).increment(1);
+    }
+
+    /// Record a dead letter discarded because the queue was full
+    pub fn record_dead_letter_overflow() {
+        counter!("event_bus_dead_letter_overflow_total").increment(1);
+    }
+
+    /// Record a dead letter taken from the queue for another delivery attempt
+    pub fn record_dlq_reprocess_attempt() {
+        counter!("event_bus_dlq_reprocess_attempts_total").increment(1);
+    }
+
+    /// Record a dead letter failed permanently after its last reprocess attempt
+    pub fn record_dlq_permanent_failure() {
+        counter!("event_bus_dlq_permanent_failures_total").increment(1);
+    }
+
+    /// Update the number of events waiting to be published after a delay
+    pub fn update_scheduled_events(count: f64) {
+        gauge!("event_bus_scheduled_events").set(count);
+    }
+
+    /// Record a failed push of the metrics snapshot to the push endpoint
+    pub fn record_metrics_push_failure() {
+        counter!("event_bus_metrics_push_failures_total").increment(1);
+    }
+
     /// Record batch size
     pub fn record_batch_size(size: f64) {
         histogram!("event_bus_batch_size").record(size);
@@ -71,13 +149,139 @@ impl ProcessingTimer {
 }
 
 /// Initialize the metrics subsystem
-pub fn init_metrics() {
+///
+/// Must be called from within the Tokio runtime. The scrape endpoint is always
+/// served; a push exporter is started as well when `push_endpoint` is set.
+pub fn init_metrics(config: &MetricsConfig) {
     // Initialize Prometheus exporter
     let builder = metrics_exporter_prometheus::PrometheusBuilder::new();
-    builder
+    let (recorder, exporter) = builder
         .with_http_listener(([0, 0, 0, 0], 9090))
-        .install()
-        .expect("Failed to install Prometheus exporter");
+        .build()
+        .expect("Failed to build Prometheus exporter");
+    let handle = recorder.handle();
+    tokio::spawn(exporter);
+    metrics::set_global_recorder(recorder).expect("Failed to install Prometheus recorder");
 
     tracing::info!("Metrics server listening on :9090/metrics");
+
+    if let Some(endpoint) = &config.push_endpoint {
+        let interval = Duration::from_secs(config.export_interval_secs);
+        spawn_push_exporter(handle, endpoint.clone(), interval);
+        tracing::info!(
+            "Pushing metrics to {} every {}s",
+            endpoint,
+            config.export_interval_secs
+        );
+    }
+}
+
+/// Push the current metrics snapshot to `endpoint` every `interval`
+///
+/// The snapshot is POSTed in the Prometheus text format. Failed pushes are
+/// logged and counted but never stop the loop, so an unreachable push target
+/// cannot affect request handling.
+pub fn spawn_push_exporter(
+    handle: PrometheusHandle,
+    endpoint: String,
+    interval: Duration,
+) -> JoinHandle<()> {
+    let client = reqwest::Client::builder()
+        .timeout(interval)
+        .build()
+        .unwrap_or_default();
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick completes immediately; push once a full interval has passed
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            handle.run_upkeep();
+            if let Err(e) = push_snapshot(&client, &endpoint, handle.render()).await {
+                warn!("Failed to push metrics to {}: {}", endpoint, e);
+                EventMetrics::record_metrics_push_failure();
+            }
+        }
+    })
+}
+
+async fn push_snapshot(
+    client: &reqwest::Client,
+    endpoint: &str,
+    body: String,
+) -> reqwest::Result<()> {
+    client
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_push_exporter_sends_metrics_recorded_during_interval() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let app = Router::new().route(
+            "/metrics/job/event-bus",
+            post(move |body: String| async move {
+                let _ = tx.send(body);
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let exporter = spawn_push_exporter(
+            recorder.handle(),
+            format!("http://{addr}/metrics/job/event-bus"),
+            Duration::from_millis(50),
+        );
+
+        metrics::with_local_recorder(&recorder, || {
+            EventMetrics::record_events_routed("game.state", 3);
+        });
+
+        let pushed = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            pushed.contains(r#"event_bus_events_routed_total{topic="game.state"} 3"#),
+            "{pushed}"
+        );
+        exporter.abort();
+    }
+
+    #[tokio::test]
+    async fn test_push_exporter_survives_unreachable_endpoint() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let exporter = spawn_push_exporter(
+            recorder.handle(),
+            format!("http://{addr}/metrics"),
+            Duration::from_millis(20),
+        );
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!exporter.is_finished());
+        exporter.abort();
+    }
 }
@@ -1,12 +1,49 @@
 use anyhow::{anyhow, Result};
 use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::api::models::JsonEvent;
-use crate::proto::{event, Event, EventType};
+use crate::proto::{
+    event, Blind, Card, CardsDiscardedEvent, ConnectionTestEvent, Consumable, ConsumableProperties,
+    Deck, Event, EventType, GamePhase, GameStateEvent, HandPlayedEvent, HeartbeatEvent, Joker,
+    JokerProperties, JokersChangedEvent, MoneyChangedEvent, PhaseChangedEvent, RoundChangedEvent,
+    RoundCompleteEvent, ScoreChangedEvent, ScoreHistory, ShopItem,
+};
+
+/// Event type strings the REST API accepts, and the proto type of each
+const JSON_TYPES: [(&str, EventType); 11] = [
+    ("GAME_STATE", EventType::GameState),
+    ("HEARTBEAT", EventType::Heartbeat),
+    ("MONEY_CHANGED", EventType::MoneyChanged),
+    ("SCORE_CHANGED", EventType::ScoreChanged),
+    ("HAND_PLAYED", EventType::HandPlayed),
+    ("CARDS_DISCARDED", EventType::CardsDiscarded),
+    ("JOKERS_CHANGED", EventType::JokersChanged),
+    ("ROUND_CHANGED", EventType::RoundChanged),
+    ("PHASE_CHANGED", EventType::PhaseChanged),
+    ("ROUND_COMPLETE", EventType::RoundComplete),
+    ("CONNECTION_TEST", EventType::ConnectionTest),
+];
 
 /// Convert JSON event from BalatroMCP to Protocol Buffer event
+///
+/// Rejects event types the bus does not know.
 pub fn json_to_proto_event(json_event: JsonEvent) -> Result<Event> {
+    convert(json_event, false)
+}
+
+/// Convert like `json_to_proto_event`, but map unknown event types to
+/// `EventType::Unspecified` instead of failing
+///
+/// The original type string is kept in `raw_type`, which the router uses to
+/// publish such events on `events.unknown.{raw_type}`.
+pub fn json_to_proto_event_preserving_unknown(json_event: JsonEvent) -> Result<Event> {
+    convert(json_event, true)
+}
+
+fn convert(json_event: JsonEvent, allow_unknown: bool) -> Result<Event> {
     // Validate required fields are not empty
     if json_event.event_type.is_empty() {
         return Err(anyhow!("Event type cannot be empty"));
@@ -15,172 +52,1083 @@ pub fn json_to_proto_event(json_event: JsonEvent) -> Result<Event> {
         return Err(anyhow!("Event source cannot be empty"));
     }
 
-    let event_type = match json_event.event_type.as_str() {
-        "GAME_STATE" => EventType::GameState as i32,
-        "HEARTBEAT" => EventType::Heartbeat as i32,
-        "MONEY_CHANGED" => EventType::MoneyChanged as i32,
-        "SCORE_CHANGED" => EventType::ScoreChanged as i32,
-        "HAND_PLAYED" => EventType::HandPlayed as i32,
-        "CARDS_DISCARDED" => EventType::CardsDiscarded as i32,
-        "JOKERS_CHANGED" => EventType::JokersChanged as i32,
-        "ROUND_CHANGED" => EventType::RoundChanged as i32,
-        "PHASE_CHANGED" => EventType::PhaseChanged as i32,
-        "ROUND_COMPLETE" => EventType::RoundComplete as i32,
-        "CONNECTION_TEST" => EventType::ConnectionTest as i32,
-        _ => return Err(anyhow!("Unknown event type: {}", json_event.event_type)),
+    let event_type = match JSON_TYPES
+        .iter()
+        .find(|(name, _)| *name == json_event.event_type)
+    {
+        Some((_, event_type)) => *event_type as i32,
+        None if allow_unknown => EventType::Unspecified as i32,
+        None => return Err(anyhow!("Unknown event type: {}", json_event.event_type)),
     };
 
     let timestamp = json_event
         .timestamp
         .unwrap_or_else(|| Utc::now().timestamp_millis());
 
+    let correlation_id = json_event
+        .correlation_id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| json_event.source.clone());
+
     let mut proto_event = Event {
         event_id: Uuid::new_v4().to_string(),
         timestamp,
         r#type: event_type,
+        raw_type: json_event.event_type,
         source: json_event.source,
+        correlation_id,
+        causation_id: json_event.causation_id.unwrap_or_default(),
         version: json_event.version.unwrap_or(1),
         payload: None,
         metadata: json_event.headers.unwrap_or_default(),
         ..Default::default()
     };
 
-    // Convert payload based on event type
-    proto_event.payload = match EventType::try_from(event_type).ok() {
-        Some(EventType::GameState) => Some(event::Payload::GameState(parse_game_state(
-            json_event.payload,
-        )?)),
-        Some(EventType::Heartbeat) => Some(event::Payload::Heartbeat(parse_heartbeat(
-            json_event.payload,
-        )?)),
-        Some(EventType::MoneyChanged) => Some(event::Payload::MoneyChanged(parse_money_changed(
-            json_event.payload,
-        )?)),
-        Some(EventType::ConnectionTest) => Some(event::Payload::ConnectionTest(
-            parse_connection_test(json_event.payload)?,
-        )),
-        // TODO: Implement other event type parsers
-        _ => None,
-    };
+    proto_event.payload = parse_payload(event_type, &json_event.payload)?;
 
     Ok(proto_event)
 }
 
-use crate::proto::{
-    ConnectionTestEvent, GamePhase, GameStateEvent, HeartbeatEvent, MoneyChangedEvent,
-};
+/// Convert a proto event back into the JSON shape BalatroMCP publishes,
+/// inverting `json_to_proto_event`
+///
+/// Payload fields are written under the names `json_to_proto_event` reads,
+/// so a converted event can be posted back to the REST API. Fields the JSON
+/// left out come back with their proto defaults, and `f32` fields (a blind's
+/// `mult`) come back rounded to single precision. Events whose type the REST
+/// API does not accept, including unknown ones, are an error.
+pub fn proto_to_json_event(event: &Event) -> Result<JsonEvent> {
+    let event_type = match JSON_TYPES
+        .iter()
+        .find(|(_, event_type)| *event_type as i32 == event.r#type)
+    {
+        Some((name, _)) => name.to_string(),
+        None if event.r#type == EventType::Unspecified as i32 => {
+            return Err(anyhow!(
+                "Event {} has unknown type `{}` and cannot be converted to JSON",
+                event.event_id,
+                event.raw_type
+            ))
+        }
+        None => {
+            let name = EventType::try_from(event.r#type)
+                .map(|t| t.as_str_name().to_string())
+                .unwrap_or_else(|_| event.r#type.to_string());
+            return Err(anyhow!(
+                "Event {} has type {name}, which the REST API does not accept",
+                event.event_id
+            ));
+        }
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    Ok(JsonEvent {
+        event_type,
+        source: event.source.clone(),
+        timestamp: Some(event.timestamp),
+        version: Some(event.version),
+        payload: event
+            .payload
+            .as_ref()
+            .map(payload_to_json)
+            .unwrap_or_else(|| json!({})),
+        headers: (!event.metadata.is_empty()).then(|| event.metadata.clone()),
+        correlation_id: non_empty(&event.correlation_id),
+        causation_id: non_empty(&event.causation_id),
+    })
+}
+
+/// Typed payload for a known event type
+///
+/// Types with required fields reject payloads missing them. Numbers outside
+/// the `int32` range of a proto field are clamped to it and fractional
+/// numbers truncated; `NaN` and infinities cannot be written in JSON (they
+/// serialize as `null`) so they read as missing. Only the fields below are
+/// read: any other nesting in a payload is ignored, however deep.
+fn parse_payload(event_type: i32, payload: &Value) -> Result<Option<event::Payload>> {
+    let Ok(event_type) = EventType::try_from(event_type) else {
+        return Ok(None);
+    };
+    let name = event_type.as_str_name().trim_start_matches("EVENT_TYPE_");
+    let fields = Fields::new(name.to_string(), payload);
 
-fn parse_game_state(payload: serde_json::Value) -> Result<GameStateEvent> {
-    // Basic parsing - expand as needed
-    let mut game_state = GameStateEvent {
-        in_game: payload
-            .get("in_game")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false),
-        game_id: payload
-            .get("game_id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string(),
-        ante: payload.get("ante").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-        round: payload.get("round").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-        hand_number: payload
-            .get("hand_number")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        chips: payload.get("chips").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-        mult: payload.get("mult").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-        money: payload.get("money").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-        hand_size: payload
-            .get("hand_size")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        hands_remaining: payload
-            .get("hands_remaining")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        discards_remaining: payload
-            .get("discards_remaining")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        // Initialize with defaults
-        jokers: vec![],
-        hand: vec![],
-        deck: None,
-        consumables: vec![],
-        shop_items: std::collections::HashMap::new(),
-        game_state: GamePhase::PhaseUnspecified as i32,
-        ui_state: String::new(),
-        blind: None,
-        frame_count: 0,
-        score_history: std::collections::HashMap::new(),
-        changes: vec![],
-        initial: false,
-        debug: false,
+    let payload = match event_type {
+        EventType::GameState => event::Payload::GameState(parse_game_state(&fields)?),
+        EventType::Heartbeat => event::Payload::Heartbeat(HeartbeatEvent {
+            version: fields.string("version")?.unwrap_or_default(),
+            uptime: fields.int64("uptime")?.unwrap_or(0),
+            headless: fields.boolean("headless")?.unwrap_or(false),
+            game_state: fields.string("game_state")?.unwrap_or_default(),
+        }),
+        EventType::MoneyChanged => {
+            let old_value = fields.required_int("old_value")?;
+            let new_value = fields.required_int("new_value")?;
+            event::Payload::MoneyChanged(MoneyChangedEvent {
+                old_value,
+                new_value,
+                difference: fields
+                    .int("difference")?
+                    .unwrap_or_else(|| new_value.saturating_sub(old_value)),
+            })
+        }
+        EventType::ScoreChanged => event::Payload::ScoreChanged(ScoreChangedEvent {
+            old_chips: fields.required_int("old_chips")?,
+            new_chips: fields.required_int("new_chips")?,
+            old_mult: fields.required_int("old_mult")?,
+            new_mult: fields.required_int("new_mult")?,
+        }),
+        EventType::HandPlayed => event::Payload::HandPlayed(HandPlayedEvent {
+            hands_remaining: fields.required_int("hands_remaining")?,
+            hand_number: fields.int("hand_number")?.unwrap_or(0),
+        }),
+        EventType::CardsDiscarded => event::Payload::CardsDiscarded(CardsDiscardedEvent {
+            discards_remaining: fields.required_int("discards_remaining")?,
+        }),
+        EventType::JokersChanged => event::Payload::JokersChanged(JokersChangedEvent {
+            old_count: fields.required_int("old_count")?,
+            new_count: fields.required_int("new_count")?,
+            jokers: fields.list("jokers", parse_joker)?,
+        }),
+        EventType::RoundChanged => event::Payload::RoundChanged(RoundChangedEvent {
+            ante: fields.required_int("ante")?,
+            round: fields.required_int("round")?,
+        }),
+        EventType::PhaseChanged => event::Payload::PhaseChanged(PhaseChangedEvent {
+            // Absent on the first change after the mod loads
+            old_phase: fields.string("old_phase")?.unwrap_or_default(),
+            new_phase: fields.required_string("new_phase")?,
+        }),
+        EventType::RoundComplete => event::Payload::RoundComplete(RoundCompleteEvent {
+            ante: fields.required_int("ante")?,
+            round: fields.required_int("round")?,
+            score: fields.int("score")?.unwrap_or(0),
+            money: fields.int("money")?.unwrap_or(0),
+        }),
+        EventType::ConnectionTest => event::Payload::ConnectionTest(ConnectionTestEvent {
+            message: fields.string("message")?.unwrap_or_default(),
+        }),
+        _ => return Ok(None),
     };
+    Ok(Some(payload))
+}
+
+fn parse_game_state(fields: &Fields) -> Result<GameStateEvent> {
+    let int = |key| fields.int(key).map(Option::unwrap_or_default);
+    Ok(GameStateEvent {
+        in_game: fields.boolean("in_game")?.unwrap_or(false),
+        game_id: fields.string("game_id")?.unwrap_or_default(),
+        ante: int("ante")?,
+        round: int("round")?,
+        hand_number: int("hand_number")?,
+        chips: int("chips")?,
+        mult: int("mult")?,
+        money: int("money")?,
+        hand_size: int("hand_size")?,
+        hands_remaining: int("hands_remaining")?,
+        discards_remaining: int("discards_remaining")?,
+        jokers: fields.list("jokers", parse_joker)?,
+        hand: fields.list("hand", parse_card)?,
+        deck: fields.object("deck", |deck| {
+            Ok(Deck {
+                remaining_count: deck.int("remaining_count")?.unwrap_or(0),
+                cards_remaining: deck.list("cards_remaining", parse_card)?,
+                full_deck: deck.list("full_deck", parse_card)?,
+            })
+        })?,
+        consumables: fields.list("consumables", parse_consumable)?,
+        shop_items: fields.map("shop_items", |item| {
+            Ok(ShopItem {
+                name: item.string("name")?.unwrap_or_default(),
+                cost: item.int("cost")?.unwrap_or(0),
+                rarity: item.string("rarity")?.unwrap_or_default(),
+            })
+        })?,
+        game_state: fields
+            .string("game_state")?
+            .and_then(|phase| GamePhase::from_str_name(&format!("PHASE_{phase}")))
+            .unwrap_or(GamePhase::PhaseUnspecified) as i32,
+        ui_state: fields.string("ui_state")?.unwrap_or_default(),
+        blind: fields.object("blind", |blind| {
+            Ok(Blind {
+                name: blind.string("name")?.unwrap_or_default(),
+                chips: blind.int("chips")?.unwrap_or(0),
+                chip_text: blind.string("chip_text")?.unwrap_or_default(),
+                mult: blind.float("mult")?.unwrap_or(1.0),
+                defeated: blind.boolean("defeated")?.unwrap_or(false),
+                boss: blind.boolean("boss")?.unwrap_or(false),
+            })
+        })?,
+        frame_count: int("frame_count")?,
+        score_history: fields.map("score_history", |history| {
+            Ok(ScoreHistory {
+                scores: history.ints()?,
+            })
+        })?,
+        // Nested change events are not accepted over JSON
+        changes: Vec::new(),
+        initial: fields.boolean("initial")?.unwrap_or(false),
+        debug: fields.boolean("debug")?.unwrap_or(false),
+    })
+}
+
+fn parse_joker(joker: &Fields) -> Result<Joker> {
+    Ok(Joker {
+        id: joker.string("id")?.unwrap_or_default(),
+        name: joker.string("name")?.unwrap_or_default(),
+        position: joker.int("position")?.unwrap_or(0),
+        properties: joker.object("properties", |properties| {
+            Ok(JokerProperties {
+                mult: properties.int("mult")?.unwrap_or(0),
+                chips: properties.int("chips")?.unwrap_or(0),
+                cost: properties.int("cost")?.unwrap_or(0),
+                sell_value: properties.int("sell_value")?.unwrap_or(0),
+                edition: properties.string("edition")?.unwrap_or_default(),
+            })
+        })?,
+    })
+}
+
+fn parse_consumable(consumable: &Fields) -> Result<Consumable> {
+    Ok(Consumable {
+        id: consumable.string("id")?.unwrap_or_default(),
+        name: consumable.string("name")?.unwrap_or_default(),
+        card_type: consumable.string("card_type")?.unwrap_or_default(),
+        position: consumable.int("position")?.unwrap_or(0),
+        properties: consumable.object("properties", |properties| {
+            Ok(ConsumableProperties {
+                cost: properties.int("cost")?.unwrap_or(0),
+                edition: properties.string("edition")?.unwrap_or_default(),
+            })
+        })?,
+    })
+}
+
+/// Game rank names (`card.base.value`) in `Rank` order, starting at Ace
+const RANK_NAMES: [&str; 13] = [
+    "Ace", "2", "3", "4", "5", "6", "7", "8", "9", "10", "Jack", "Queen", "King",
+];
+/// Game suit names (`card.base.suit`) in `Suit` order, starting at Spades
+const SUIT_NAMES: [&str; 4] = ["Spades", "Hearts", "Clubs", "Diamonds"];
+
+fn parse_card(card: &Fields) -> Result<Card> {
+    let index_of = |names: &[&str], name: Option<String>| {
+        name.and_then(|name| names.iter().position(|n| *n == name))
+            .map_or(0, |i| i as i32 + 1)
+    };
+    Ok(Card {
+        id: card.string("id")?.unwrap_or_default(),
+        rank: index_of(&RANK_NAMES, card.string("rank")?),
+        suit: index_of(&SUIT_NAMES, card.string("suit")?),
+        enhancement: card.string("enhancement")?.unwrap_or_default(),
+        edition: card.string("edition")?.unwrap_or_default(),
+        seal: card.string("seal")?.unwrap_or_default(),
+        position: card.int("position")?.unwrap_or(0),
+    })
+}
+
+/// Typed reads from one JSON object, naming where a bad field was in errors
+///
+/// `null` counts as absent, and a non-object has no fields.
+struct Fields<'a> {
+    path: String,
+    value: &'a Value,
+}
 
-    // Parse game_state/phase if present
-    if let Some(phase_str) = payload.get("game_state").and_then(|v| v.as_str()) {
-        game_state.game_state = match phase_str {
-            "MENU" => GamePhase::PhaseMenu as i32,
-            "BLIND_SELECT" => GamePhase::PhaseBlindSelect as i32,
-            "SHOP" => GamePhase::PhaseShop as i32,
-            "PLAYING" => GamePhase::PhasePlaying as i32,
-            "GAME_OVER" => GamePhase::PhaseGameOver as i32,
-            _ => GamePhase::PhaseUnspecified as i32,
+impl<'a> Fields<'a> {
+    fn new(path: String, value: &'a Value) -> Self {
+        Self { path, value }
+    }
+
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.value.get(key).filter(|v| !v.is_null())
+    }
+
+    fn invalid(&self, key: &str, expected: &str, got: &Value) -> anyhow::Error {
+        anyhow!("{} field `{key}` must be {expected}, got {got}", self.path)
+    }
+
+    fn missing(&self, key: &str) -> anyhow::Error {
+        anyhow!("{} payload is missing required field `{key}`", self.path)
+    }
+
+    fn int(&self, key: &str) -> Result<Option<i32>> {
+        self.get(key)
+            .map(|v| to_i32(v).ok_or_else(|| self.invalid(key, "a number", v)))
+            .transpose()
+    }
+
+    fn required_int(&self, key: &str) -> Result<i32> {
+        self.int(key)?.ok_or_else(|| self.missing(key))
+    }
+
+    fn int64(&self, key: &str) -> Result<Option<i64>> {
+        self.get(key)
+            .map(|v| {
+                v.as_i64()
+                    .or_else(|| v.as_f64().map(|f| f as i64))
+                    .ok_or_else(|| self.invalid(key, "a number", v))
+            })
+            .transpose()
+    }
+
+    fn float(&self, key: &str) -> Result<Option<f32>> {
+        self.get(key)
+            .map(|v| {
+                v.as_f64()
+                    .map(|f| f as f32)
+                    .ok_or_else(|| self.invalid(key, "a number", v))
+            })
+            .transpose()
+    }
+
+    fn boolean(&self, key: &str) -> Result<Option<bool>> {
+        self.get(key)
+            .map(|v| v.as_bool().ok_or_else(|| self.invalid(key, "a boolean", v)))
+            .transpose()
+    }
+
+    /// Strings, or numbers and booleans in their JSON spelling
+    fn string(&self, key: &str) -> Result<Option<String>> {
+        self.get(key)
+            .map(|v| match v {
+                Value::String(s) => Ok(s.clone()),
+                Value::Number(_) | Value::Bool(_) => Ok(v.to_string()),
+                _ => Err(self.invalid(key, "a string", v)),
+            })
+            .transpose()
+    }
+
+    fn required_string(&self, key: &str) -> Result<String> {
+        self.string(key)?.ok_or_else(|| self.missing(key))
+    }
+
+    fn object<T>(&self, key: &str, parse: impl Fn(&Fields) -> Result<T>) -> Result<Option<T>> {
+        self.get(key)
+            .map(|v| parse(&Fields::new(format!("{}.{key}", self.path), v)))
+            .transpose()
+    }
+
+    /// Array field parsed element by element; Lua encodes an empty table as
+    /// `{}`, so an empty object is accepted as an empty list
+    fn list<T>(&self, key: &str, parse: impl Fn(&Fields) -> Result<T>) -> Result<Vec<T>> {
+        match self.get(key) {
+            None => Ok(Vec::new()),
+            Some(Value::Array(items)) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| parse(&Fields::new(format!("{}.{key}[{i}]", self.path), item)))
+                .collect(),
+            Some(Value::Object(map)) if map.is_empty() => Ok(Vec::new()),
+            Some(v) => Err(self.invalid(key, "an array", v)),
+        }
+    }
+
+    fn map<T>(
+        &self,
+        key: &str,
+        parse: impl Fn(&Fields) -> Result<T>,
+    ) -> Result<HashMap<String, T>> {
+        match self.get(key) {
+            None => Ok(HashMap::new()),
+            Some(Value::Object(entries)) => entries
+                .iter()
+                .map(|(name, entry)| {
+                    let path = format!("{}.{key}.{name}", self.path);
+                    Ok((name.clone(), parse(&Fields::new(path, entry))?))
+                })
+                .collect(),
+            Some(v) => Err(self.invalid(key, "an object", v)),
+        }
+    }
+
+    /// This value as a list of integers
+    fn ints(&self) -> Result<Vec<i32>> {
+        let items = match self.value {
+            Value::Array(items) => items.as_slice(),
+            Value::Object(map) if map.is_empty() => &[],
+            v => {
+                return Err(anyhow!(
+                    "{} must be an array of numbers, got {v}",
+                    self.path
+                ))
+            }
         };
+        items
+            .iter()
+            .map(|v| {
+                to_i32(v).ok_or_else(|| anyhow!("{} must hold only numbers, got {v}", self.path))
+            })
+            .collect()
     }
+}
 
-    if let Some(ui_state) = payload.get("ui_state").and_then(|v| v.as_str()) {
-        game_state.ui_state = ui_state.to_string();
+/// A JSON number as `int32`, clamped to its range and truncated toward zero
+fn to_i32(value: &Value) -> Option<i32> {
+    if let Some(i) = value.as_i64() {
+        return Some(i.clamp(i32::MIN as i64, i32::MAX as i64) as i32);
     }
+    // Float-to-int `as` saturates, covering u64 values past i64::MAX too
+    value.as_f64().map(|f| f as i32)
+}
 
-    Ok(game_state)
+fn payload_to_json(payload: &event::Payload) -> Value {
+    use event::Payload::*;
+    match payload {
+        GameState(state) => game_state_to_json(state),
+        Heartbeat(heartbeat) => json!({
+            "version": heartbeat.version,
+            "uptime": heartbeat.uptime,
+            "headless": heartbeat.headless,
+            "game_state": heartbeat.game_state,
+        }),
+        MoneyChanged(money) => json!({
+            "old_value": money.old_value,
+            "new_value": money.new_value,
+            "difference": money.difference,
+        }),
+        ScoreChanged(score) => json!({
+            "old_chips": score.old_chips,
+            "new_chips": score.new_chips,
+            "old_mult": score.old_mult,
+            "new_mult": score.new_mult,
+        }),
+        HandPlayed(hand) => json!({
+            "hands_remaining": hand.hands_remaining,
+            "hand_number": hand.hand_number,
+        }),
+        CardsDiscarded(discard) => json!({ "discards_remaining": discard.discards_remaining }),
+        JokersChanged(jokers) => json!({
+            "old_count": jokers.old_count,
+            "new_count": jokers.new_count,
+            "jokers": jokers.jokers.iter().map(joker_to_json).collect::<Vec<_>>(),
+        }),
+        RoundChanged(round) => json!({ "ante": round.ante, "round": round.round }),
+        PhaseChanged(phase) => json!({
+            "old_phase": phase.old_phase,
+            "new_phase": phase.new_phase,
+        }),
+        RoundComplete(round) => json!({
+            "ante": round.ante,
+            "round": round.round,
+            "score": round.score,
+            "money": round.money,
+        }),
+        ConnectionTest(test) => json!({ "message": test.message }),
+        _ => json!({}),
+    }
 }
 
-fn parse_heartbeat(payload: serde_json::Value) -> Result<HeartbeatEvent> {
-    Ok(HeartbeatEvent {
-        version: payload
-            .get("version")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string(),
-        uptime: payload.get("uptime").and_then(|v| v.as_i64()).unwrap_or(0),
-        headless: payload
-            .get("headless")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false),
-        game_state: payload
-            .get("game_state")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string(),
-    })
+fn game_state_to_json(state: &GameStateEvent) -> Value {
+    let cards = |cards: &[Card]| cards.iter().map(card_to_json).collect::<Vec<_>>();
+    let mut json = json!({
+        "in_game": state.in_game,
+        "game_id": state.game_id,
+        "ante": state.ante,
+        "round": state.round,
+        "hand_number": state.hand_number,
+        "chips": state.chips,
+        "mult": state.mult,
+        "money": state.money,
+        "hand_size": state.hand_size,
+        "hands_remaining": state.hands_remaining,
+        "discards_remaining": state.discards_remaining,
+        "jokers": state.jokers.iter().map(joker_to_json).collect::<Vec<_>>(),
+        "hand": cards(&state.hand),
+        "consumables": state.consumables.iter().map(|c| json!({
+            "id": c.id,
+            "name": c.name,
+            "card_type": c.card_type,
+            "position": c.position,
+            "properties": c.properties.as_ref().map(|p| json!({
+                "cost": p.cost,
+                "edition": p.edition,
+            })),
+        })).collect::<Vec<_>>(),
+        "shop_items": state.shop_items.iter().map(|(name, item)| (name.clone(), json!({
+            "name": item.name,
+            "cost": item.cost,
+            "rarity": item.rarity,
+        }))).collect::<serde_json::Map<_, _>>(),
+        "ui_state": state.ui_state,
+        "frame_count": state.frame_count,
+        "score_history": state.score_history.iter().map(|(ante, history)| {
+            (ante.clone(), json!(history.scores))
+        }).collect::<serde_json::Map<_, _>>(),
+        "initial": state.initial,
+        "debug": state.debug,
+    });
+
+    if let Some(deck) = &state.deck {
+        json["deck"] = json!({
+            "remaining_count": deck.remaining_count,
+            "cards_remaining": cards(&deck.cards_remaining),
+            "full_deck": cards(&deck.full_deck),
+        });
+    }
+    if let Some(blind) = &state.blind {
+        json["blind"] = json!({
+            "name": blind.name,
+            "chips": blind.chips,
+            "chip_text": blind.chip_text,
+            "mult": blind.mult,
+            "defeated": blind.defeated,
+            "boss": blind.boss,
+        });
+    }
+    match GamePhase::try_from(state.game_state) {
+        Ok(GamePhase::PhaseUnspecified) | Err(_) => {}
+        Ok(phase) => json["game_state"] = json!(phase.as_str_name().trim_start_matches("PHASE_")),
+    }
+    json
 }
 
-fn parse_money_changed(payload: serde_json::Value) -> Result<MoneyChangedEvent> {
-    Ok(MoneyChangedEvent {
-        old_value: payload
-            .get("old_value")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        new_value: payload
-            .get("new_value")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        difference: payload
-            .get("difference")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
+fn joker_to_json(joker: &Joker) -> Value {
+    json!({
+        "id": joker.id,
+        "name": joker.name,
+        "position": joker.position,
+        "properties": joker.properties.as_ref().map(|p| json!({
+            "mult": p.mult,
+            "chips": p.chips,
+            "cost": p.cost,
+            "sell_value": p.sell_value,
+            "edition": p.edition,
+        })),
     })
 }
 
-fn parse_connection_test(payload: serde_json::Value) -> Result<ConnectionTestEvent> {
-    Ok(ConnectionTestEvent {
-        message: payload
-            .get("message")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string(),
+fn card_to_json(card: &Card) -> Value {
+    let name = |names: &[&str], index: i32| {
+        usize::try_from(index - 1)
+            .ok()
+            .and_then(|i| names.get(i))
+            .map(|name| name.to_string())
+    };
+    json!({
+        "id": card.id,
+        "rank": name(&RANK_NAMES, card.rank),
+        "suit": name(&SUIT_NAMES, card.suit),
+        "enhancement": card.enhancement,
+        "edition": card.edition,
+        "seal": card.seal,
+        "position": card.position,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use balatro_emulator::{BalatroRng, SeedType};
+
+    fn heartbeat(correlation_id: Option<&str>, causation_id: Option<&str>) -> JsonEvent {
+        JsonEvent {
+            event_type: "HEARTBEAT".to_string(),
+            source: "BalatroMCP".to_string(),
+            timestamp: None,
+            version: None,
+            payload: serde_json::json!({}),
+            headers: None,
+            correlation_id: correlation_id.map(str::to_string),
+            causation_id: causation_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_correlation_and_causation_ids_carried_through() {
+        let event = json_to_proto_event(heartbeat(Some("game-42"), Some("evt-1"))).unwrap();
+        assert_eq!(event.correlation_id, "game-42");
+        assert_eq!(event.causation_id, "evt-1");
+    }
+
+    #[test]
+    fn test_correlation_id_defaults_to_source() {
+        let event = json_to_proto_event(heartbeat(None, None)).unwrap();
+        assert_eq!(event.correlation_id, "BalatroMCP");
+        assert_eq!(event.causation_id, "");
+
+        let event = json_to_proto_event(heartbeat(Some(""), None)).unwrap();
+        assert_eq!(event.correlation_id, "BalatroMCP");
+
+        let parsed: JsonEvent = serde_json::from_str(
+            r#"{"type": "HEARTBEAT", "source": "BalatroMCP", "payload": {}, "correlation_id": "game-7"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json_to_proto_event(parsed).unwrap().correlation_id,
+            "game-7"
+        );
+    }
+
+    #[test]
+    fn test_unknown_type_keeps_raw_type() {
+        let mut event = heartbeat(None, None);
+        event.event_type = "MOD_JOKER_SPAWNED".to_string();
+        assert!(json_to_proto_event(event.clone()).is_err());
+
+        let converted = json_to_proto_event_preserving_unknown(event).unwrap();
+        assert_eq!(converted.r#type, EventType::Unspecified as i32);
+        assert_eq!(converted.raw_type, "MOD_JOKER_SPAWNED");
+        assert!(converted.payload.is_none());
+
+        let known = json_to_proto_event_preserving_unknown(heartbeat(None, None)).unwrap();
+        assert_eq!(known.r#type, EventType::Heartbeat as i32);
+        assert_eq!(known.raw_type, "HEARTBEAT");
+    }
+    fn typed(event_type: &str, payload: Value) -> JsonEvent {
+        JsonEvent {
+            event_type: event_type.to_string(),
+            payload,
+            ..heartbeat(None, None)
+        }
+    }
+
+    /// `json -> proto -> json` must return the payload it was given
+    fn assert_round_trip(event_type: &str, payload: Value) {
+        let proto = json_to_proto_event(typed(event_type, payload.clone())).unwrap();
+        assert!(proto.payload.is_some(), "{event_type} has no typed payload");
+        let json = proto_to_json_event(&proto).unwrap();
+        assert_eq!(json.event_type, event_type);
+        assert_eq!(json.payload, payload, "{event_type} payload changed");
+    }
+
+    fn joker(position: i32) -> Value {
+        json!({
+            "id": format!("j_joker_{position}"),
+            "name": "Joker",
+            "position": position,
+            "properties": {
+                "mult": 4,
+                "chips": 0,
+                "cost": 2,
+                "sell_value": 1,
+                "edition": "foil",
+            },
+        })
+    }
+
+    fn card(rank: &str, suit: &str, position: i32) -> Value {
+        json!({
+            "id": format!("c_{rank}_{suit}"),
+            "rank": rank,
+            "suit": suit,
+            "enhancement": "m_glass",
+            "edition": "",
+            "seal": "Red",
+            "position": position,
+        })
+    }
+
+    #[test]
+    fn test_every_event_type_round_trips() {
+        assert_round_trip(
+            "GAME_STATE",
+            json!({
+                "in_game": true,
+                "game_id": "run-1",
+                "ante": 3,
+                "round": 7,
+                "hand_number": 2,
+                "chips": 300,
+                "mult": 12,
+                "money": 25,
+                "hand_size": 8,
+                "hands_remaining": 3,
+                "discards_remaining": 1,
+                "jokers": [joker(1), joker(2)],
+                "hand": [card("Ace", "Spades", 1), card("10", "Diamonds", 2)],
+                "deck": {
+                    "remaining_count": 2,
+                    "cards_remaining": [card("King", "Hearts", 0)],
+                    "full_deck": [card("2", "Clubs", 0), card("Queen", "Hearts", 0)],
+                },
+                "consumables": [{
+                    "id": "c_pluto",
+                    "name": "Pluto",
+                    "card_type": "Planet",
+                    "position": 1,
+                    "properties": { "cost": 3, "edition": "" },
+                }],
+                "shop_items": {
+                    "j_blueprint": { "name": "Blueprint", "cost": 10, "rarity": "3" },
+                },
+                "game_state": "SHOP",
+                "ui_state": "shop",
+                "blind": {
+                    "name": "The Wall",
+                    "chips": 4000,
+                    "chip_text": "4,000",
+                    "mult": 2.5,
+                    "defeated": false,
+                    "boss": true,
+                },
+                "frame_count": 1200,
+                "score_history": { "1": [300, 450], "2": [] },
+                "initial": false,
+                "debug": true,
+            }),
+        );
+        assert_round_trip(
+            "HEARTBEAT",
+            json!({ "version": "1.2.0", "uptime": 86_400_000_000i64, "headless": true, "game_state": "MENU" }),
+        );
+        assert_round_trip(
+            "MONEY_CHANGED",
+            json!({ "old_value": 4, "new_value": 9, "difference": 5 }),
+        );
+        assert_round_trip(
+            "SCORE_CHANGED",
+            json!({ "old_chips": 10, "new_chips": 50, "old_mult": 1, "new_mult": 4 }),
+        );
+        assert_round_trip(
+            "HAND_PLAYED",
+            json!({ "hands_remaining": 3, "hand_number": 2 }),
+        );
+        assert_round_trip("CARDS_DISCARDED", json!({ "discards_remaining": 2 }));
+        assert_round_trip(
+            "JOKERS_CHANGED",
+            json!({ "old_count": 1, "new_count": 2, "jokers": [joker(1), joker(2)] }),
+        );
+        assert_round_trip("ROUND_CHANGED", json!({ "ante": 2, "round": 5 }));
+        assert_round_trip(
+            "PHASE_CHANGED",
+            json!({ "old_phase": "PLAYING", "new_phase": "SHOP" }),
+        );
+        assert_round_trip(
+            "ROUND_COMPLETE",
+            json!({ "ante": 2, "round": 5, "score": 1200, "money": 14 }),
+        );
+        assert_round_trip("CONNECTION_TEST", json!({ "message": "ping" }));
+    }
+
+    #[test]
+    fn test_envelope_round_trips() {
+        let mut event = typed("CARDS_DISCARDED", json!({ "discards_remaining": 2 }));
+        event.timestamp = Some(1_700_000_000_000);
+        event.version = Some(2);
+        event.correlation_id = Some("game-42".to_string());
+        event.causation_id = Some("evt-1".to_string());
+        event.headers = Some(HashMap::from([("trace".to_string(), "abc".to_string())]));
+
+        let json = proto_to_json_event(&json_to_proto_event(event.clone()).unwrap()).unwrap();
+        assert_eq!(json.event_type, event.event_type);
+        assert_eq!(json.source, event.source);
+        assert_eq!(json.timestamp, event.timestamp);
+        assert_eq!(json.version, event.version);
+        assert_eq!(json.headers, event.headers);
+        assert_eq!(json.correlation_id, event.correlation_id);
+        assert_eq!(json.causation_id, event.causation_id);
+
+        let unknown =
+            json_to_proto_event_preserving_unknown(typed("MOD_EVENT", json!({}))).unwrap();
+        let err = proto_to_json_event(&unknown).unwrap_err().to_string();
+        assert!(err.contains("unknown type `MOD_EVENT`"), "{err}");
+
+        let game_start = Event {
+            r#type: EventType::GameStart as i32,
+            ..Default::default()
+        };
+        let err = proto_to_json_event(&game_start).unwrap_err().to_string();
+        assert!(err.contains("EVENT_TYPE_GAME_START"), "{err}");
+    }
+
+    #[test]
+    fn test_missing_required_fields_are_rejected() {
+        let err = json_to_proto_event(typed("MONEY_CHANGED", json!({ "new_value": 9 })))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("MONEY_CHANGED"), "{err}");
+        assert!(err.contains("`old_value`"), "{err}");
+
+        // null is as good as absent
+        let err = json_to_proto_event(typed(
+            "PHASE_CHANGED",
+            json!({ "old_phase": "SHOP", "new_phase": null }),
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("`new_phase`"), "{err}");
+
+        let err = json_to_proto_event(typed("ROUND_CHANGED", json!({ "ante": "two", "round": 1 })))
+            .unwrap_err();
+        assert!(err.to_string().contains("`ante` must be a number"), "{err}");
+
+        let err = json_to_proto_event(typed(
+            "JOKERS_CHANGED",
+            json!({ "old_count": 0, "new_count": 1, "jokers": [{ "position": "first" }] }),
+        ))
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("JOKERS_CHANGED.jokers[0]"),
+            "{err}"
+        );
+
+        // Snapshots have no required fields
+        assert!(json_to_proto_event(typed("GAME_STATE", json!({}))).is_ok());
+    }
+
+    #[test]
+    fn test_out_of_range_numbers_are_clamped() {
+        let proto = json_to_proto_event(typed(
+            "MONEY_CHANGED",
+            json!({ "old_value": i64::MIN, "new_value": i64::MAX }),
+        ))
+        .unwrap();
+        let Some(event::Payload::MoneyChanged(money)) = proto.payload else {
+            panic!("expected a money payload");
+        };
+        assert_eq!(money.old_value, i32::MIN);
+        assert_eq!(money.new_value, i32::MAX);
+        assert_eq!(money.difference, i32::MAX);
+
+        let proto = json_to_proto_event(typed(
+            "ROUND_CHANGED",
+            json!({ "ante": u64::MAX, "round": 2.9 }),
+        ))
+        .unwrap();
+        let Some(event::Payload::RoundChanged(round)) = proto.payload else {
+            panic!("expected a round payload");
+        };
+        assert_eq!((round.ante, round.round), (i32::MAX, 2));
+
+        // serde_json writes NaN and infinities as null, which reads as missing
+        let payload = json!({ "discards_remaining": f64::NAN });
+        assert!(json_to_proto_event(typed("CARDS_DISCARDED", payload)).is_err());
+    }
+
+    #[test]
+    fn test_unread_nesting_is_ignored() {
+        let mut deep = json!("bottom");
+        for _ in 0..100 {
+            deep = json!({ "inner": [deep] });
+        }
+        let proto = json_to_proto_event(typed(
+            "HAND_PLAYED",
+            json!({ "hands_remaining": 2, "extra": deep }),
+        ))
+        .unwrap();
+        assert_eq!(
+            proto_to_json_event(&proto).unwrap().payload,
+            json!({ "hands_remaining": 2, "hand_number": 0 })
+        );
+    }
+    /// Random valid events for the property test below
+    struct EventGen(BalatroRng);
+
+    impl EventGen {
+        fn int(&mut self, key: &str, min: i32, max: i32) -> i32 {
+            let seed = self.0.pseudoseed(key);
+            self.0
+                .pseudorandom(SeedType::Numeric(seed), Some(min), Some(max)) as i32
+        }
+
+        fn flag(&mut self, key: &str) -> bool {
+            self.int(key, 0, 1) == 1
+        }
+
+        fn pick<'a>(&mut self, key: &str, options: &[&'a str]) -> &'a str {
+            options[self.int(key, 1, options.len() as i32) as usize - 1]
+        }
+
+        /// Any `int32`, often at or near the ends of the range
+        fn any_int(&mut self, key: &str) -> i32 {
+            match self.int(&format!("{key}_kind"), 1, 4) {
+                1 => i32::MIN,
+                2 => i32::MAX,
+                _ => self.int(key, -1_000_000, 1_000_000),
+            }
+        }
+
+        fn joker(&mut self, position: i32) -> Value {
+            json!({
+                "id": format!("j_{}", self.int("joker_id", 1, 150)),
+                "name": self.pick("joker_name", &["Joker", "Blueprint", "Brainstorm", "Cavendish"]),
+                "position": position,
+                "properties": {
+                    "mult": self.any_int("joker_mult"),
+                    "chips": self.any_int("joker_chips"),
+                    "cost": self.int("joker_cost", 1, 20),
+                    "sell_value": self.int("joker_sell", 1, 10),
+                    "edition": self.pick("joker_edition", &["", "foil", "holo", "polychrome"]),
+                },
+            })
+        }
+
+        fn card(&mut self, position: i32) -> Value {
+            json!({
+                "id": format!("c_{}", self.int("card_id", 1, 52)),
+                "rank": self.pick("card_rank", &RANK_NAMES),
+                "suit": self.pick("card_suit", &SUIT_NAMES),
+                "enhancement": self.pick("card_enhancement", &["", "m_bonus", "m_glass", "m_steel"]),
+                "edition": self.pick("card_edition", &["", "foil", "negative"]),
+                "seal": self.pick("card_seal", &["", "Red", "Gold", "Purple"]),
+                "position": position,
+            })
+        }
+
+        fn list(&mut self, key: &str, max: i32, item: fn(&mut Self, i32) -> Value) -> Vec<Value> {
+            (1..=self.int(key, 0, max)).map(|i| item(self, i)).collect()
+        }
+
+        fn game_state(&mut self) -> Value {
+            json!({
+                "in_game": self.flag("in_game"),
+                "game_id": format!("run-{}", self.int("game_id", 1, 9999)),
+                "ante": self.int("ante", 1, 8),
+                "round": self.int("round", 1, 24),
+                "hand_number": self.int("hand_number", 0, 4),
+                "chips": self.any_int("chips"),
+                "mult": self.any_int("mult"),
+                "money": self.any_int("money"),
+                "hand_size": self.int("hand_size", 5, 10),
+                "hands_remaining": self.int("hands", 0, 4),
+                "discards_remaining": self.int("discards", 0, 3),
+                "jokers": self.list("jokers", 5, Self::joker),
+                "hand": self.list("hand", 8, Self::card),
+                "deck": {
+                    "remaining_count": self.int("deck_count", 0, 52),
+                    "cards_remaining": self.list("deck_remaining", 4, Self::card),
+                    "full_deck": self.list("deck_full", 4, Self::card),
+                },
+                "consumables": [{
+                    "id": "c_pluto",
+                    "name": "Pluto",
+                    "card_type": self.pick("consumable_type", &["Planet", "Tarot", "Spectral"]),
+                    "position": 1,
+                    "properties": { "cost": self.int("consumable_cost", 1, 6), "edition": "" },
+                }],
+                "shop_items": {
+                    "slot_1": {
+                        "name": self.pick("shop_name", &["Joker", "Buffoon Pack"]),
+                        "cost": self.int("shop_cost", 1, 10),
+                        "rarity": self.int("shop_rarity", 1, 4).to_string(),
+                    },
+                },
+                "game_state": self.pick("phase", &["MENU", "BLIND_SELECT", "PLAYING", "SHOP", "GAME_OVER"]),
+                "ui_state": self.pick("ui_state", &["", "shop", "blind_select"]),
+                "blind": {
+                    "name": self.pick("blind_name", &["Small Blind", "Big Blind", "The Wall"]),
+                    "chips": self.any_int("blind_chips"),
+                    "chip_text": "300",
+                    // Tenths are not exact in f32, so this exercises the rounding
+                    "mult": self.int("blind_mult", 10, 40) as f64 / 10.0,
+                    "defeated": self.flag("blind_defeated"),
+                    "boss": self.flag("blind_boss"),
+                },
+                "frame_count": self.int("frames", 0, i32::MAX - 1),
+                "score_history": {
+                    "1": (0..self.int("history", 0, 3)).map(|_| self.any_int("score")).collect::<Vec<_>>(),
+                },
+                "initial": self.flag("initial"),
+                "debug": self.flag("debug"),
+            })
+        }
+
+        fn payload(&mut self, event_type: EventType) -> Value {
+            match event_type {
+                EventType::GameState => self.game_state(),
+                EventType::Heartbeat => json!({
+                    "version": self.pick("hb_version", &["1.0.0", "1.2.3"]),
+                    "uptime": i64::from(self.int("uptime", 0, i32::MAX - 1)) * 1000,
+                    "headless": self.flag("headless"),
+                    "game_state": self.pick("hb_state", &["MENU", "SHOP"]),
+                }),
+                EventType::MoneyChanged => {
+                    let (old, new) = (self.any_int("old_money"), self.any_int("new_money"));
+                    json!({ "old_value": old, "new_value": new, "difference": new.saturating_sub(old) })
+                }
+                EventType::ScoreChanged => json!({
+                    "old_chips": self.any_int("old_chips"),
+                    "new_chips": self.any_int("new_chips"),
+                    "old_mult": self.any_int("old_mult"),
+                    "new_mult": self.any_int("new_mult"),
+                }),
+                EventType::HandPlayed => json!({
+                    "hands_remaining": self.int("hands", 0, 4),
+                    "hand_number": self.int("hand_number", 0, 100),
+                }),
+                EventType::CardsDiscarded => {
+                    json!({ "discards_remaining": self.int("discards", 0, 3) })
+                }
+                EventType::JokersChanged => json!({
+                    "old_count": self.int("old_count", 0, 5),
+                    "new_count": self.int("new_count", 0, 5),
+                    "jokers": self.list("jokers", 5, Self::joker),
+                }),
+                EventType::RoundChanged => json!({
+                    "ante": self.any_int("ante"),
+                    "round": self.any_int("round"),
+                }),
+                EventType::PhaseChanged => json!({
+                    "old_phase": self.pick("old_phase", &["", "SHOP", "PLAYING"]),
+                    "new_phase": self.pick("new_phase", &["SHOP", "PLAYING", "GAME_OVER"]),
+                }),
+                EventType::RoundComplete => json!({
+                    "ante": self.any_int("ante"),
+                    "round": self.any_int("round"),
+                    "score": self.any_int("score"),
+                    "money": self.any_int("money"),
+                }),
+                EventType::ConnectionTest => {
+                    json!({ "message": format!("ping {}", self.int("ping", 0, 99)) })
+                }
+                _ => json!({}),
+            }
+        }
+    }
+
+    /// The payload after rounding through the `f32` blind multiplier
+    fn f32_normalized(mut payload: Value) -> Value {
+        if let Some(mult) = payload.pointer_mut("/blind/mult") {
+            *mult = json!(mult.as_f64().unwrap() as f32);
+        }
+        payload
+    }
+
+    #[test]
+    fn test_random_events_round_trip_through_proto() {
+        let mut gen = EventGen(BalatroRng::new(SeedType::from("CONVERT")));
+        for case in 0..200 {
+            let (name, event_type) =
+                JSON_TYPES[gen.int("type", 1, JSON_TYPES.len() as i32) as usize - 1];
+            let mut event = typed(name, gen.payload(event_type));
+            event.timestamp = Some(i64::from(gen.int("timestamp", 0, i32::MAX - 1)) * 1000 + 7);
+            event.version = Some(gen.int("version", 1, 3));
+            event.correlation_id = Some(format!("game-{}", gen.int("correlation", 1, 50)));
+
+            // Through the wire format too, where the timestamp is RFC 3339
+            let wire = serde_json::to_string(&event).unwrap();
+            let parsed: JsonEvent = serde_json::from_str(&wire).unwrap();
+            let proto = json_to_proto_event(parsed).unwrap();
+            let back = proto_to_json_event(&proto).unwrap();
+
+            assert_eq!(back.event_type, event.event_type, "case {case}");
+            assert_eq!(back.timestamp, event.timestamp, "case {case}");
+            assert_eq!(back.version, event.version, "case {case}");
+            assert_eq!(back.correlation_id, event.correlation_id, "case {case}");
+            assert_eq!(
+                back.payload,
+                f32_normalized(event.payload),
+                "case {case}: {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamps_are_written_as_rfc3339() {
+        let mut event = heartbeat(None, None);
+        event.timestamp = Some(1_704_067_200_123);
+        let wire = serde_json::to_value(&event).unwrap();
+        assert_eq!(wire["timestamp"], "2024-01-01T00:00:00.123Z");
+
+        let parsed: JsonEvent = serde_json::from_value(wire).unwrap();
+        assert_eq!(parsed.timestamp, Some(1_704_067_200_123));
+        let parsed: JsonEvent = serde_json::from_str(
+            r#"{"type": "HEARTBEAT", "source": "s", "timestamp": 1704067200000, "payload": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.timestamp, Some(1_704_067_200_000));
+        assert!(serde_json::from_str::<JsonEvent>(
+            r#"{"type": "HEARTBEAT", "source": "s", "timestamp": "yesterday", "payload": {}}"#,
+        )
+        .is_err());
+    }
+}
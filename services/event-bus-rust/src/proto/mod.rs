@@ -4,8 +4,16 @@ pub mod converter;
 #[allow(clippy::enum_variant_names)]
 pub mod jimbot {
     tonic::include_proto!("jimbot");
+
+    /// The `EventBus` gRPC service and its messages
+    pub mod eventbus {
+        tonic::include_proto!("jimbot.eventbus");
+    }
 }
 
+/// Encoded descriptors of every compiled proto, for gRPC reflection
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("jimbot_descriptor");
+
 // Re-export commonly used types
 pub use jimbot::*;
 
@@ -17,12 +25,19 @@ use tonic::{Request, Response, Status};
 pub struct PublishResponse {
     pub success: bool,
     pub message: String,
+    /// Number of subscribers the event(s) were delivered to
+    pub routed: usize,
 }
 
 #[derive(Debug, Clone)]
 pub struct SubscribeRequest {
     pub topic_pattern: String,
     pub subscriber_id: String,
+    /// Journal offset to replay from before live events (requires the journal)
+    pub cursor: Option<u64>,
+    /// Overflow strategy for the subscriber's bounded queue; `None` uses
+    /// `routing.subscriber_overflow`
+    pub overflow: Option<crate::routing::overflow::OverflowStrategy>,
 }
 
 // Custom trait for Event Bus gRPC service
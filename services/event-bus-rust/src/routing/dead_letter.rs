@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::metrics::EventMetrics;
+use crate::proto::Event;
+
+/// Metadata key carrying why an event was dead-lettered
+pub const DEAD_LETTER_REASON_KEY: &str = "dead_letter_reason";
+
+/// Metadata key counting how many times an event was reprocessed from the
+/// dead-letter queue
+pub const DEAD_LETTER_ATTEMPTS_KEY: &str = "dead_letter_attempts";
+
+/// Why an event ended up in the dead-letter queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadLetterReason {
+    /// No subscriber matched the event's topic
+    NoSubscribers,
+    /// A subscriber kept rejecting the event through every retry
+    MaxRetriesExhausted,
+    /// A subscriber rejected the event with its retry backlog already full
+    RetryBacklogFull,
+    /// The event was older than its topic's TTL
+    Expired,
+}
+
+impl DeadLetterReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoSubscribers => "no_subscribers",
+            Self::MaxRetriesExhausted => "max_retries_exhausted",
+            Self::RetryBacklogFull => "retry_backlog_full",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// How many times, and how fast, dead letters may be reprocessed
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ReprocessBudget {
+    /// Reprocess attempts per event before it is failed permanently
+    pub max_attempts: u32,
+    /// Events reprocessed per second across the whole queue
+    pub rate_per_sec: u32,
+}
+
+/// Token bucket holding up to one second's worth of reprocessing
+struct RateLimiter {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Bounded in-memory queue of undeliverable events, dropping the oldest when full
+///
+/// Events that fail again after `max_attempts` reprocesses move to a separate,
+/// equally bounded bucket of permanent failures and are not reprocessed again.
+pub(super) struct DeadLetterQueue {
+    queue: Mutex<VecDeque<Event>>,
+    permanent: Mutex<VecDeque<Event>>,
+    capacity: usize,
+    budget: ReprocessBudget,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl DeadLetterQueue {
+    pub(super) fn new(capacity: usize, budget: ReprocessBudget) -> Self {
+        let budget = ReprocessBudget {
+            max_attempts: budget.max_attempts.max(1),
+            rate_per_sec: budget.rate_per_sec.max(1),
+        };
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            permanent: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            budget,
+            limiter: Mutex::new(RateLimiter {
+                tokens: budget.rate_per_sec as f64,
+                refilled: Instant::now(),
+            }),
+        }
+    }
+
+    /// Queue `event`, stamped with `reason` under `DEAD_LETTER_REASON_KEY`
+    ///
+    /// An event that has already used up its reprocess attempts is failed
+    /// permanently instead.
+    pub(super) fn push(&self, mut event: Event, reason: DeadLetterReason) {
+        event.metadata.insert(
+            DEAD_LETTER_REASON_KEY.to_string(),
+            reason.as_str().to_string(),
+        );
+
+        if attempts(&event) >= self.budget.max_attempts {
+            EventMetrics::record_dlq_permanent_failure();
+            push_bounded(&self.permanent, event, self.capacity);
+            return;
+        }
+
+        EventMetrics::record_dead_letter(reason.as_str());
+        push_bounded(&self.queue, event, self.capacity);
+    }
+
+    /// Take the oldest events the rate limit allows for another delivery
+    /// attempt, counting the attempt under `DEAD_LETTER_ATTEMPTS_KEY`
+    ///
+    /// Events that fail again come back through `push`.
+    pub(super) fn reprocess(&self) -> Vec<Event> {
+        let mut limiter = self.limiter.lock().unwrap();
+        let rate = self.budget.rate_per_sec as f64;
+        let now = Instant::now();
+        let elapsed = now.duration_since(limiter.refilled).as_secs_f64();
+        limiter.tokens = (limiter.tokens + elapsed * rate).min(rate);
+        limiter.refilled = now;
+
+        let allowed = limiter.tokens as usize;
+        let mut queue = self.queue.lock().unwrap();
+        let taken = allowed.min(queue.len());
+        limiter.tokens -= taken as f64;
+
+        queue
+            .drain(..taken)
+            .map(|mut event| {
+                let attempt = attempts(&event) + 1;
+                event.metadata.remove(DEAD_LETTER_REASON_KEY);
+                event
+                    .metadata
+                    .insert(DEAD_LETTER_ATTEMPTS_KEY.to_string(), attempt.to_string());
+                EventMetrics::record_dlq_reprocess_attempt();
+                event
+            })
+            .collect()
+    }
+
+    /// Copy of every permanently failed event, oldest first
+    pub(super) fn permanent_failures(&self) -> Vec<Event> {
+        self.permanent.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Copy of every queued event, oldest first
+    pub(super) fn snapshot(&self) -> Vec<Event> {
+        self.queue.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Remove and return every queued event, oldest first
+    pub(super) fn drain(&self) -> Vec<Event> {
+        self.queue.lock().unwrap().drain(..).collect()
+    }
+}
+
+/// Reprocess attempts recorded on `event` so far
+pub fn attempts(event: &Event) -> u32 {
+    event
+        .metadata
+        .get(DEAD_LETTER_ATTEMPTS_KEY)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+fn push_bounded(queue: &Mutex<VecDeque<Event>>, event: Event, capacity: usize) {
+    let mut queue = queue.lock().unwrap();
+    if queue.len() >= capacity {
+        queue.pop_front();
+        EventMetrics::record_dead_letter_overflow();
+    }
+    queue.push_back(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: ReprocessBudget = ReprocessBudget {
+        max_attempts: 3,
+        rate_per_sec: 100,
+    };
+
+    fn event(id: &str) -> Event {
+        Event {
+            event_id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let dlq = DeadLetterQueue::new(2, BUDGET);
+        for id in ["a", "b", "c"] {
+            dlq.push(event(id), DeadLetterReason::NoSubscribers);
+        }
+
+        let ids: Vec<String> = dlq.drain().into_iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert!(dlq.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_always_failing_event_exhausts_its_budget() {
+        let dlq = DeadLetterQueue::new(10, BUDGET);
+        dlq.push(event("a"), DeadLetterReason::NoSubscribers);
+
+        let mut reprocessed = 0;
+        while let Some(event) = dlq.reprocess().pop() {
+            reprocessed += 1;
+            assert_eq!(attempts(&event), reprocessed);
+            assert!(!event.metadata.contains_key(DEAD_LETTER_REASON_KEY));
+            // Still undeliverable
+            dlq.push(event, DeadLetterReason::NoSubscribers);
+            assert!(reprocessed <= BUDGET.max_attempts, "event kept looping");
+        }
+
+        assert_eq!(reprocessed, BUDGET.max_attempts);
+        assert!(dlq.snapshot().is_empty());
+        let failed = dlq.permanent_failures();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(attempts(&failed[0]), BUDGET.max_attempts);
+        assert_eq!(failed[0].metadata[DEAD_LETTER_REASON_KEY], "no_subscribers");
+    }
+
+    #[test]
+    fn test_reprocess_is_rate_limited() {
+        let dlq = DeadLetterQueue::new(
+            10,
+            ReprocessBudget {
+                max_attempts: 3,
+                rate_per_sec: 2,
+            },
+        );
+        for id in ["a", "b", "c"] {
+            dlq.push(event(id), DeadLetterReason::NoSubscribers);
+        }
+
+        let ids: Vec<String> = dlq.reprocess().into_iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(dlq.reprocess().is_empty());
+        assert_eq!(dlq.snapshot().len(), 1);
+    }
+}
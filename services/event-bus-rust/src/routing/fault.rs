@@ -0,0 +1,130 @@
+use balatro_emulator::{BalatroRng, SeedType};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::ChaosConfig;
+
+/// What the injector decided for one routed event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Deliver normally
+    None,
+    /// Silently drop delivery to every subscriber
+    Drop,
+    /// Deliver after sleeping for the given duration
+    Delay(Duration),
+}
+
+/// Seeded chaos source deciding per event whether to drop or delay delivery
+///
+/// Decisions come from a `BalatroRng` advanced once per routed event, so the
+/// same seed and event order always produce the same faults.
+pub struct FaultInjector {
+    rng: Mutex<BalatroRng>,
+    drop_rate: f64,
+    delay_rate: f64,
+    max_delay: Duration,
+}
+
+impl FaultInjector {
+    pub fn new(config: &ChaosConfig) -> Self {
+        Self {
+            rng: Mutex::new(BalatroRng::new(SeedType::Numeric(config.seed))),
+            drop_rate: config.drop_rate,
+            delay_rate: config.delay_rate,
+            max_delay: Duration::from_millis(config.max_delay_ms),
+        }
+    }
+
+    /// Decide the fault for the next routed event
+    pub fn next_fault(&self) -> Fault {
+        let mut rng = self.rng.lock().unwrap();
+
+        let drop_seed = rng.pseudoseed("chaos_drop");
+        if rng.probability_check(self.drop_rate, drop_seed) {
+            return Fault::Drop;
+        }
+
+        let delay_seed = rng.pseudoseed("chaos_delay");
+        if self.max_delay > Duration::ZERO && rng.probability_check(self.delay_rate, delay_seed) {
+            let millis_seed = rng.pseudoseed("chaos_delay_ms");
+            let max_ms = self.max_delay.as_millis().min(i32::MAX as u128) as i32;
+            let millis = rng.pseudorandom(SeedType::Numeric(millis_seed), Some(1), Some(max_ms));
+            return Fault::Delay(Duration::from_millis(millis as u64));
+        }
+
+        Fault::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{Event, EventType};
+    use crate::routing::EventRouter;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    async fn delivered_ids(seed: u64) -> Vec<String> {
+        let config = ChaosConfig {
+            enabled: true,
+            seed,
+            drop_rate: 0.3,
+            delay_rate: 0.0,
+            max_delay_ms: 0,
+        };
+        let router = EventRouter::new().with_fault_injector(Arc::new(FaultInjector::new(&config)));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+
+        for i in 0..50 {
+            router
+                .route_event(Event {
+                    event_id: format!("evt-{i}"),
+                    r#type: EventType::Heartbeat as i32,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut ids = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            ids.push(event.event_id);
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_drops_same_events() {
+        let first = delivered_ids(42).await;
+        assert_eq!(first, delivered_ids(42).await);
+        assert!(
+            !first.is_empty() && first.len() < 50,
+            "{} delivered",
+            first.len()
+        );
+        assert_ne!(first, delivered_ids(43).await);
+    }
+
+    #[test]
+    fn test_delays_are_bounded() {
+        let injector = FaultInjector::new(&ChaosConfig {
+            enabled: true,
+            seed: 7,
+            drop_rate: 0.0,
+            delay_rate: 1.0,
+            max_delay_ms: 20,
+        });
+        for _ in 0..20 {
+            match injector.next_fault() {
+                Fault::Delay(delay) => {
+                    assert!(delay >= Duration::from_millis(1) && delay <= Duration::from_millis(20))
+                }
+                fault => panic!("expected a delay, got {fault:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::HashSet;
+
+use crate::proto::{event::Payload, Event};
+
+/// What an interceptor decided for an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intercept {
+    /// Hand the (possibly modified) event to the next interceptor
+    Pass,
+    /// Stop routing the event; later interceptors don't see it
+    Drop,
+}
+
+/// Transform applied to every event before it is journaled and matched
+///
+/// Interceptors run in registration order and may mutate the event in place.
+pub trait EventInterceptor: Send + Sync {
+    /// Name used in logs when the interceptor drops an event
+    fn name(&self) -> &str;
+
+    fn intercept(&self, event: &mut Event) -> Intercept;
+}
+
+/// Replaces the values of sensitive metadata keys before events are stored or
+/// fanned out, so subscribers never see credentials a publisher attached as
+/// metadata; see `RedactPayload` for credentials inside the payload
+pub struct RedactMetadata {
+    keys: HashSet<String>,
+}
+
+/// Value written over redacted metadata
+pub const REDACTED: &str = "[REDACTED]";
+
+impl RedactMetadata {
+    /// Redact the given keys (compared case-insensitively)
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            keys: keys
+                .into_iter()
+                .map(|key| key.as_ref().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+}
+
+impl Default for RedactMetadata {
+    /// Common credential header names
+    fn default() -> Self {
+        Self::new(["authorization", "api_key", "x-api-key", "password", "token"])
+    }
+}
+
+impl EventInterceptor for RedactMetadata {
+    fn name(&self) -> &str {
+        "redact_metadata"
+    }
+
+    fn intercept(&self, event: &mut Event) -> Intercept {
+        for (key, value) in event.metadata.iter_mut() {
+            if self.keys.contains(&key.to_ascii_lowercase()) {
+                *value = REDACTED.to_string();
+            }
+        }
+        Intercept::Pass
+    }
+}
+
+/// Redacts credentials written into the free-text fields of event payloads
+///
+/// `RedactMetadata` only sees metadata; this covers the payload strings a
+/// publisher controls: a heartbeat's `game_state`, a connection test's
+/// `message`, and an error's `message`, `stack_trace` and `context`. Inside
+/// text, the value after a listed key followed by `=` or `:` is replaced,
+/// whether written `token=abc`, `password: abc` or `"api_key": "abc"`, as is
+/// the credential after an `authorization: Bearer` or `Basic` scheme. Context
+/// entries whose key is listed are replaced whole.
+pub struct RedactPayload {
+    keys: HashSet<String>,
+}
+
+impl RedactPayload {
+    /// Redact the given keys (compared case-insensitively)
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            keys: keys
+                .into_iter()
+                .map(|key| key.as_ref().to_ascii_lowercase())
+                .filter(|key| !key.is_empty())
+                .collect(),
+        }
+    }
+
+    fn redact(&self, text: &mut String) {
+        if let Some(redacted) = self.redact_text(text) {
+            *text = redacted;
+        }
+    }
+
+    /// `text` with the value after every listed key replaced, or `None` if
+    /// no key was found
+    fn redact_text(&self, text: &str) -> Option<String> {
+        // ASCII lowercasing keeps byte offsets, so spans apply to `text` too
+        let lower = text.to_ascii_lowercase();
+        let mut spans = Vec::new();
+        for key in &self.keys {
+            let mut from = 0;
+            while let Some(found) = lower[from..].find(key.as_str()) {
+                let start = from + found;
+                from = start + key.len();
+                let inside_word = lower[..start]
+                    .bytes()
+                    .next_back()
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_');
+                if !inside_word {
+                    spans.extend(value_span(&lower, from));
+                }
+            }
+        }
+        if spans.is_empty() {
+            return None;
+        }
+
+        spans.sort_unstable();
+        let mut redacted = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (start, end) in spans {
+            if start < copied {
+                // Overlaps a span already replaced
+                copied = copied.max(end);
+                continue;
+            }
+            redacted.push_str(&text[copied..start]);
+            redacted.push_str(REDACTED);
+            copied = end;
+        }
+        redacted.push_str(&text[copied..]);
+        Some(redacted)
+    }
+}
+
+impl Default for RedactPayload {
+    /// The same credential names as `RedactMetadata`
+    fn default() -> Self {
+        Self::new(["authorization", "api_key", "x-api-key", "password", "token"])
+    }
+}
+
+/// Byte range of the value following a key that ends at `at`, if the key is
+/// followed by `=` or `:`
+fn value_span(lower: &str, at: usize) -> Option<(usize, usize)> {
+    let bytes = lower.as_bytes();
+    let skip_spaces = |mut i: usize| {
+        while bytes.get(i).is_some_and(|b| *b == b' ' || *b == b'\t') {
+            i += 1;
+        }
+        i
+    };
+    let scan_value = |mut i: usize| {
+        while bytes
+            .get(i)
+            .is_some_and(|b| !b.is_ascii_whitespace() && !b"\",;&}".contains(b))
+        {
+            i += 1;
+        }
+        i
+    };
+
+    // The closing quote of a JSON key
+    let mut i = at + usize::from(bytes.get(at) == Some(&b'"'));
+    i = skip_spaces(i);
+    if !matches!(bytes.get(i), Some(b'=' | b':')) {
+        return None;
+    }
+    i = skip_spaces(i + 1);
+    i += usize::from(bytes.get(i) == Some(&b'"'));
+
+    let start = i;
+    let mut end = scan_value(start);
+    if matches!(&lower[start..end], "bearer" | "basic") {
+        end = scan_value(skip_spaces(end));
+    }
+    (end > start).then_some((start, end))
+}
+
+impl EventInterceptor for RedactPayload {
+    fn name(&self) -> &str {
+        "redact_payload"
+    }
+
+    fn intercept(&self, event: &mut Event) -> Intercept {
+        match &mut event.payload {
+            Some(Payload::Heartbeat(heartbeat)) => self.redact(&mut heartbeat.game_state),
+            Some(Payload::ConnectionTest(test)) => self.redact(&mut test.message),
+            Some(Payload::Error(error)) => {
+                self.redact(&mut error.message);
+                self.redact(&mut error.stack_trace);
+                for (key, value) in error.context.iter_mut() {
+                    if self.keys.contains(&key.to_ascii_lowercase()) {
+                        *value = REDACTED.to_string();
+                    } else {
+                        self.redact(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Intercept::Pass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::EventType;
+    use crate::routing::EventRouter;
+    use tokio::sync::mpsc;
+
+    struct DropFlagged;
+
+    impl EventInterceptor for DropFlagged {
+        fn name(&self) -> &str {
+            "drop_flagged"
+        }
+
+        fn intercept(&self, event: &mut Event) -> Intercept {
+            if event.metadata.contains_key("flagged") {
+                Intercept::Drop
+            } else {
+                Intercept::Pass
+            }
+        }
+    }
+
+    struct StampReceived;
+
+    impl EventInterceptor for StampReceived {
+        fn name(&self) -> &str {
+            "stamp_received"
+        }
+
+        fn intercept(&self, event: &mut Event) -> Intercept {
+            event
+                .metadata
+                .insert("received_by".to_string(), "event-bus".to_string());
+            Intercept::Pass
+        }
+    }
+
+    fn heartbeat(metadata: &[(&str, &str)]) -> Event {
+        Event {
+            r#type: EventType::Heartbeat as i32,
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptors_drop_and_enrich_before_delivery() {
+        let router = EventRouter::new()
+            .with_interceptor(Box::new(DropFlagged))
+            .with_interceptor(Box::new(StampReceived))
+            .with_interceptor(Box::new(RedactMetadata::default()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+
+        let outcome = router
+            .try_route_event(heartbeat(&[("flagged", "true")]))
+            .await
+            .unwrap();
+        assert_eq!(outcome.routed, 0);
+        assert!(rx.try_recv().is_err());
+
+        let outcome = router
+            .try_route_event(heartbeat(&[("Authorization", "Bearer secret")]))
+            .await
+            .unwrap();
+        assert_eq!(outcome.routed, 1);
+        let delivered = rx.try_recv().unwrap();
+        assert_eq!(delivered.metadata["received_by"], "event-bus");
+        assert_eq!(delivered.metadata["Authorization"], REDACTED);
+    }
+
+    #[test]
+    fn test_redact_payload_text() {
+        let redact = RedactPayload::default();
+        let cases = [
+            ("token=abc123 retrying", "token=[REDACTED] retrying"),
+            (
+                r#"{"user": "jim", "Password": "hunter2"}"#,
+                r#"{"user": "jim", "Password": "[REDACTED]"}"#,
+            ),
+            (
+                "Authorization: Bearer eyJhbGci, then api_key = k1;",
+                "Authorization: [REDACTED], then api_key = [REDACTED];",
+            ),
+            // Only whole keys followed by a value
+            (
+                "mytoken=abc and token expired",
+                "mytoken=abc and token expired",
+            ),
+        ];
+        for (text, expected) in cases {
+            let redacted = redact.redact_text(text);
+            assert_eq!(redacted.as_deref().unwrap_or(text), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payload_credentials_are_not_delivered() {
+        use crate::proto::{ConnectionTestEvent, ErrorEvent};
+
+        let router = EventRouter::new().with_interceptor(Box::new(RedactPayload::default()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router.subscribe_channel("#".to_string(), tx).unwrap();
+
+        router
+            .route_event(Event {
+                r#type: EventType::ConnectionTest as i32,
+                payload: Some(Payload::ConnectionTest(ConnectionTestEvent {
+                    message: "hello with password=swordfish".to_string(),
+                })),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        router
+            .route_event(Event {
+                r#type: EventType::Error as i32,
+                payload: Some(Payload::Error(ErrorEvent {
+                    message: "login failed for token: t-42".to_string(),
+                    context: [("API_KEY".to_string(), "k-1".to_string())].into(),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let Some(Payload::ConnectionTest(test)) = rx.try_recv().unwrap().payload else {
+            panic!("expected a connection test payload");
+        };
+        assert_eq!(test.message, "hello with password=[REDACTED]");
+        let Some(Payload::Error(error)) = rx.try_recv().unwrap().payload else {
+            panic!("expected an error payload");
+        };
+        assert_eq!(error.message, "login failed for token: [REDACTED]");
+        assert_eq!(error.context["API_KEY"], REDACTED);
+    }
+}
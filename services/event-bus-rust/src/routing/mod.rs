@@ -1,20 +1,124 @@
+pub mod dead_letter;
+pub mod fault;
+pub mod interceptor;
+pub mod overflow;
+pub mod retry;
+pub mod schedule;
+pub mod topic_metrics;
+
 use anyhow::Result;
+use chrono::Utc;
 use dashmap::DashMap;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
+use crate::config::RoutingConfig;
+use crate::journal::EventJournal;
+use crate::metrics::EventMetrics;
 use crate::proto::{Event, EventType};
+use dead_letter::{DeadLetterQueue, DeadLetterReason, ReprocessBudget};
+use fault::{Fault, FaultInjector};
+use interceptor::{EventInterceptor, Intercept};
+use overflow::{bounded_channel, BoundedReceiver, BoundedSender, OverflowStrategy, SendOutcome};
+use retry::{retry_delivery, RetryOutcome, RetryPolicy};
+use schedule::{ScheduleError, ScheduledEvents};
+use topic_metrics::{topic_label, RouterMetrics};
 
 pub type EventHandler = Arc<dyn Fn(Event) + Send + Sync>;
 pub type EventChannel = mpsc::UnboundedSender<Event>;
 
+/// Topic each known event type is routed to
+pub const KNOWN_TOPICS: [(EventType, &str); 11] = [
+    (EventType::GameState, "game.state.update"),
+    (EventType::Heartbeat, "system.heartbeat"),
+    (EventType::MoneyChanged, "game.money.changed"),
+    (EventType::ScoreChanged, "game.score.changed"),
+    (EventType::HandPlayed, "game.hand.played"),
+    (EventType::CardsDiscarded, "game.cards.discarded"),
+    (EventType::JokersChanged, "game.jokers.changed"),
+    (EventType::RoundChanged, "game.round.changed"),
+    (EventType::PhaseChanged, "game.phase.changed"),
+    (EventType::RoundComplete, "game.round.complete"),
+    (EventType::ConnectionTest, "system.connection.test"),
+];
+
+/// Metadata key carrying an event's journal offset, usable as a replay cursor
+pub const JOURNAL_OFFSET_KEY: &str = "journal_offset";
+
+/// Token identifying one handler, channel or bounded subscription, for
+/// `unsubscribe`
+///
+/// Ids are unique per router and increase monotonically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(u64);
+
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Why a subscription was refused
+#[derive(Debug, thiserror::Error)]
+pub enum SubscribeError {
+    #[error("subscribed topic limit of {max} distinct patterns reached")]
+    TooManyTopics { max: usize },
+    #[error("pattern '{pattern}' already has the maximum of {max} subscribers")]
+    TooManySubscribers { pattern: String, max: usize },
+}
+
+/// Result of routing a single event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteOutcome {
+    /// Number of handlers and channels the event was delivered to
+    pub routed: usize,
+    /// Whether the event was handed to the dead-letter queue instead
+    pub dead_lettered: bool,
+}
+
+/// Result of one `reprocess_dead_letters` pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReprocessOutcome {
+    /// Dead letters routed again
+    pub reprocessed: usize,
+    /// Of those, how many were dead-lettered again
+    pub failed: usize,
+}
+
 /// Topic-based event router
 pub struct EventRouter {
     /// Map of topic patterns to handlers
-    handlers: DashMap<String, Vec<EventHandler>>,
+    handlers: DashMap<String, Vec<(SubscriptionId, EventHandler)>>,
     /// Map of topic patterns to channels (for gRPC streaming)
-    channels: DashMap<String, Vec<EventChannel>>,
+    channels: DashMap<String, Vec<(SubscriptionId, EventChannel)>>,
+    /// Map of topic patterns to bounded queues with an overflow strategy
+    bounded: DashMap<String, Vec<(SubscriptionId, BoundedSender)>>,
+    /// Routing configuration (TTL, retries, limits)
+    config: RoutingConfig,
+    /// Backoff for redelivering to `Block` subscribers that timed out
+    retry: RetryPolicy,
+    /// Undeliverable events, when `routing.dead_letter_enabled` is set
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+    /// Optional on-disk journal every routed event is appended to
+    journal: Option<Arc<EventJournal>>,
+    /// Optional chaos-mode fault injection applied before delivery
+    faults: Option<Arc<FaultInjector>>,
+    /// Transforms applied in order to every event before routing
+    interceptors: Vec<Box<dyn EventInterceptor>>,
+    /// When each pattern was last subscribed to or delivered to
+    last_used: DashMap<String, Instant>,
+    /// Next `SubscriptionId` to hand out
+    next_subscription: AtomicU64,
+    /// Serializes admitting new patterns so the topic cap can't be overshot
+    admission: Mutex<()>,
+    /// Events waiting on `publish_after`
+    scheduled: ScheduledEvents,
+    /// Per-topic routed, dropped and subscriber counts
+    metrics: RouterMetrics,
 }
 
 impl Default for EventRouter {
@@ -25,101 +129,803 @@ impl Default for EventRouter {
 
 impl EventRouter {
     pub fn new() -> Self {
+        Self::new_with_config(RoutingConfig::default())
+    }
+
+    /// Create a router using the given routing configuration
+    pub fn new_with_config(config: RoutingConfig) -> Self {
+        let scheduled = ScheduledEvents::new(config.max_scheduled_events);
+        let retry = RetryPolicy::new(config.max_retry_attempts, &config.retry_backoff);
+        let dead_letters = config.dead_letter_enabled.then(|| {
+            Arc::new(DeadLetterQueue::new(
+                config.dead_letter_capacity,
+                ReprocessBudget {
+                    max_attempts: config.dead_letter_max_reprocess_attempts,
+                    rate_per_sec: config.dead_letter_reprocess_rate,
+                },
+            ))
+        });
         Self {
             handlers: DashMap::new(),
             channels: DashMap::new(),
+            bounded: DashMap::new(),
+            config,
+            retry,
+            dead_letters,
+            journal: None,
+            faults: None,
+            interceptors: Vec::new(),
+            last_used: DashMap::new(),
+            next_subscription: AtomicU64::new(1),
+            admission: Mutex::new(()),
+            scheduled,
+            metrics: RouterMetrics::new(),
         }
     }
 
+    /// Drop or delay deliveries as decided by `injector` (chaos mode only)
+    pub fn with_fault_injector(mut self, injector: Arc<FaultInjector>) -> Self {
+        self.faults = Some(injector);
+        self
+    }
+
+    /// Run `interceptor` on every event, after those already registered
+    pub fn with_interceptor(mut self, interceptor: Box<dyn EventInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Append every routed event to `journal`
+    pub fn with_journal(mut self, journal: Arc<EventJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Per-topic routing counters
+    pub fn metrics(&self) -> &RouterMetrics {
+        &self.metrics
+    }
+
     /// Route an event to all matching subscribers
     pub async fn route_event(&self, event: Event) -> Result<()> {
+        self.try_route_event(event).await.map(|_| ())
+    }
+
+    /// Route an event and report how many subscribers received it
+    ///
+    /// Unbounded channels and handlers never block; bounded subscribers apply
+    /// their overflow strategy, so a `Block` subscriber can delay routing up
+    /// to its timeout. A `Block` subscriber that times out is retried in the
+    /// background per `routing.retry_backoff`, so that event may arrive after
+    /// later ones and is not counted in `routed`. Interceptors run first;
+    /// events they drop, and expired events, are discarded with `routed: 0`.
+    pub async fn try_route_event(&self, event: Event) -> Result<RouteOutcome> {
+        let mut event = event;
+        for interceptor in &self.interceptors {
+            if interceptor.intercept(&mut event) == Intercept::Drop {
+                debug!(
+                    "Event {} dropped by interceptor {}",
+                    event.event_id,
+                    interceptor.name()
+                );
+                self.metrics.record_dropped(&self.event_to_topic(&event));
+                return Ok(RouteOutcome::default());
+            }
+        }
+
         let topic = self.event_to_topic(&event);
-        debug!("Routing event to topic: {}", topic);
+        debug!(
+            "Routing event to topic: {} (correlation_id={}, causation_id={})",
+            topic, event.correlation_id, event.causation_id
+        );
 
-        let mut routed_count = 0;
+        if self.is_expired(&event, &topic, Utc::now().timestamp_millis()) {
+            debug!(
+                "Dropping expired event {} for topic: {}",
+                event.event_id, topic
+            );
+            EventMetrics::record_event_expired(topic_label(&topic));
+            self.metrics.record_dropped(&topic);
+            let mut outcome = RouteOutcome::default();
+            if self.config.dead_letter_expired {
+                if let Some(dead_letters) = &self.dead_letters {
+                    dead_letters.push(event, DeadLetterReason::Expired);
+                    outcome.dead_lettered = true;
+                }
+            }
+            return Ok(outcome);
+        }
 
-        // Route to handlers
-        for entry in self.handlers.iter() {
-            if self.matches_pattern(&topic, entry.key()) {
-                for handler in entry.value() {
-                    handler(event.clone());
-                    routed_count += 1;
+        if let Some(journal) = &self.journal {
+            match journal.append(&event) {
+                Ok(offset) => {
+                    event
+                        .metadata
+                        .insert(JOURNAL_OFFSET_KEY.to_string(), offset.to_string());
+                }
+                Err(e) => error!("Failed to journal event {}: {}", event.event_id, e),
+            }
+        }
+
+        self.deliver(event, topic).await
+    }
+
+    /// Hand an event that passed interceptors, the TTL and the journal to
+    /// every matching subscriber, dead-lettering it if there are none
+    async fn deliver(&self, event: Event, topic: String) -> Result<RouteOutcome> {
+        if let Some(faults) = &self.faults {
+            match faults.next_fault() {
+                Fault::None => {}
+                Fault::Drop => {
+                    debug!("Chaos: dropping delivery of event {}", event.event_id);
+                    self.metrics.record_dropped(&topic);
+                    return Ok(RouteOutcome::default());
+                }
+                Fault::Delay(delay) => {
+                    debug!("Chaos: delaying event {} by {:?}", event.event_id, delay);
+                    tokio::time::sleep(delay).await;
                 }
             }
         }
 
-        // Route to channels
-        let mut dead_channels = Vec::new();
-        for entry in self.channels.iter() {
-            if self.matches_pattern(&topic, entry.key()) {
-                for (idx, channel) in entry.value().iter().enumerate() {
-                    if channel.send(event.clone()).is_err() {
-                        dead_channels.push((entry.key().clone(), idx));
-                    } else {
-                        routed_count += 1;
+        // Subscribers are collected first so no map guard is held while
+        // handlers run or a blocking subscriber waits for space
+        let handlers = self.matching(&self.handlers, &topic);
+        let channels = self.matching(&self.channels, &topic);
+        let bounded = self.matching(&self.bounded, &topic);
+
+        let mut routed_count = 0;
+        // Subscribers that were still open, whether or not they took the event
+        let mut live_count = handlers.len();
+        let mut retrying = false;
+        let mut delivered_patterns = Vec::new();
+        let mut dead_patterns = Vec::new();
+
+        for (pattern, (_, handler)) in handlers {
+            handler(event.clone());
+            routed_count += 1;
+            delivered_patterns.push(pattern);
+        }
+
+        for (pattern, (_, channel)) in channels {
+            if channel.send(event.clone()).is_err() {
+                dead_patterns.push(pattern.clone());
+            } else {
+                routed_count += 1;
+                live_count += 1;
+            }
+            delivered_patterns.push(pattern);
+        }
+
+        for (pattern, (_, sender)) in bounded {
+            let outcome = sender.send(event.clone()).await;
+            if outcome != SendOutcome::Closed {
+                live_count += 1;
+            }
+            match outcome {
+                SendOutcome::Queued => routed_count += 1,
+                SendOutcome::DroppedOldest => {
+                    debug!("Subscriber queue for '{}' full, dropped oldest", pattern);
+                    routed_count += 1;
+                }
+                SendOutcome::Rejected if self.should_retry(&sender) => {
+                    match sender.park_retry(event.clone()) {
+                        Some(start) => {
+                            debug!(
+                                "Subscriber queue for '{}' full, retrying event {}",
+                                pattern, event.event_id
+                            );
+                            if start {
+                                self.spawn_retry(pattern.clone(), sender);
+                            }
+                            retrying = true;
+                        }
+                        None => {
+                            warn!(
+                                "Retry backlog for '{}' full, event {} not delivered",
+                                pattern, event.event_id
+                            );
+                            EventMetrics::record_delivery_retry("backlog_full");
+                            if let Some(dead_letters) = &self.dead_letters {
+                                dead_letters
+                                    .push(event.clone(), DeadLetterReason::RetryBacklogFull);
+                            }
+                        }
                     }
                 }
+                SendOutcome::Rejected => warn!(
+                    "Subscriber queue for '{}' full, event {} not delivered",
+                    pattern, event.event_id
+                ),
+                SendOutcome::Closed => dead_patterns.push(pattern.clone()),
             }
+            delivered_patterns.push(pattern);
         }
 
-        // Clean up dead channels
-        for (pattern, _) in dead_channels {
-            self.channels.alter(&pattern, |_, mut channels| {
-                channels.retain(|ch| !ch.is_closed());
-                channels
-            });
+        dead_patterns.sort();
+        dead_patterns.dedup();
+        let reaped = !dead_patterns.is_empty();
+        for pattern in dead_patterns {
+            self.reap_closed(&pattern);
+        }
+        if reaped {
+            self.refresh_subscriber_counts();
+        }
+
+        self.metrics.record_routed(&topic, routed_count);
+        if routed_count == 0 && !retrying {
+            self.metrics.record_dropped(&topic);
+        }
+
+        let now = Instant::now();
+        for pattern in delivered_patterns {
+            self.last_used.insert(pattern, now);
         }
 
-        if routed_count == 0 {
+        let mut dead_lettered = false;
+        if live_count == 0 {
             debug!("No subscribers for topic: {}", topic);
+            if let Some(dead_letters) = &self.dead_letters {
+                dead_letters.push(event, DeadLetterReason::NoSubscribers);
+                dead_lettered = true;
+            }
         } else {
             debug!("Event routed to {} subscribers", routed_count);
         }
 
-        Ok(())
+        Ok(RouteOutcome {
+            routed: routed_count,
+            dead_lettered,
+        })
+    }
+
+    /// Clone out every subscriber in `map` whose pattern matches `topic`
+    ///
+    /// Shard read guards are held only while copying, never during delivery.
+    fn matching<T: Clone>(&self, map: &DashMap<String, Vec<T>>, topic: &str) -> Vec<(String, T)> {
+        let mut matched = Vec::new();
+        for entry in map.iter() {
+            if self.matches_pattern(topic, entry.key()) {
+                let pattern = entry.key();
+                matched.extend(entry.value().iter().map(|s| (pattern.clone(), s.clone())));
+            }
+        }
+        matched
+    }
+
+    /// Whether a rejected send to `sender` is transient and worth retrying
+    ///
+    /// Only `Block` subscribers are retried: `DropNewest` rejects by design.
+    fn should_retry(&self, sender: &BoundedSender) -> bool {
+        self.retry.max_attempts() > 0 && matches!(sender.strategy(), OverflowStrategy::Block { .. })
+    }
+
+    /// Redeliver the events parked on `sender` with backoff, one at a time
+    /// and oldest first, on a background task
+    ///
+    /// At most one such task runs per subscriber; it stops once the backlog
+    /// is empty.
+    fn spawn_retry(&self, pattern: String, sender: BoundedSender) {
+        let policy = self.retry.clone();
+        let dead_letters = self.dead_letters.clone();
+        tokio::spawn(async move {
+            while let Some(event) = sender.next_retry() {
+                let event_id = event.event_id.clone();
+                let outcome = retry_delivery(&policy, event.clone(), |event| {
+                    let sender = sender.clone();
+                    async move { sender.send(event).await }
+                })
+                .await;
+                match outcome {
+                    RetryOutcome::Delivered { attempts } => {
+                        debug!(
+                            "Event {} delivered to '{}' after {} retries",
+                            event_id, pattern, attempts
+                        );
+                        EventMetrics::record_delivery_retry("delivered");
+                    }
+                    RetryOutcome::Exhausted { attempts } => {
+                        warn!(
+                            "Giving up on event {} for '{}' after {} retries",
+                            event_id, pattern, attempts
+                        );
+                        EventMetrics::record_delivery_retry("exhausted");
+                        if let Some(dead_letters) = &dead_letters {
+                            dead_letters.push(event, DeadLetterReason::MaxRetriesExhausted);
+                        }
+                    }
+                    RetryOutcome::Closed { .. } => {
+                        debug!("Subscriber for '{}' closed during retry", pattern);
+                        EventMetrics::record_delivery_retry("closed");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Drop closed channels and bounded queues registered under `pattern`
+    fn reap_closed(&self, pattern: &str) {
+        if let Some(mut channels) = self.channels.get_mut(pattern) {
+            channels.retain(|(_, ch)| !ch.is_closed());
+        }
+        if let Some(mut senders) = self.bounded.get_mut(pattern) {
+            senders.retain(|(_, s)| !s.is_closed());
+        }
+    }
+
+    /// Remove and return every dead-lettered event, oldest first
+    ///
+    /// Each carries its reason under `dead_letter::DEAD_LETTER_REASON_KEY`.
+    /// Always empty unless `routing.dead_letter_enabled` is set.
+    pub fn drain_dead_letters(&self) -> Vec<Event> {
+        self.dead_letters
+            .as_ref()
+            .map(|dead_letters| dead_letters.drain())
+            .unwrap_or_default()
+    }
+
+    /// Dead-lettered events, oldest first, without removing them
+    pub fn dead_letters(&self) -> Vec<Event> {
+        self.dead_letters
+            .as_ref()
+            .map(|dead_letters| dead_letters.snapshot())
+            .unwrap_or_default()
+    }
+
+    /// Route the oldest dead letters again, as many as
+    /// `routing.dead_letter_reprocess_rate` allows
+    ///
+    /// Dead letters already went through interceptors, the TTL check and the
+    /// journal, so they go straight to delivery: the journal holds each event
+    /// once, and expired events are delivered rather than expiring again.
+    ///
+    /// Events that still can't be delivered go back into the queue, until
+    /// they have been reprocessed `routing.dead_letter_max_reprocess_attempts`
+    /// times; after that they are failed permanently and listed by
+    /// `permanent_failures` instead.
+    pub async fn reprocess_dead_letters(&self) -> ReprocessOutcome {
+        let Some(dead_letters) = &self.dead_letters else {
+            return ReprocessOutcome::default();
+        };
+
+        let mut outcome = ReprocessOutcome::default();
+        for event in dead_letters.reprocess() {
+            let event_id = event.event_id.clone();
+            outcome.reprocessed += 1;
+            let topic = self.event_to_topic(&event);
+            match self.deliver(event, topic).await {
+                Ok(routed) if routed.dead_lettered => outcome.failed += 1,
+                Ok(_) => {}
+                Err(e) => error!("Failed to reprocess dead letter {}: {}", event_id, e),
+            }
+        }
+        if outcome.reprocessed > 0 {
+            info!(
+                "Reprocessed {} dead letters, {} failed again",
+                outcome.reprocessed, outcome.failed
+            );
+        }
+        outcome
+    }
+
+    /// Dead letters that used up their reprocess attempts, oldest first
+    pub fn permanent_failures(&self) -> Vec<Event> {
+        self.dead_letters
+            .as_ref()
+            .map(|dead_letters| dead_letters.permanent_failures())
+            .unwrap_or_default()
+    }
+
+    /// Route `event` once `delay` has elapsed; returns the id to cancel it by
+    ///
+    /// The id is the event's `event_id` (a new UUID if empty). Pending events
+    /// live only as long as the process; they are not journaled until routed.
+    pub fn publish_after(
+        self: &Arc<Self>,
+        mut event: Event,
+        delay: Duration,
+    ) -> Result<String, ScheduleError> {
+        if event.event_id.is_empty() {
+            event.event_id = uuid::Uuid::new_v4().to_string();
+        }
+        let id = event.event_id.clone();
+
+        let router = Arc::clone(self);
+        let task_id = id.clone();
+        self.scheduled.insert(id.clone(), move || {
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if router.scheduled.take_due(&task_id) {
+                    if let Err(e) = router.try_route_event(event).await {
+                        error!("Failed to route scheduled event {}: {}", task_id, e);
+                    }
+                }
+            })
+            .abort_handle()
+        })?;
+
+        debug!("Scheduled event {} in {:?}", id, delay);
+        Ok(id)
+    }
+
+    /// Cancel a pending `publish_after`; false if it already fired or is unknown
+    pub fn cancel_scheduled(&self, id: &str) -> bool {
+        self.scheduled.cancel(id)
+    }
+
+    /// Number of events waiting on `publish_after`
+    pub fn scheduled_count(&self) -> usize {
+        self.scheduled.len()
     }
 
     /// Subscribe a handler to a topic pattern
-    pub fn subscribe_handler(&self, pattern: String, handler: EventHandler) {
-        info!("Adding handler subscription for pattern: {}", pattern);
-        self.handlers.entry(pattern).or_default().push(handler);
+    ///
+    /// The returned id removes just this handler via `unsubscribe`.
+    pub fn subscribe_handler(
+        &self,
+        pattern: String,
+        handler: EventHandler,
+    ) -> Result<SubscriptionId, SubscribeError> {
+        let _admission = self.admit_pattern(&pattern)?;
+        let id = self.next_subscription_id();
+        info!(
+            "Adding handler subscription {:?} for pattern: {}",
+            id, pattern
+        );
+        self.handlers
+            .entry(pattern)
+            .or_default()
+            .push((id, handler));
+        self.refresh_subscriber_counts();
+        Ok(id)
     }
 
     /// Subscribe a channel to a topic pattern (for gRPC streaming)
-    pub fn subscribe_channel(&self, pattern: String, channel: EventChannel) {
-        info!("Adding channel subscription for pattern: {}", pattern);
-        self.channels.entry(pattern).or_default().push(channel);
+    ///
+    /// The returned id removes just this channel via `unsubscribe`.
+    pub fn subscribe_channel(
+        &self,
+        pattern: String,
+        channel: EventChannel,
+    ) -> Result<SubscriptionId, SubscribeError> {
+        let _admission = self.admit_pattern(&pattern)?;
+        let id = self.next_subscription_id();
+        info!(
+            "Adding channel subscription {:?} for pattern: {}",
+            id, pattern
+        );
+        self.channels
+            .entry(pattern)
+            .or_default()
+            .push((id, channel));
+        self.refresh_subscriber_counts();
+        Ok(id)
+    }
+
+    fn next_subscription_id(&self) -> SubscriptionId {
+        SubscriptionId(self.next_subscription.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Subscribe a bounded queue of `event_buffer_size` events to a topic pattern
+    ///
+    /// When the subscriber falls behind, `strategy` decides which events are
+    /// lost (or whether routing waits); see [`OverflowStrategy`]. The returned
+    /// id removes just this queue via `unsubscribe`.
+    pub fn subscribe_bounded(
+        &self,
+        pattern: String,
+        strategy: OverflowStrategy,
+    ) -> Result<(SubscriptionId, BoundedReceiver), SubscribeError> {
+        let _admission = self.admit_pattern(&pattern)?;
+        let id = self.next_subscription_id();
+        info!(
+            "Adding bounded subscription {:?} for pattern: {} ({:?})",
+            id, pattern, strategy
+        );
+        let (tx, rx) = bounded_channel(self.config.event_buffer_size, strategy);
+        self.bounded.entry(pattern).or_default().push((id, tx));
+        self.refresh_subscriber_counts();
+        Ok((id, rx))
+    }
+
+    /// Overflow strategy for streaming subscribers that don't pick their own,
+    /// from `routing.subscriber_overflow`
+    pub fn subscriber_overflow(&self) -> OverflowStrategy {
+        self.config.subscriber_overflow.into()
+    }
+
+    /// Longest a subscriber may make routing wait on its full queue, from
+    /// `routing.max_block_timeout_ms`
+    pub fn max_block_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.max_block_timeout_ms)
+    }
+
+    /// Check a pattern against `routing.max_topics` and
+    /// `routing.max_subscribers_per_topic` before subscribing to it
+    ///
+    /// Existing patterns are admitted while they have fewer live subscribers
+    /// (handlers, channels and bounded queues together) than the per-topic
+    /// cap. At the topic cap, the least recently used pattern with no live
+    /// subscribers is evicted to make room for a new one; if every pattern is
+    /// live the subscription is refused. The returned guard must be held until
+    /// the subscription is registered.
+    fn admit_pattern(&self, pattern: &str) -> Result<MutexGuard<'_, ()>, SubscribeError> {
+        let guard = self.admission.lock().unwrap();
+
+        let mut patterns: Vec<String> = self
+            .handlers
+            .iter()
+            .map(|entry| entry.key().clone())
+            .chain(self.channels.iter().map(|entry| entry.key().clone()))
+            .chain(self.bounded.iter().map(|entry| entry.key().clone()))
+            .collect();
+        patterns.sort();
+        patterns.dedup();
+
+        let max = self.config.max_topics;
+        if !patterns.iter().any(|p| p == pattern) && patterns.len() >= max {
+            let evictable = patterns
+                .iter()
+                .filter(|p| !self.is_live(p))
+                .min_by_key(|p| self.last_used.get(*p).map(|t| *t));
+            match evictable {
+                Some(stale) => {
+                    info!("Evicting unused pattern '{}' to admit '{}'", stale, pattern);
+                    self.remove_pattern(stale);
+                }
+                None => {
+                    warn!(
+                        "Rejecting subscription to '{}': {} topics in use",
+                        pattern, max
+                    );
+                    EventMetrics::record_topic_limit_rejection();
+                    return Err(SubscribeError::TooManyTopics { max });
+                }
+            }
+        }
+
+        let max = self.config.max_subscribers_per_topic;
+        if self.live_subscribers(pattern) >= max {
+            warn!(
+                "Rejecting subscription to '{}': {} subscribers already",
+                pattern, max
+            );
+            EventMetrics::record_subscriber_limit_rejection();
+            return Err(SubscribeError::TooManySubscribers {
+                pattern: pattern.to_string(),
+                max,
+            });
+        }
+
+        self.last_used.insert(pattern.to_string(), Instant::now());
+        Ok(guard)
+    }
+
+    /// Whether a pattern has a handler or an open channel
+    fn is_live(&self, pattern: &str) -> bool {
+        self.live_subscribers(pattern) > 0
+    }
+
+    /// Handlers plus open channels and bounded queues on exactly `pattern`
+    fn live_subscribers(&self, pattern: &str) -> usize {
+        self.handlers
+            .get(pattern)
+            .map_or(0, |handlers| handlers.len())
+            + self.channels.get(pattern).map_or(0, |channels| {
+                channels.iter().filter(|(_, ch)| !ch.is_closed()).count()
+            })
+            + self.bounded.get(pattern).map_or(0, |senders| {
+                senders.iter().filter(|(_, tx)| !tx.is_closed()).count()
+            })
+    }
+
+    fn remove_pattern(&self, pattern: &str) -> bool {
+        self.last_used.remove(pattern);
+        let had_handlers = self.handlers.remove(pattern).is_some();
+        let had_channels = self.channels.remove(pattern).is_some();
+        let had_bounded = self.bounded.remove(pattern).is_some();
+        self.refresh_subscriber_counts();
+        had_handlers || had_channels || had_bounded
+    }
+
+    /// Remove the handler, channel or bounded subscription `id`
+    ///
+    /// Other subscriptions on the same pattern are unaffected. Returns false
+    /// if `id` is unknown or was already removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut removed = false;
+        self.handlers.retain(|_, handlers| {
+            let before = handlers.len();
+            handlers.retain(|(sub, _)| *sub != id);
+            removed |= handlers.len() != before;
+            true
+        });
+        if !removed {
+            self.channels.retain(|_, channels| {
+                let before = channels.len();
+                channels.retain(|(sub, _)| *sub != id);
+                removed |= channels.len() != before;
+                true
+            });
+        }
+        if !removed {
+            self.bounded.retain(|_, senders| {
+                let before = senders.len();
+                senders.retain(|(sub, _)| *sub != id);
+                removed |= senders.len() != before;
+                true
+            });
+        }
+        if removed {
+            info!("Removed subscription {:?}", id);
+            self.refresh_subscriber_counts();
+        }
+        removed
+    }
+
+    /// Remove all subscriptions for an exact pattern
+    ///
+    /// Returns true if anything was registered under `pattern`.
+    pub fn unsubscribe_pattern(&self, pattern: &str) -> bool {
+        info!("Removing subscriptions for pattern: {}", pattern);
+        self.remove_pattern(pattern)
+    }
+
+    /// Whether any live handler or channel would receive an event on `topic`
+    pub fn has_subscribers(&self, topic: &str) -> bool {
+        !self.matching_patterns(topic).is_empty()
+    }
+
+    /// Registered patterns with at least one live subscriber matching `topic`
+    ///
+    /// Channels whose receiver has been dropped are not counted.
+    pub fn matching_patterns(&self, topic: &str) -> Vec<String> {
+        let mut patterns: Vec<String> = self
+            .handlers
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| entry.key().clone())
+            .chain(
+                self.channels
+                    .iter()
+                    .filter(|entry| entry.value().iter().any(|(_, ch)| !ch.is_closed()))
+                    .map(|entry| entry.key().clone()),
+            )
+            .chain(
+                self.bounded
+                    .iter()
+                    .filter(|entry| entry.value().iter().any(|(_, tx)| !tx.is_closed()))
+                    .map(|entry| entry.key().clone()),
+            )
+            .filter(|pattern| self.matches_pattern(topic, pattern))
+            .collect();
+        patterns.sort();
+        patterns.dedup();
+        patterns
+    }
+
+    /// Journaled events matching `pattern` from offset `cursor` onwards
+    ///
+    /// Returns the events (stamped with their `journal_offset`) and the offset
+    /// the replay ends at; live events below that offset are duplicates.
+    /// Returns `None` when no journal is configured. The journal is read on a
+    /// blocking thread, holding up routing only while its buffer is written.
+    pub async fn replay_from(
+        &self,
+        pattern: &str,
+        cursor: u64,
+    ) -> Result<Option<(Vec<Event>, u64)>> {
+        let Some(journal) = self.journal.clone() else {
+            return Ok(None);
+        };
+
+        let (records, end) =
+            tokio::task::spawn_blocking(move || journal.read_from(cursor)).await??;
+        let events = records
+            .into_iter()
+            .filter(|(_, event)| self.matches_pattern(&self.event_to_topic(event), pattern))
+            .map(|(offset, mut event)| {
+                event
+                    .metadata
+                    .insert(JOURNAL_OFFSET_KEY.to_string(), offset.to_string());
+                event
+            })
+            .collect();
+        Ok(Some((events, end)))
+    }
+
+    /// TTL in seconds for a topic, honoring per-topic overrides (0 = no expiry)
+    pub fn ttl_for_topic(&self, topic: &str) -> u64 {
+        self.config
+            .topic_ttl_overrides
+            .get(topic)
+            .copied()
+            .unwrap_or(self.config.event_ttl_secs)
+    }
+
+    /// Check whether an event is older than its topic's TTL at `now_ms`
+    ///
+    /// Events without a timestamp never expire.
+    fn is_expired(&self, event: &Event, topic: &str, now_ms: i64) -> bool {
+        let ttl_secs = self.ttl_for_topic(topic);
+        let Some(timestamp) = event_timestamp_ms(event).filter(|_| ttl_secs > 0) else {
+            return false;
+        };
+        now_ms.saturating_sub(timestamp) > (ttl_secs as i64).saturating_mul(1000)
     }
 
     /// Convert event to topic string
+    ///
+    /// Events of an unknown type go to `events.unknown.{raw_type}`, with dots
+    /// in the raw type replaced so it stays a single topic segment.
     fn event_to_topic(&self, event: &Event) -> String {
-        let event_type = EventType::try_from(event.r#type).ok();
-        match event_type {
-            Some(EventType::GameState) => "game.state.update".to_string(),
-            Some(EventType::Heartbeat) => "system.heartbeat".to_string(),
-            Some(EventType::MoneyChanged) => "game.money.changed".to_string(),
-            Some(EventType::ScoreChanged) => "game.score.changed".to_string(),
-            Some(EventType::HandPlayed) => "game.hand.played".to_string(),
-            Some(EventType::CardsDiscarded) => "game.cards.discarded".to_string(),
-            Some(EventType::JokersChanged) => "game.jokers.changed".to_string(),
-            Some(EventType::RoundChanged) => "game.round.changed".to_string(),
-            Some(EventType::PhaseChanged) => "game.phase.changed".to_string(),
-            Some(EventType::RoundComplete) => "game.round.complete".to_string(),
-            Some(EventType::ConnectionTest) => "system.connection.test".to_string(),
-            _ => "unknown".to_string(),
-        }
-    }
-
-    /// Check if topic matches pattern (supports * wildcard)
+        let known = EventType::try_from(event.r#type)
+            .ok()
+            .and_then(|event_type| KNOWN_TOPICS.iter().find(|(t, _)| *t == event_type));
+        match known {
+            Some((_, topic)) => topic.to_string(),
+            None if event.raw_type.is_empty() => "unknown".to_string(),
+            None => format!("events.unknown.{}", event.raw_type.replace('.', "_")),
+        }
+    }
+
+    /// Recount live subscribers for every known topic
+    ///
+    /// Run whenever subscriptions change; unknown topics aren't counted.
+    fn refresh_subscriber_counts(&self) {
+        for (_, topic) in KNOWN_TOPICS {
+            let mut count = 0;
+            for entry in self.handlers.iter() {
+                if self.matches_pattern(topic, entry.key()) {
+                    count += entry.value().len();
+                }
+            }
+            for entry in self.channels.iter() {
+                if self.matches_pattern(topic, entry.key()) {
+                    count += entry
+                        .value()
+                        .iter()
+                        .filter(|(_, ch)| !ch.is_closed())
+                        .count();
+                }
+            }
+            for entry in self.bounded.iter() {
+                if self.matches_pattern(topic, entry.key()) {
+                    count += entry
+                        .value()
+                        .iter()
+                        .filter(|(_, tx)| !tx.is_closed())
+                        .count();
+                }
+            }
+            self.metrics.set_subscriber_count(topic, count);
+        }
+    }
+
+    /// Check if topic matches pattern
+    ///
+    /// `*` matches exactly one segment. A final `#` matches one or more
+    /// remaining segments, so `game.#` covers every `game.` topic at any
+    /// depth; anywhere else `#` is an ordinary literal segment. The empty
+    /// topic only matches the empty pattern.
     pub fn matches_pattern(&self, topic: &str, pattern: &str) -> bool {
         if pattern == topic {
             return true;
         }
+        if topic.is_empty() {
+            return false;
+        }
 
-        let pattern_parts: Vec<&str> = pattern.split('.').collect();
+        let mut pattern_parts: Vec<&str> = pattern.split('.').collect();
         let topic_parts: Vec<&str> = topic.split('.').collect();
 
-        if pattern_parts.len() != topic_parts.len() {
+        if pattern_parts.last() == Some(&"#") {
+            pattern_parts.pop();
+            // Segments past the prefix are the `#` match; zip skips them
+            if topic_parts.len() <= pattern_parts.len() {
+                return false;
+            }
+        } else if pattern_parts.len() != topic_parts.len() {
             return false;
         }
 
@@ -133,9 +939,19 @@ impl EventRouter {
     }
 }
 
+/// An event's creation time in Unix milliseconds, if it has one
+///
+/// Publishers that never set the field leave it at zero; zero and negative
+/// values are treated as missing.
+pub fn event_timestamp_ms(event: &Event) -> Option<i64> {
+    (event.timestamp > 0).then_some(event.timestamp)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::models::JsonEvent;
+    use crate::proto::converter::json_to_proto_event_preserving_unknown;
 
     #[test]
     fn test_pattern_matching() {
@@ -148,5 +964,891 @@ mod tests {
 
         assert!(!router.matches_pattern("game.state.update", "game.state"));
         assert!(!router.matches_pattern("game.state.update", "system.*.*"));
+
+        // A trailing `#` matches one or more remaining segments
+        assert!(router.matches_pattern("game.state.update", "game.#"));
+        assert!(router.matches_pattern("game.round.complete", "game.#"));
+        assert!(router.matches_pattern("game.state", "game.#"));
+        assert!(router.matches_pattern("events.unknown.mod_x", "#"));
+        assert!(!router.matches_pattern("game", "game.#"));
+        assert!(!router.matches_pattern("system.heartbeat", "game.#"));
+
+        // `*.#` needs a first segment plus at least one more
+        assert!(router.matches_pattern("system.heartbeat", "*.#"));
+        assert!(router.matches_pattern("game.state.update", "*.#"));
+        assert!(!router.matches_pattern("unknown", "*.#"));
+
+        // `#` anywhere but the end is a literal segment
+        assert!(!router.matches_pattern("game.state.update", "game.#.update"));
+        assert!(router.matches_pattern("game.#.update", "game.#.update"));
+        assert!(!router.matches_pattern("game.state.update", "#.update"));
+
+        // The empty topic only matches the empty pattern
+        assert!(router.matches_pattern("", ""));
+        assert!(!router.matches_pattern("", "*"));
+        assert!(!router.matches_pattern("", "#"));
+        assert!(!router.matches_pattern("game.state.update", ""));
+    }
+
+    #[tokio::test]
+    async fn test_try_route_event_reports_delivery() {
+        let router = EventRouter::new();
+        let heartbeat = Event {
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+
+        let outcome = router.try_route_event(heartbeat.clone()).await.unwrap();
+        assert_eq!(outcome.routed, 0);
+        assert!(!outcome.dead_lettered);
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+        let outcome = router.try_route_event(heartbeat).await.unwrap();
+        assert_eq!(outcome.routed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_type_routes_on_raw_type() {
+        let router = EventRouter::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("events.unknown.*".to_string(), tx)
+            .unwrap();
+
+        let event = json_to_proto_event_preserving_unknown(JsonEvent {
+            event_type: "mod.joker_spawned".to_string(),
+            source: "BalatroMCP".to_string(),
+            timestamp: None,
+            version: None,
+            payload: serde_json::json!({}),
+            headers: None,
+            correlation_id: None,
+            causation_id: None,
+        })
+        .unwrap();
+        assert_eq!(
+            router.event_to_topic(&event),
+            "events.unknown.mod_joker_spawned"
+        );
+
+        let outcome = router.try_route_event(event).await.unwrap();
+        assert_eq!(outcome.routed, 1);
+        assert_eq!(rx.recv().await.unwrap().raw_type, "mod.joker_spawned");
+
+        // Events built without a raw type keep the old catch-all topic
+        assert_eq!(router.event_to_topic(&Event::default()), "unknown");
+    }
+
+    #[tokio::test]
+    async fn test_bounded_subscriber_honors_overflow_strategy() {
+        let config = RoutingConfig {
+            event_buffer_size: 1,
+            ..RoutingConfig::default()
+        };
+        let router = EventRouter::new_with_config(config);
+        let heartbeat = Event {
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+
+        let (_, mut newest) = router
+            .subscribe_bounded("system.heartbeat".to_string(), OverflowStrategy::DropNewest)
+            .unwrap();
+        let (_, mut oldest) = router
+            .subscribe_bounded("system.*".to_string(), OverflowStrategy::DropOldest)
+            .unwrap();
+        assert!(router.has_subscribers("system.heartbeat"));
+
+        let first = Event {
+            event_id: "first".to_string(),
+            ..heartbeat.clone()
+        };
+        let second = Event {
+            event_id: "second".to_string(),
+            ..heartbeat
+        };
+        assert_eq!(router.try_route_event(first).await.unwrap().routed, 2);
+
+        // The full drop-newest queue rejects; the drop-oldest one still accepts
+        assert_eq!(router.try_route_event(second).await.unwrap().routed, 1);
+        assert_eq!(newest.try_recv().unwrap().event_id, "first");
+        assert_eq!(oldest.try_recv().unwrap().event_id, "second");
+
+        drop(newest);
+        assert_eq!(
+            router.matching_patterns("system.heartbeat"),
+            vec!["system.*"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_block_subscriber_is_retried() {
+        let config = RoutingConfig {
+            event_buffer_size: 1,
+            max_retry_attempts: 5,
+            retry_backoff: crate::config::BackoffConfig {
+                initial_ms: 5,
+                max_ms: 20,
+                multiplier: 2.0,
+            },
+            ..RoutingConfig::default()
+        };
+        let router = EventRouter::new_with_config(config);
+        let (_, mut rx) = router
+            .subscribe_bounded(
+                "system.heartbeat".to_string(),
+                OverflowStrategy::Block {
+                    timeout: Duration::from_millis(1),
+                },
+            )
+            .unwrap();
+
+        for id in ["first", "second"] {
+            router
+                .route_event(Event {
+                    event_id: id.to_string(),
+                    r#type: EventType::Heartbeat as i32,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        // The second event timed out against the full queue and is redelivered
+        // once the subscriber catches up
+        assert_eq!(rx.try_recv().unwrap().event_id, "first");
+        let retried = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("event was not retried")
+            .unwrap();
+        assert_eq!(retried.event_id, "second");
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_event_is_dead_lettered() {
+        let heartbeat = Event {
+            event_id: "lonely".to_string(),
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+
+        // Disabled by default: unrouted events are discarded
+        let router = EventRouter::new();
+        let outcome = router.try_route_event(heartbeat.clone()).await.unwrap();
+        assert!(!outcome.dead_lettered);
+        assert!(router.drain_dead_letters().is_empty());
+
+        let router = EventRouter::new_with_config(RoutingConfig {
+            dead_letter_enabled: true,
+            ..RoutingConfig::default()
+        });
+        let outcome = router.try_route_event(heartbeat).await.unwrap();
+        assert_eq!(outcome.routed, 0);
+        assert!(outcome.dead_lettered);
+
+        let dead = router.drain_dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].event_id, "lonely");
+        assert_eq!(
+            dead[0].metadata[dead_letter::DEAD_LETTER_REASON_KEY],
+            "no_subscribers"
+        );
+        assert!(router.drain_dead_letters().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unroutable_dead_letter_fails_permanently() {
+        let router = EventRouter::new_with_config(RoutingConfig {
+            dead_letter_enabled: true,
+            dead_letter_max_reprocess_attempts: 2,
+            ..RoutingConfig::default()
+        });
+        router
+            .route_event(Event {
+                event_id: "lonely".to_string(),
+                r#type: EventType::Heartbeat as i32,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let outcome = router.reprocess_dead_letters().await;
+            assert_eq!(
+                outcome,
+                ReprocessOutcome {
+                    reprocessed: 1,
+                    failed: 1
+                }
+            );
+        }
+
+        // Out of attempts: nothing left to reprocess
+        assert_eq!(
+            router.reprocess_dead_letters().await,
+            ReprocessOutcome::default()
+        );
+        assert!(router.dead_letters().is_empty());
+        let failed = router.permanent_failures();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].event_id, "lonely");
+        assert_eq!(dead_letter::attempts(&failed[0]), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_skips_journal_and_ttl() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", uuid::Uuid::new_v4()));
+        let journal = Arc::new(EventJournal::open(&path, 1000).unwrap());
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_ttl_secs: 1,
+            dead_letter_enabled: true,
+            dead_letter_expired: true,
+            ..RoutingConfig::default()
+        })
+        .with_journal(journal.clone());
+
+        let heartbeat = |id: &str, age_ms: i64| Event {
+            event_id: id.to_string(),
+            timestamp: Utc::now().timestamp_millis() - age_ms,
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+        router.route_event(heartbeat("unrouted", 0)).await.unwrap();
+        router.route_event(heartbeat("stale", 5_000)).await.unwrap();
+        let journaled = journal.read_from(0).unwrap().0.len();
+        assert_eq!(journaled, 1);
+
+        // Still no subscriber: the unrouted event fails again without being
+        // journaled a second time
+        let outcome = router.reprocess_dead_letters().await;
+        assert_eq!(outcome.reprocessed, 2);
+        assert_eq!(journal.read_from(0).unwrap().0.len(), journaled);
+
+        // Once someone subscribes, the expired event is delivered too
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+        let outcome = router.reprocess_dead_letters().await;
+        assert_eq!(
+            outcome,
+            ReprocessOutcome {
+                reprocessed: 2,
+                failed: 0
+            }
+        );
+        let mut ids = vec![
+            rx.try_recv().unwrap().event_id,
+            rx.try_recv().unwrap().event_id,
+        ];
+        ids.sort();
+        assert_eq!(ids, vec!["stale", "unrouted"]);
+        assert_eq!(journal.read_from(0).unwrap().0.len(), journaled);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_retry_backlog_is_bounded_per_subscriber() {
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_buffer_size: 1,
+            dead_letter_enabled: true,
+            max_retry_attempts: 10,
+            retry_backoff: crate::config::BackoffConfig {
+                initial_ms: 60_000,
+                max_ms: 60_000,
+                multiplier: 2.0,
+            },
+            ..RoutingConfig::default()
+        });
+        let (_, _stalled) = router
+            .subscribe_bounded(
+                "system.heartbeat".to_string(),
+                OverflowStrategy::Block {
+                    timeout: Duration::from_millis(1),
+                },
+            )
+            .unwrap();
+
+        for i in 0..5 {
+            router
+                .route_event(Event {
+                    event_id: format!("evt-{i}"),
+                    r#type: EventType::Heartbeat as i32,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        // evt-0 fills the queue and evt-1 waits to be retried; the rest find
+        // the backlog full instead of each spawning a retry
+        let dead: Vec<_> = router
+            .dead_letters()
+            .into_iter()
+            .map(|e| {
+                (
+                    e.event_id,
+                    e.metadata[dead_letter::DEAD_LETTER_REASON_KEY].clone(),
+                )
+            })
+            .collect();
+        let expected: Vec<_> = (2..5)
+            .map(|i| (format!("evt-{i}"), "retry_backlog_full".to_string()))
+            .collect();
+        assert_eq!(dead, expected);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_are_dead_lettered() {
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_buffer_size: 1,
+            dead_letter_enabled: true,
+            max_retry_attempts: 2,
+            retry_backoff: crate::config::BackoffConfig {
+                initial_ms: 1,
+                max_ms: 2,
+                multiplier: 2.0,
+            },
+            ..RoutingConfig::default()
+        });
+        let (_, _stalled) = router
+            .subscribe_bounded(
+                "system.heartbeat".to_string(),
+                OverflowStrategy::Block {
+                    timeout: Duration::from_millis(1),
+                },
+            )
+            .unwrap();
+
+        for id in ["first", "second"] {
+            let outcome = router
+                .try_route_event(Event {
+                    event_id: id.to_string(),
+                    r#type: EventType::Heartbeat as i32,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            // A full queue is not "no subscribers"
+            assert!(!outcome.dead_lettered);
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while router.dead_letters().is_empty() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let dead = router.drain_dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].event_id, "second");
+        assert_eq!(
+            dead[0].metadata[dead_letter::DEAD_LETTER_REASON_KEY],
+            "max_retries_exhausted"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_publishers_and_subscribers_deliver_without_deadlock() {
+        const PUBLISHERS: usize = 8;
+        const EVENTS_PER_PUBLISHER: usize = 200;
+        const TOTAL: usize = PUBLISHERS * EVENTS_PER_PUBLISHER;
+
+        let router = Arc::new(EventRouter::new());
+        let mut live = Vec::new();
+        for i in 0..32 {
+            let pattern = if i % 2 == 0 {
+                "system.heartbeat"
+            } else {
+                "system.*"
+            };
+            let (tx, rx) = mpsc::unbounded_channel();
+            router.subscribe_channel(pattern.to_string(), tx).unwrap();
+            live.push(rx);
+        }
+        // Closed subscribers are reaped while publishing is under way
+        for _ in 0..16 {
+            let (tx, rx) = mpsc::unbounded_channel();
+            router
+                .subscribe_channel("system.heartbeat".to_string(), tx)
+                .unwrap();
+            drop(rx);
+        }
+        // A handler touching the router mid-delivery must not deadlock
+        let handler_router = Arc::downgrade(&router);
+        let handled = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handler_count = handled.clone();
+        router
+            .subscribe_handler(
+                "system.*".to_string(),
+                Arc::new(move |_| {
+                    handler_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    if let Some(router) = handler_router.upgrade() {
+                        assert!(!router.unsubscribe_pattern("game.unused"));
+                    }
+                }),
+            )
+            .unwrap();
+
+        let publishers: Vec<_> = (0..PUBLISHERS)
+            .map(|_| {
+                let router = router.clone();
+                tokio::spawn(async move {
+                    let mut routed = 0;
+                    for _ in 0..EVENTS_PER_PUBLISHER {
+                        let event = Event {
+                            r#type: EventType::Heartbeat as i32,
+                            ..Default::default()
+                        };
+                        routed += router.try_route_event(event).await.unwrap().routed;
+                    }
+                    routed
+                })
+            })
+            .collect();
+
+        let routed = tokio::time::timeout(Duration::from_secs(10), async {
+            let mut routed = 0;
+            for publisher in publishers {
+                routed += publisher.await.unwrap();
+            }
+            routed
+        })
+        .await
+        .expect("routing deadlocked");
+
+        assert_eq!(routed, TOTAL * (live.len() + 1));
+        assert_eq!(handled.load(std::sync::atomic::Ordering::Relaxed), TOTAL);
+        for rx in &mut live {
+            let mut received = 0;
+            while rx.try_recv().is_ok() {
+                received += 1;
+            }
+            assert_eq!(received, TOTAL);
+        }
+        assert_eq!(router.channels.get("system.heartbeat").unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_topic_cap_rejects_new_patterns_until_one_frees() {
+        let config = RoutingConfig {
+            max_topics: 2,
+            ..RoutingConfig::default()
+        };
+        let router = EventRouter::new_with_config(config);
+
+        let (tx_a, _rx_a) = mpsc::unbounded_channel();
+        let (tx_b, rx_b) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("game.*.*".to_string(), tx_a.clone())
+            .unwrap();
+        router
+            .subscribe_channel("system.*".to_string(), tx_b)
+            .unwrap();
+
+        let (tx_c, _rx_c) = mpsc::unbounded_channel();
+        let err = router
+            .subscribe_channel("game.money.*".to_string(), tx_c.clone())
+            .unwrap_err();
+        assert!(matches!(err, SubscribeError::TooManyTopics { max: 2 }));
+
+        // Existing patterns keep accepting subscribers at the cap
+        router
+            .subscribe_channel("game.*.*".to_string(), tx_a)
+            .unwrap();
+
+        // Once a pattern has no live subscribers it is evicted for a new one
+        drop(rx_b);
+        router
+            .subscribe_channel("game.money.*".to_string(), tx_c)
+            .unwrap();
+        assert!(!router.has_subscribers("system.heartbeat"));
+        assert!(router.has_subscribers("game.money.changed"));
+
+        // Explicitly unsubscribing frees a slot too
+        assert!(router.unsubscribe_pattern("game.money.*"));
+        router
+            .subscribe_handler("system.*".to_string(), Arc::new(|_| {}))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_subscriber_cap_counts_every_kind_of_subscriber() {
+        let config = RoutingConfig {
+            max_subscribers_per_topic: 3,
+            ..RoutingConfig::default()
+        };
+        let router = EventRouter::new_with_config(config);
+        let pattern = || "game.*.*".to_string();
+
+        let handler = router
+            .subscribe_handler(pattern(), Arc::new(|_| {}))
+            .unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        router.subscribe_channel(pattern(), tx).unwrap();
+        let (_, _bounded) = router
+            .subscribe_bounded(pattern(), OverflowStrategy::DropOldest)
+            .unwrap();
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let err = router.subscribe_channel(pattern(), tx.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            SubscribeError::TooManySubscribers { max: 3, .. }
+        ));
+        assert!(router
+            .subscribe_handler(pattern(), Arc::new(|_| {}))
+            .is_err());
+        // The cap is per pattern
+        router
+            .subscribe_channel("system.*".to_string(), tx.clone())
+            .unwrap();
+
+        assert!(router.unsubscribe(handler));
+        let replacement = router.subscribe_channel(pattern(), tx.clone()).unwrap();
+        assert!(router.subscribe_channel(pattern(), tx.clone()).is_err());
+
+        // A channel whose receiver is gone no longer counts
+        drop(rx);
+        router.subscribe_channel(pattern(), tx).unwrap();
+        assert!(router.unsubscribe(replacement));
+    }
+
+    #[tokio::test]
+    async fn test_publish_after_delays_and_cancels() {
+        let config = RoutingConfig {
+            max_scheduled_events: 2,
+            ..RoutingConfig::default()
+        };
+        let router = Arc::new(EventRouter::new_with_config(config));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+        let heartbeat = |id: &str| Event {
+            event_id: id.to_string(),
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+
+        let id = router
+            .publish_after(heartbeat("later"), Duration::from_millis(100))
+            .unwrap();
+        let cancelled = router
+            .publish_after(heartbeat("cancelled"), Duration::from_millis(100))
+            .unwrap();
+        assert!(matches!(
+            router.publish_after(heartbeat("third"), Duration::from_millis(100)),
+            Err(ScheduleError::TooManyScheduled { max: 2 })
+        ));
+
+        assert!(router.cancel_scheduled(&cancelled));
+        assert!(!router.cancel_scheduled(&cancelled));
+        assert_eq!(router.scheduled_count(), 1);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(rx.try_recv().is_err(), "delivered before its delay");
+
+        let delivered = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivered.event_id, id);
+        assert_eq!(router.scheduled_count(), 0);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(rx.try_recv().is_err(), "cancelled event was delivered");
+    }
+
+    #[test]
+    fn test_subscriber_introspection() {
+        let router = EventRouter::new();
+        assert!(!router.has_subscribers("game.state.update"));
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("game.*.update".to_string(), tx)
+            .unwrap();
+        router
+            .subscribe_handler("*.*.*".to_string(), Arc::new(|_| {}))
+            .unwrap();
+        router
+            .subscribe_handler("system.*".to_string(), Arc::new(|_| {}))
+            .unwrap();
+
+        assert!(router.has_subscribers("game.state.update"));
+        assert_eq!(
+            router.matching_patterns("game.state.update"),
+            vec!["*.*.*".to_string(), "game.*.update".to_string()]
+        );
+
+        assert!(router.unsubscribe_pattern("*.*.*"));
+        assert_eq!(
+            router.matching_patterns("game.state.update"),
+            vec!["game.*.update".to_string()]
+        );
+
+        // A dropped receiver no longer counts as a subscriber
+        drop(rx);
+        assert!(!router.has_subscribers("game.state.update"));
+        assert!(router.has_subscribers("system.heartbeat"));
+        assert!(!router.unsubscribe_pattern("no.such.pattern"));
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_removes_only_that_subscription() {
+        let router = EventRouter::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let handler = |name: &'static str| -> EventHandler {
+            let fired = fired.clone();
+            Arc::new(move |_| fired.lock().unwrap().push(name))
+        };
+
+        let first = router
+            .subscribe_handler("system.heartbeat".to_string(), handler("first"))
+            .unwrap();
+        let second = router
+            .subscribe_handler("system.heartbeat".to_string(), handler("second"))
+            .unwrap();
+        assert!(second > first);
+
+        assert!(router.unsubscribe(first));
+        assert!(!router.unsubscribe(first));
+
+        let heartbeat = Event {
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+        let outcome = router.try_route_event(heartbeat.clone()).await.unwrap();
+        assert_eq!(outcome.routed, 1);
+        assert_eq!(*fired.lock().unwrap(), vec!["second"]);
+
+        // Channels are removed the same way
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let channel = router
+            .subscribe_channel("system.*".to_string(), tx)
+            .unwrap();
+        assert!(router.unsubscribe(channel));
+        router.try_route_event(heartbeat.clone()).await.unwrap();
+        assert!(rx.try_recv().is_err());
+        assert_eq!(*fired.lock().unwrap(), vec!["second", "second"]);
+
+        // And so are bounded queues
+        let (bounded, mut queue) = router
+            .subscribe_bounded("system.#".to_string(), OverflowStrategy::DropOldest)
+            .unwrap();
+        assert!(bounded > channel);
+        assert!(router.unsubscribe(bounded));
+        assert!(!router.unsubscribe(bounded));
+        router.try_route_event(heartbeat).await.unwrap();
+        assert!(queue.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_topic_ttl_overrides() {
+        let mut config = RoutingConfig {
+            event_ttl_secs: 30,
+            ..RoutingConfig::default()
+        };
+        config
+            .topic_ttl_overrides
+            .insert("system.heartbeat".to_string(), 5);
+        config
+            .topic_ttl_overrides
+            .insert("game.round.complete".to_string(), 3600);
+        let router = EventRouter::new_with_config(config);
+
+        assert_eq!(router.ttl_for_topic("system.heartbeat"), 5);
+        assert_eq!(router.ttl_for_topic("game.money.changed"), 30);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("*.*".to_string(), tx.clone())
+            .unwrap();
+        router.subscribe_channel("*.*.*".to_string(), tx).unwrap();
+
+        let ten_seconds_ago = Utc::now().timestamp_millis() - 10_000;
+        let heartbeat = Event {
+            event_id: "hb".to_string(),
+            timestamp: ten_seconds_ago,
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+        let round_complete = Event {
+            event_id: "rc".to_string(),
+            timestamp: ten_seconds_ago,
+            r#type: EventType::RoundComplete as i32,
+            ..Default::default()
+        };
+
+        router.route_event(heartbeat).await.unwrap();
+        router.route_event(round_complete).await.unwrap();
+
+        let delivered = rx.try_recv().unwrap();
+        assert_eq!(delivered.event_id, "rc");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_topic_metrics_track_routing_and_subscribers() {
+        let router = EventRouter::new();
+        let event = |event_type: EventType, raw_type: &str| Event {
+            r#type: event_type as i32,
+            raw_type: raw_type.to_string(),
+            ..Default::default()
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+        router
+            .subscribe_handler("game.#".to_string(), Arc::new(|_| {}))
+            .unwrap();
+        router
+            .subscribe_handler("*.money.*".to_string(), Arc::new(|_| {}))
+            .unwrap();
+        let metrics = router.metrics();
+        assert_eq!(metrics.topic("system.heartbeat").subscriber_count, 1);
+        assert_eq!(metrics.topic("game.money.changed").subscriber_count, 2);
+        assert_eq!(metrics.topic("game.state.update").subscriber_count, 1);
+
+        for _ in 0..3 {
+            router
+                .route_event(event(EventType::Heartbeat, ""))
+                .await
+                .unwrap();
+        }
+        router
+            .route_event(event(EventType::MoneyChanged, ""))
+            .await
+            .unwrap();
+        router
+            .route_event(event(EventType::Unspecified, "mod.a"))
+            .await
+            .unwrap();
+        router
+            .route_event(event(EventType::Unspecified, "mod.b"))
+            .await
+            .unwrap();
+
+        let heartbeat = metrics.topic("system.heartbeat");
+        assert_eq!((heartbeat.events_routed, heartbeat.events_dropped), (3, 0));
+        assert_eq!(metrics.topic("game.money.changed").events_routed, 2);
+        // Every unknown type shares one label
+        assert_eq!(metrics.topic("events.unknown.mod_a").events_dropped, 2);
+
+        // The dropped subscriber is swept on the next publish
+        drop(rx);
+        router
+            .route_event(event(EventType::Heartbeat, ""))
+            .await
+            .unwrap();
+        let heartbeat = metrics.topic("system.heartbeat");
+        assert_eq!(heartbeat.events_routed, 3);
+        assert_eq!(heartbeat.events_dropped, 1);
+        assert_eq!(heartbeat.subscriber_count, 0);
+    }
+
+    #[test]
+    fn test_ttl_expiry_at_injected_now() {
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_ttl_secs: 60,
+            ..RoutingConfig::default()
+        });
+        let now = 1_700_000_000_000;
+        let at = |timestamp| Event {
+            timestamp,
+            ..Default::default()
+        };
+
+        assert!(!router.is_expired(&at(now - 60_000), "game.state.update", now));
+        assert!(router.is_expired(&at(now - 60_001), "game.state.update", now));
+        // Future-dated events are fresh, not negative-aged
+        assert!(!router.is_expired(&at(now + 5_000), "game.state.update", now));
+
+        // Missing timestamps never expire
+        assert_eq!(event_timestamp_ms(&at(0)), None);
+        assert_eq!(event_timestamp_ms(&at(-1)), None);
+        assert!(!router.is_expired(&at(0), "game.state.update", now));
+
+        // A TTL of 0 disables expiry
+        let router = EventRouter::new();
+        assert!(!router.is_expired(&at(1), "game.state.update", now));
+    }
+
+    #[tokio::test]
+    async fn test_expired_events_can_be_dead_lettered() {
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_ttl_secs: 1,
+            dead_letter_enabled: true,
+            dead_letter_expired: true,
+            ..RoutingConfig::default()
+        });
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        router
+            .subscribe_channel("system.heartbeat".to_string(), tx)
+            .unwrap();
+
+        let stale = Event {
+            event_id: "stale".to_string(),
+            timestamp: Utc::now().timestamp_millis() - 5_000,
+            r#type: EventType::Heartbeat as i32,
+            ..Default::default()
+        };
+        let fresh = Event {
+            event_id: "fresh".to_string(),
+            timestamp: Utc::now().timestamp_millis(),
+            ..stale.clone()
+        };
+
+        let outcome = router.try_route_event(stale).await.unwrap();
+        assert_eq!(outcome.routed, 0);
+        assert!(outcome.dead_lettered);
+        assert_eq!(router.try_route_event(fresh).await.unwrap().routed, 1);
+
+        assert_eq!(rx.try_recv().unwrap().event_id, "fresh");
+        let dead = router.drain_dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].event_id, "stale");
+        assert_eq!(
+            dead[0].metadata[dead_letter::DEAD_LETTER_REASON_KEY],
+            "expired"
+        );
+    }
+
+    #[test]
+    fn test_expired_unknown_types_share_one_metric_series() {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let router = EventRouter::new_with_config(RoutingConfig {
+            event_ttl_secs: 1,
+            ..RoutingConfig::default()
+        });
+
+        metrics::with_local_recorder(&recorder, || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                for raw_type in ["a", "b"] {
+                    let stale = Event {
+                        r#type: 9999,
+                        raw_type: raw_type.to_string(),
+                        timestamp: Utc::now().timestamp_millis() - 5_000,
+                        ..Default::default()
+                    };
+                    router.try_route_event(stale).await.unwrap();
+                }
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains(r#"event_bus_events_expired_total{topic="events.unknown"} 2"#),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("events.unknown.a"), "{rendered}");
     }
 }
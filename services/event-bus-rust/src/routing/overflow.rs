@@ -0,0 +1,336 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::config::SubscriberOverflow;
+use crate::proto::Event;
+
+/// What a bounded subscriber queue does when an event arrives while it is full
+///
+/// - `DropOldest` keeps the subscriber current at the cost of gaps: the stalest
+///   queued event is discarded. Suits dashboards that only show latest state.
+/// - `DropNewest` keeps what is already queued and rejects the incoming event.
+///   Publishers are never slowed, but the subscriber sees a stale backlog.
+/// - `Block` makes the router wait up to `timeout` for space, so nothing is
+///   lost unless the subscriber stalls longer than that. Slows routing for
+///   every subscriber of the event, so reserve it for consumers that need no
+///   gaps (auditors) and keep the timeout short.
+/// - `Disconnect` closes the subscription instead. The receiver still gets
+///   what was queued, then sees the end of the stream and must resubscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowStrategy {
+    DropOldest,
+    DropNewest,
+    Block { timeout: Duration },
+    Disconnect,
+}
+
+impl From<SubscriberOverflow> for OverflowStrategy {
+    fn from(policy: SubscriberOverflow) -> Self {
+        match policy {
+            SubscriberOverflow::DropOldest => Self::DropOldest,
+            SubscriberOverflow::Disconnect => Self::Disconnect,
+        }
+    }
+}
+
+/// Result of offering an event to a bounded subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOutcome {
+    /// Queued with room to spare
+    Queued,
+    /// Queued after discarding the oldest queued event
+    DroppedOldest,
+    /// Not queued: the queue was full (after waiting, for `Block`)
+    Rejected,
+    /// The receiver is gone, or was just disconnected for falling behind
+    Closed,
+}
+
+struct Shared {
+    queue: Mutex<VecDeque<Event>>,
+    /// Rejected events waiting for redelivery, at most `capacity` of them
+    retries: Mutex<RetryBacklog>,
+    capacity: usize,
+    strategy: OverflowStrategy,
+    event_ready: Notify,
+    space_ready: Notify,
+    closed: AtomicBool,
+}
+
+#[derive(Default)]
+struct RetryBacklog {
+    events: VecDeque<Event>,
+    /// Whether a task is redelivering `events`
+    draining: bool,
+    /// Whether that task holds an event taken from `events`
+    in_flight: bool,
+}
+
+/// Router side of a bounded subscriber queue
+#[derive(Clone)]
+pub struct BoundedSender {
+    shared: Arc<Shared>,
+}
+
+/// Subscriber side of a bounded queue; dropping it closes the subscription
+pub struct BoundedReceiver {
+    shared: Arc<Shared>,
+}
+
+/// Create a queue holding at most `capacity` events, overflowing per `strategy`
+pub fn bounded_channel(
+    capacity: usize,
+    strategy: OverflowStrategy,
+) -> (BoundedSender, BoundedReceiver) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(VecDeque::with_capacity(capacity)),
+        retries: Mutex::new(RetryBacklog::default()),
+        capacity: capacity.max(1),
+        strategy,
+        event_ready: Notify::new(),
+        space_ready: Notify::new(),
+        closed: AtomicBool::new(false),
+    });
+    (
+        BoundedSender {
+            shared: shared.clone(),
+        },
+        BoundedReceiver { shared },
+    )
+}
+
+impl BoundedSender {
+    /// Offer an event, applying the overflow strategy if the queue is full
+    pub async fn send(&self, event: Event) -> SendOutcome {
+        let shared = &self.shared;
+        let deadline = match shared.strategy {
+            OverflowStrategy::Block { timeout } => Some(tokio::time::Instant::now() + timeout),
+            _ => None,
+        };
+
+        loop {
+            // Register interest before checking so a dequeue can't be missed
+            let space = shared.space_ready.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+
+            if self.is_closed() {
+                return SendOutcome::Closed;
+            }
+
+            {
+                let mut queue = shared.queue.lock().unwrap();
+                if queue.len() < shared.capacity {
+                    queue.push_back(event);
+                    drop(queue);
+                    shared.event_ready.notify_one();
+                    return SendOutcome::Queued;
+                }
+
+                match shared.strategy {
+                    OverflowStrategy::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(event);
+                        drop(queue);
+                        shared.event_ready.notify_one();
+                        return SendOutcome::DroppedOldest;
+                    }
+                    OverflowStrategy::DropNewest => return SendOutcome::Rejected,
+                    OverflowStrategy::Disconnect => {
+                        drop(queue);
+                        shared.closed.store(true, Ordering::SeqCst);
+                        shared.event_ready.notify_one();
+                        return SendOutcome::Closed;
+                    }
+                    OverflowStrategy::Block { .. } => {}
+                }
+            }
+
+            let deadline = deadline.expect("only Block waits for space");
+            if tokio::time::timeout_at(deadline, space).await.is_err() {
+                return SendOutcome::Rejected;
+            }
+        }
+    }
+
+    /// Park a rejected event for redelivery
+    ///
+    /// Each subscriber holds at most as many parked events as its queue,
+    /// counting the one being redelivered, so a stalled subscriber can't grow
+    /// the bus without limit. Returns `None`, dropping the event, when the
+    /// backlog is full, and `Some(true)` when nothing is draining the backlog
+    /// yet and the caller must start a task calling `next_retry`.
+    pub fn park_retry(&self, event: Event) -> Option<bool> {
+        let mut backlog = self.shared.retries.lock().unwrap();
+        if backlog.events.len() + usize::from(backlog.in_flight) >= self.shared.capacity {
+            return None;
+        }
+        backlog.events.push_back(event);
+        let start = !backlog.draining;
+        backlog.draining = true;
+        Some(start)
+    }
+
+    /// Next parked event, oldest first, for the draining task
+    ///
+    /// Calling it again means the previous event is done with. `None` once
+    /// the backlog is empty, after which the task must stop: the next
+    /// `park_retry` starts a new one.
+    pub fn next_retry(&self) -> Option<Event> {
+        let mut backlog = self.shared.retries.lock().unwrap();
+        let event = backlog.events.pop_front();
+        backlog.draining = event.is_some();
+        backlog.in_flight = event.is_some();
+        event
+    }
+
+    pub fn strategy(&self) -> OverflowStrategy {
+        self.shared.strategy
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl BoundedReceiver {
+    /// Wait for the next event; `None` once disconnected and drained
+    pub async fn recv(&mut self) -> Option<Event> {
+        let shared = self.shared.clone();
+        loop {
+            // Register interest before checking so a send can't be missed
+            let ready = shared.event_ready.notified();
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            if shared.closed.load(Ordering::SeqCst) {
+                return None;
+            }
+            ready.await;
+        }
+    }
+
+    /// Take the next queued event without waiting
+    pub fn try_recv(&mut self) -> Option<Event> {
+        let event = self.shared.queue.lock().unwrap().pop_front();
+        if event.is_some() {
+            self.shared.space_ready.notify_waiters();
+        }
+        event
+    }
+
+    /// Number of queued events
+    pub fn len(&self) -> usize {
+        self.shared.queue.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for BoundedReceiver {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+        self.shared.space_ready.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str) -> Event {
+        Event {
+            event_id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn ids(rx: &mut BoundedReceiver) -> Vec<String> {
+        std::iter::from_fn(|| rx.try_recv())
+            .map(|e| e.event_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_discards_stalest_event() {
+        let (tx, mut rx) = bounded_channel(2, OverflowStrategy::DropOldest);
+        assert_eq!(tx.send(event("a")).await, SendOutcome::Queued);
+        assert_eq!(tx.send(event("b")).await, SendOutcome::Queued);
+        assert_eq!(tx.send(event("c")).await, SendOutcome::DroppedOldest);
+        assert_eq!(ids(&mut rx), vec!["b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_rejects_incoming_event() {
+        let (tx, mut rx) = bounded_channel(2, OverflowStrategy::DropNewest);
+        tx.send(event("a")).await;
+        tx.send(event("b")).await;
+        assert_eq!(tx.send(event("c")).await, SendOutcome::Rejected);
+        assert_eq!(ids(&mut rx), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space_until_timeout() {
+        let strategy = OverflowStrategy::Block {
+            timeout: Duration::from_millis(200),
+        };
+        let (tx, mut rx) = bounded_channel(1, strategy);
+        tx.send(event("a")).await;
+
+        // Nobody reads: the send gives up after the timeout
+        let start = std::time::Instant::now();
+        assert_eq!(tx.send(event("b")).await, SendOutcome::Rejected);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        // A reader freeing space lets the blocked send through
+        let sender = tx.clone();
+        let blocked = tokio::spawn(async move { sender.send(event("c")).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+        assert_eq!(rx.recv().await.unwrap().event_id, "a");
+        assert_eq!(blocked.await.unwrap(), SendOutcome::Queued);
+        assert_eq!(ids(&mut rx), vec!["c"]);
+
+        drop(rx);
+        assert_eq!(tx.send(event("d")).await, SendOutcome::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_closes_full_queue() {
+        let (tx, mut rx) = bounded_channel(2, OverflowStrategy::Disconnect);
+        tx.send(event("a")).await;
+        tx.send(event("b")).await;
+        assert_eq!(tx.send(event("c")).await, SendOutcome::Closed);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(event("d")).await, SendOutcome::Closed);
+
+        // What was queued is still delivered, then the stream ends
+        assert_eq!(rx.recv().await.unwrap().event_id, "a");
+        assert_eq!(rx.recv().await.unwrap().event_id, "b");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_backlog_is_bounded() {
+        let (tx, _rx) = bounded_channel(2, OverflowStrategy::DropNewest);
+        assert_eq!(tx.park_retry(event("a")), Some(true));
+        assert_eq!(tx.park_retry(event("b")), Some(false));
+        assert_eq!(tx.park_retry(event("c")), None);
+
+        // The event being redelivered still takes a slot
+        assert_eq!(tx.next_retry().unwrap().event_id, "a");
+        assert_eq!(tx.park_retry(event("d")), None);
+        assert_eq!(tx.next_retry().unwrap().event_id, "b");
+        assert_eq!(tx.park_retry(event("d")), Some(false));
+        assert_eq!(tx.next_retry().unwrap().event_id, "d");
+        assert!(tx.next_retry().is_none());
+
+        // Drained: the next parked event needs a new task
+        assert_eq!(tx.park_retry(event("e")), Some(true));
+    }
+}
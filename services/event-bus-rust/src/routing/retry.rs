@@ -0,0 +1,147 @@
+use std::future::Future;
+use std::time::Duration;
+
+use super::overflow::SendOutcome;
+use crate::config::BackoffConfig;
+use crate::proto::Event;
+
+/// Exponential backoff schedule for redelivering events that failed to send
+///
+/// Built from `routing.max_retry_attempts` and `routing.retry_backoff`: retry
+/// `n` (1-based) waits `initial_ms * multiplier^(n - 1)`, capped at `max_ms`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial: Duration,
+    max: Duration,
+    multiplier: f64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, backoff: &BackoffConfig) -> Self {
+        Self {
+            max_attempts,
+            initial: Duration::from_millis(backoff.initial_ms),
+            max: Duration::from_millis(backoff.max_ms),
+            multiplier: backoff.multiplier,
+        }
+    }
+
+    /// Retries allowed after the first failed send
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Delay before retry number `attempt` (1-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let millis = self.initial.as_millis() as f64 * self.multiplier.powi(exponent);
+        Duration::from_millis(millis.min(self.max.as_millis() as f64) as u64)
+    }
+}
+
+/// How a retried delivery ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOutcome {
+    /// Delivered on the given retry (1-based)
+    Delivered { attempts: u32 },
+    /// Still failing after every allowed retry
+    Exhausted { attempts: u32 },
+    /// The subscriber went away; retrying stopped after the given retry
+    Closed { attempts: u32 },
+}
+
+/// Redeliver `event`, which just failed to send, until `send` accepts it
+///
+/// Each retry sleeps per `policy` and then offers a fresh clone of the event.
+/// `Rejected` is treated as transient and retried; `Closed` is permanent.
+pub async fn retry_delivery<F, Fut>(policy: &RetryPolicy, event: Event, mut send: F) -> RetryOutcome
+where
+    F: FnMut(Event) -> Fut,
+    Fut: Future<Output = SendOutcome>,
+{
+    for attempt in 1..=policy.max_attempts {
+        tokio::time::sleep(policy.delay(attempt)).await;
+        match send(event.clone()).await {
+            SendOutcome::Queued | SendOutcome::DroppedOldest => {
+                return RetryOutcome::Delivered { attempts: attempt }
+            }
+            SendOutcome::Closed => return RetryOutcome::Closed { attempts: attempt },
+            SendOutcome::Rejected => {}
+        }
+    }
+    RetryOutcome::Exhausted {
+        attempts: policy.max_attempts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::new(
+            max_attempts,
+            &BackoffConfig {
+                initial_ms: 1,
+                max_ms: 4,
+                multiplier: 2.0,
+            },
+        )
+    }
+
+    /// A send that is rejected `failures` times, then accepts
+    fn flaky(
+        failures: u32,
+        calls: Arc<AtomicU32>,
+    ) -> impl FnMut(Event) -> std::future::Ready<SendOutcome> {
+        move |_| {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            std::future::ready(if call <= failures {
+                SendOutcome::Rejected
+            } else {
+                SendOutcome::Queued
+            })
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_exponentially_up_to_max() {
+        let policy = RetryPolicy::new(
+            5,
+            &BackoffConfig {
+                initial_ms: 100,
+                max_ms: 1000,
+                multiplier: 3.0,
+            },
+        );
+        let delays: Vec<u64> = (1..=5)
+            .map(|n| policy.delay(n).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![100, 300, 900, 1000, 1000]);
+    }
+
+    #[tokio::test]
+    async fn test_flaky_send_is_eventually_delivered() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let outcome = retry_delivery(&policy(3), Event::default(), flaky(2, calls.clone())).await;
+        assert_eq!(outcome, RetryOutcome::Delivered { attempts: 3 });
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let outcome = retry_delivery(&policy(3), Event::default(), flaky(5, calls.clone())).await;
+        assert_eq!(outcome, RetryOutcome::Exhausted { attempts: 3 });
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let outcome = retry_delivery(&policy(3), Event::default(), |_| async {
+            SendOutcome::Closed
+        })
+        .await;
+        assert_eq!(outcome, RetryOutcome::Closed { attempts: 1 });
+    }
+}
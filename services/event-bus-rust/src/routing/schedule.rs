@@ -0,0 +1,76 @@
+use dashmap::{mapref::entry::Entry, DashMap};
+use tokio::task::AbortHandle;
+
+use crate::metrics::EventMetrics;
+
+/// Why a delayed publish was refused
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("scheduled event limit of {max} reached")]
+    TooManyScheduled { max: usize },
+    #[error("an event with id '{0}' is already scheduled")]
+    DuplicateId(String),
+}
+
+/// Delayed events waiting on their timers, keyed by event id
+///
+/// Each pending event is a task sleeping on tokio's timer wheel; whichever of
+/// the timer or a cancellation removes the entry first decides whether the
+/// event is routed.
+pub(super) struct ScheduledEvents {
+    pending: DashMap<String, AbortHandle>,
+    max: usize,
+}
+
+impl ScheduledEvents {
+    pub(super) fn new(max: usize) -> Self {
+        Self {
+            pending: DashMap::new(),
+            max,
+        }
+    }
+
+    /// Register `id`, spawning its timer task with `spawn` once admitted
+    pub(super) fn insert(
+        &self,
+        id: String,
+        spawn: impl FnOnce() -> AbortHandle,
+    ) -> Result<(), ScheduleError> {
+        if self.pending.len() >= self.max {
+            return Err(ScheduleError::TooManyScheduled { max: self.max });
+        }
+        match self.pending.entry(id) {
+            Entry::Occupied(entry) => Err(ScheduleError::DuplicateId(entry.key().clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(spawn());
+                self.update_gauge();
+                Ok(())
+            }
+        }
+    }
+
+    /// Claim `id` for delivery; false if it was cancelled first
+    pub(super) fn take_due(&self, id: &str) -> bool {
+        let due = self.pending.remove(id).is_some();
+        self.update_gauge();
+        due
+    }
+
+    /// Cancel `id`; false if it already fired or was never scheduled
+    pub(super) fn cancel(&self, id: &str) -> bool {
+        let Some((_, handle)) = self.pending.remove(id) else {
+            return false;
+        };
+        handle.abort();
+        self.update_gauge();
+        true
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn update_gauge(&self) {
+        EventMetrics::update_scheduled_events(self.pending.len() as f64);
+    }
+}
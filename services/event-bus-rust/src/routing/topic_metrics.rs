@@ -0,0 +1,89 @@
+use dashmap::DashMap;
+
+use crate::metrics::EventMetrics;
+
+/// Label for events routed under `events.unknown.{raw_type}`
+pub const UNKNOWN_TYPE_LABEL: &str = "events.unknown";
+/// Label for any other topic outside `KNOWN_TOPICS`
+pub const OTHER_LABEL: &str = "unknown";
+
+/// Counters for one topic label
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopicStats {
+    /// Deliveries to subscribers
+    pub events_routed: u64,
+    /// Events that reached no subscriber (expired, dropped or unsubscribed)
+    pub events_dropped: u64,
+    /// Live subscribers whose pattern matches the topic
+    pub subscriber_count: usize,
+}
+
+/// Per-topic routing counters, mirrored to Prometheus with a `topic` label
+///
+/// Labels are limited to the router's known topics plus two catch-alls, so
+/// publishers inventing event types can't grow the metrics registry.
+#[derive(Debug, Default)]
+pub struct RouterMetrics {
+    topics: DashMap<&'static str, TopicStats>,
+}
+
+impl RouterMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the counters `topic` is recorded under
+    pub fn topic(&self, topic: &str) -> TopicStats {
+        self.topics
+            .get(topic_label(topic))
+            .map(|stats| *stats)
+            .unwrap_or_default()
+    }
+
+    pub(super) fn record_routed(&self, topic: &str, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let label = topic_label(topic);
+        self.topics.entry(label).or_default().events_routed += count as u64;
+        EventMetrics::record_events_routed(label, count as u64);
+    }
+
+    pub(super) fn record_dropped(&self, topic: &str) {
+        let label = topic_label(topic);
+        self.topics.entry(label).or_default().events_dropped += 1;
+        EventMetrics::record_events_dropped(label);
+    }
+
+    pub(super) fn set_subscriber_count(&self, topic: &str, count: usize) {
+        let label = topic_label(topic);
+        self.topics.entry(label).or_default().subscriber_count = count;
+        EventMetrics::update_topic_subscribers(label, count as f64);
+    }
+}
+
+/// The bounded metric label a resolved topic is recorded under
+pub fn topic_label(topic: &str) -> &'static str {
+    if let Some(known) = super::KNOWN_TOPICS.iter().find(|(_, t)| *t == topic) {
+        return known.1;
+    }
+    if topic.starts_with("events.unknown.") {
+        UNKNOWN_TYPE_LABEL
+    } else {
+        OTHER_LABEL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_are_bounded() {
+        assert_eq!(topic_label("system.heartbeat"), "system.heartbeat");
+        assert_eq!(topic_label("events.unknown.mod_a"), UNKNOWN_TYPE_LABEL);
+        assert_eq!(topic_label("events.unknown.mod_b"), UNKNOWN_TYPE_LABEL);
+        assert_eq!(topic_label("unknown"), OTHER_LABEL);
+        assert_eq!(topic_label("made.up.topic"), OTHER_LABEL);
+    }
+}
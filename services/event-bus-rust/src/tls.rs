@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Context, Result};
+use axum::serve::Listener;
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{
+    rustls::{server::WebPkiClientVerifier, RootCertStore, ServerConfig},
+    server::TlsStream,
+    TlsAcceptor,
+};
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+use tracing::{debug, warn};
+
+use crate::config::TlsConfig;
+
+/// Handshakes slower than this are abandoned
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// PEM material named by `security.tls`, read and checked once at startup
+///
+/// `load` fails on files that are missing, unreadable or hold no usable
+/// certificate or key, so a misconfigured server never falls back to
+/// plaintext. The client CA is only loaded (and required) with `mutual_tls`.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    cert: Vec<u8>,
+    key: Vec<u8>,
+    client_ca: Option<Vec<u8>>,
+}
+
+impl TlsIdentity {
+    pub fn load(config: &TlsConfig) -> Result<Self> {
+        let client_ca = if config.mutual_tls {
+            let ca_path = config
+                .ca_path
+                .as_deref()
+                .ok_or_else(|| anyhow!("security.tls.mutual_tls requires security.tls.ca_path"))?;
+            Some(read_pem("client CA certificate", ca_path)?)
+        } else {
+            None
+        };
+        let identity = Self {
+            cert: read_pem("certificate", &config.cert_path)?,
+            key: read_pem("private key", &config.key_path)?,
+            client_ca,
+        };
+
+        identity
+            .rustls_config()
+            .with_context(|| format!("Invalid TLS certificate {}", config.cert_path))?;
+        Ok(identity)
+    }
+
+    /// Whether clients must present a certificate signed by the client CA
+    pub fn mutual(&self) -> bool {
+        self.client_ca.is_some()
+    }
+
+    /// Server config for the REST listener, offering HTTP/2 and HTTP/1.1
+    pub fn rustls_config(&self) -> Result<Arc<ServerConfig>> {
+        let certs = rustls_pemfile::certs(&mut self.cert.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse certificate PEM")?;
+        if certs.is_empty() {
+            return Err(anyhow!("No certificate found in PEM"));
+        }
+        let key = rustls_pemfile::private_key(&mut self.key.as_slice())
+            .context("Failed to parse private key PEM")?
+            .ok_or_else(|| anyhow!("No private key found in PEM"))?;
+
+        let builder = ServerConfig::builder();
+        let builder = match &self.client_ca {
+            Some(ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in rustls_pemfile::certs(&mut ca.as_slice()) {
+                    roots
+                        .add(cert.context("Failed to parse client CA PEM")?)
+                        .context("Invalid client CA certificate")?;
+                }
+                let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                    .build()
+                    .context("Invalid client CA")?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .context("Certificate and private key do not form a valid identity")?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+
+    /// The same identity and client CA for the gRPC server
+    pub fn grpc_config(&self) -> ServerTlsConfig {
+        let config = ServerTlsConfig::new().identity(Identity::from_pem(&self.cert, &self.key));
+        match &self.client_ca {
+            Some(ca) => config.client_ca_root(Certificate::from_pem(ca)),
+            None => config,
+        }
+    }
+}
+
+fn read_pem(what: &str, path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read TLS {what} {path}"))
+}
+
+/// TCP listener that completes a TLS handshake before handing out connections
+///
+/// Handshakes run on their own tasks so one slow client cannot hold up the
+/// rest. Connections that fail the handshake, including plaintext clients and
+/// (with mutual TLS) clients without a trusted certificate, are dropped.
+pub struct TlsListener {
+    connections: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, config: Arc<ServerConfig>) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let acceptor = TlsAcceptor::from(config);
+        let (tx, connections) = mpsc::channel(64);
+
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("Failed to accept TCP connection: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send((stream, addr)).await;
+                        }
+                        Ok(Err(e)) => debug!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => debug!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            connections,
+            local_addr,
+        })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.connections.recv().await {
+            Some(connection) => connection,
+            // The accept task only stops once this listener is gone
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/tls/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn config(mutual_tls: bool) -> TlsConfig {
+        TlsConfig {
+            cert_path: fixture("server.pem"),
+            key_path: fixture("server.key"),
+            ca_path: Some(fixture("ca.pem")),
+            mutual_tls,
+        }
+    }
+
+    #[test]
+    fn test_loads_fixture_identity() {
+        assert!(!TlsIdentity::load(&config(false)).unwrap().mutual());
+        assert!(TlsIdentity::load(&config(true)).unwrap().mutual());
+    }
+
+    #[test]
+    fn test_bad_files_fail_with_the_path() {
+        let mut missing = config(false);
+        missing.key_path = fixture("missing.key");
+        let err = TlsIdentity::load(&missing).err().unwrap().to_string();
+        assert!(
+            err.contains("private key") && err.contains("missing.key"),
+            "{err}"
+        );
+
+        // A key where the certificate should be
+        let mut swapped = config(false);
+        swapped.cert_path = fixture("server.key");
+        let err = format!("{:#}", TlsIdentity::load(&swapped).err().unwrap());
+        assert!(err.contains("No certificate found"), "{err}");
+
+        let mut no_ca = config(true);
+        no_ca.ca_path = None;
+        let err = TlsIdentity::load(&no_ca).err().unwrap().to_string();
+        assert!(err.contains("ca_path"), "{err}");
+    }
+}
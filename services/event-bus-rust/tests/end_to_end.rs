@@ -0,0 +1,146 @@
+//! End-to-end run of an emulated game publishing into a live event bus
+//!
+//! Ignored by default because it binds real sockets; run it with
+//! `cargo test --test end_to_end -- --ignored`. The resource coordinator half
+//! of the scenario is not covered: that crate has no server in this
+//! repository yet.
+
+use balatro_emulator::utils::{BalatroRng, ConsumableKind, ConsumablePools, SeedType};
+use event_bus_rust::{api, config::AppConfig, proto::Event, routing::EventRouter, AppState};
+use serde_json::{json, Value};
+use std::{
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+/// An event bus REST server on an ephemeral port
+struct EventBusServer {
+    addr: SocketAddr,
+    router: Arc<EventRouter>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl EventBusServer {
+    /// Serve the full REST app, wired exactly as the binary does
+    async fn start(config: AppConfig) -> Self {
+        let router = Arc::new(EventRouter::new_with_config(config.routing.clone()));
+        let app = api::rest_app(AppState {
+            router: router.clone(),
+            config: Arc::new(config),
+            draining: Arc::new(AtomicBool::new(false)),
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown, stopped) = oneshot::channel();
+        let handle = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(async {
+                let _ = stopped.await;
+            })
+            .await
+            .unwrap();
+        });
+
+        Self {
+            addr,
+            router,
+            shutdown,
+            handle,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// Stop accepting connections and wait for in-flight requests to finish
+    async fn stop(self) {
+        let _ = self.shutdown.send(());
+        tokio::time::timeout(Duration::from_secs(5), self.handle)
+            .await
+            .expect("server did not shut down")
+            .unwrap();
+    }
+}
+
+/// Events for a short emulated run: opening hand and a Celestial pack per ante
+fn emulated_game(seed: &str, antes: u8) -> Vec<Value> {
+    let mut rng = BalatroRng::new(SeedType::from(seed));
+    let deck = rng.starting_deck();
+    let pools = ConsumablePools::default();
+
+    let mut events = Vec::new();
+    for ante in 1..=antes {
+        let hand = rng.opening_hand(&deck, ante, 8);
+        events.push(json!({
+            "type": "GAME_STATE",
+            "source": "emulator",
+            "correlation_id": seed,
+            "payload": {
+                "in_game": true,
+                "game_id": seed,
+                "ante": ante,
+                "hand_size": hand.len(),
+                "game_state": "PLAYING",
+            },
+        }));
+
+        let pack = rng.generate_pack(ConsumableKind::Planet, ante, 3, &pools, None);
+        events.push(json!({
+            "type": "ROUND_COMPLETE",
+            "source": "emulator",
+            "correlation_id": seed,
+            "payload": { "ante": ante, "round": 1, "pack": pack },
+        }));
+    }
+    events
+}
+
+#[tokio::test]
+#[ignore = "binds sockets; run with --ignored"]
+async fn test_emulated_game_events_are_routed() {
+    let server = EventBusServer::start(AppConfig::default()).await;
+    let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+    server
+        .router
+        .subscribe_channel("game.*.*".to_string(), tx)
+        .unwrap();
+
+    let client = reqwest::Client::new();
+    let health = client.get(server.url("/health")).send().await.unwrap();
+    assert!(health.status().is_success());
+
+    let events = emulated_game("E2E", 3);
+    for event in &events {
+        let response = client
+            .post(server.url("/api/v1/events"))
+            .json(event)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200, "event {event} was not routed");
+        assert!(response.headers().contains_key("x-request-id"));
+    }
+
+    let mut received = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await {
+        received.push(event);
+    }
+    assert_eq!(received.len(), events.len());
+    assert!(received.iter().all(|event| event.correlation_id == "E2E"));
+
+    // The same seed replays the same game
+    assert_eq!(events, emulated_game("E2E", 3));
+
+    server.stop().await;
+}
@@ -0,0 +1,172 @@
+//! The gRPC server end to end, through a real tonic client
+
+use event_bus_rust::{
+    config::AppConfig,
+    grpc::{self, EventBusService},
+    proto::{
+        eventbus::{event_bus_client::EventBusClient, Overflow, SubscribeRequest},
+        Event, EventType,
+    },
+    routing::EventRouter,
+};
+use futures::StreamExt;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{sync::oneshot, task::JoinHandle};
+use tonic::{transport::Channel, Code};
+use tonic_reflection::pb::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest,
+};
+
+/// An event bus gRPC server on an ephemeral port
+struct GrpcServer {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl GrpcServer {
+    async fn start(config: AppConfig) -> Self {
+        let router = Arc::new(EventRouter::new_with_config(config.routing.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown, stopped) = oneshot::channel::<()>();
+
+        let handle = tokio::spawn(async move {
+            grpc::serve_with_listener(
+                EventBusService::new(router),
+                listener,
+                &config.server.grpc,
+                None,
+                async {
+                    stopped.await.ok();
+                },
+            )
+            .await
+            .unwrap();
+        });
+
+        Self {
+            addr,
+            shutdown,
+            handle,
+        }
+    }
+
+    async fn channel(&self) -> Channel {
+        Channel::from_shared(format!("http://{}", self.addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap()
+    }
+
+    async fn stop(self) {
+        self.shutdown.send(()).ok();
+        self.handle.await.unwrap();
+    }
+}
+
+fn heartbeat(id: &str) -> Event {
+    Event {
+        event_id: id.to_string(),
+        r#type: EventType::Heartbeat as i32,
+        source: "grpc-test".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_publish_reaches_grpc_subscriber() {
+    let server = GrpcServer::start(AppConfig::default()).await;
+    let mut client = EventBusClient::new(server.channel().await);
+
+    let mut stream = client
+        .subscribe(SubscribeRequest {
+            topic_pattern: "system.heartbeat".to_string(),
+            subscriber_id: "grpc-test".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let ack = stream.next().await.unwrap().unwrap();
+    assert_eq!(ack.metadata["topic"], grpc::SUBSCRIPTION_ACK_TOPIC);
+
+    let response = client
+        .publish_event(heartbeat("hb-1"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success);
+    assert_eq!(response.routed, 1);
+
+    let event = tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("subscriber received nothing")
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_id, "hb-1");
+
+    // A blocking subscription needs a timeout to block for
+    let err = client
+        .subscribe(SubscribeRequest {
+            topic_pattern: "system.heartbeat".to_string(),
+            overflow: Overflow::Block as i32,
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    // Graceful shutdown waits for open streams
+    drop((stream, client));
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_message_size_limit_and_reflection_flag() {
+    let mut config = AppConfig::default();
+    config.server.grpc.max_message_size = 1024;
+    let server = GrpcServer::start(config.clone()).await;
+    let channel = server.channel().await;
+
+    let mut oversized = heartbeat("big");
+    oversized.source = "x".repeat(2048);
+    let err = EventBusClient::new(channel.clone())
+        .publish_event(oversized)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::OutOfRange);
+
+    let list_services = || {
+        futures::stream::iter([ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(MessageRequest::ListServices(String::new())),
+        }])
+    };
+    let err = ServerReflectionClient::new(channel)
+        .server_reflection_info(list_services())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unimplemented);
+    server.stop().await;
+
+    config.server.grpc.reflection_enabled = true;
+    let server = GrpcServer::start(config).await;
+    let mut responses = ServerReflectionClient::new(server.channel().await)
+        .server_reflection_info(list_services())
+        .await
+        .unwrap()
+        .into_inner();
+    let Some(MessageResponse::ListServicesResponse(list)) =
+        responses.next().await.unwrap().unwrap().message_response
+    else {
+        panic!("expected a service list");
+    };
+    assert!(list
+        .service
+        .iter()
+        .any(|service| service.name == "jimbot.eventbus.EventBus"));
+    server.stop().await;
+}
@@ -52,7 +52,8 @@ async fn test_single_event_endpoint() {
 
     match response {
         Ok(resp) => {
-            assert_eq!(resp.status(), 200);
+            // 202 when accepted but no subscriber was listening
+            assert!(matches!(resp.status().as_u16(), 200 | 202));
             let body: serde_json::Value = resp.json().await.unwrap();
             assert_eq!(body["status"], "ok");
         }
@@ -96,7 +97,8 @@ async fn test_batch_events_endpoint() {
 
     match response {
         Ok(resp) => {
-            assert_eq!(resp.status(), 200);
+            // 202 when accepted but no subscriber was listening
+            assert!(matches!(resp.status().as_u16(), 200 | 202));
             let body: serde_json::Value = resp.json().await.unwrap();
             assert_eq!(body["status"], "ok");
         }
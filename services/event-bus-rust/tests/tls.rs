@@ -0,0 +1,194 @@
+//! TLS termination on the REST and gRPC servers
+//!
+//! The certificates under `tests/fixtures/tls` are a throwaway test CA, a
+//! `localhost` server certificate and a client certificate, all signed by
+//! that CA.
+
+use axum::serve::ListenerExt;
+use event_bus_rust::{
+    api,
+    config::{AppConfig, TlsConfig},
+    grpc::{self, EventBusService},
+    proto::{eventbus::event_bus_client::EventBusClient, Event, EventType},
+    routing::EventRouter,
+    tls::{TlsIdentity, TlsListener},
+    AppState,
+};
+use std::{
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+};
+use tokio_rustls::{
+    rustls::{pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/tls/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn tls_config(mutual_tls: bool) -> TlsConfig {
+    TlsConfig {
+        cert_path: fixture("server.pem"),
+        key_path: fixture("server.key"),
+        ca_path: Some(fixture("ca.pem")),
+        mutual_tls,
+    }
+}
+
+/// Serve the REST app over TLS on an ephemeral port, as the binary does
+async fn start_rest(tls: &TlsIdentity) -> SocketAddr {
+    let app = api::rest_app(AppState {
+        router: Arc::new(EventRouter::new()),
+        config: Arc::new(AppConfig::default()),
+        draining: Arc::new(AtomicBool::new(false)),
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener = TlsListener::new(listener, tls.rustls_config().unwrap())
+        .unwrap()
+        .tap_io(|_| {});
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    addr
+}
+
+/// A TLS client trusting the test CA, presenting the client certificate if asked
+fn connector(with_client_cert: bool) -> TlsConnector {
+    let ca = std::fs::read(fixture("ca.pem")).unwrap();
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut ca.as_slice()) {
+        roots.add(cert.unwrap()).unwrap();
+    }
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let config = if with_client_cert {
+        let cert = std::fs::read(fixture("client.pem")).unwrap();
+        let key = std::fs::read(fixture("client.key")).unwrap();
+        builder
+            .with_client_auth_cert(
+                rustls_pemfile::certs(&mut cert.as_slice())
+                    .collect::<Result<_, _>>()
+                    .unwrap(),
+                rustls_pemfile::private_key(&mut key.as_slice())
+                    .unwrap()
+                    .unwrap(),
+            )
+            .unwrap()
+    } else {
+        builder.with_no_client_auth()
+    };
+    TlsConnector::from(Arc::new(config))
+}
+
+/// `GET /health` over `stream`; whatever was read before any error
+async fn get_health(mut stream: impl AsyncRead + AsyncWrite + Unpin) -> String {
+    let request = "GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    if stream.write_all(request.as_bytes()).await.is_err() {
+        return String::new();
+    }
+    let mut response = Vec::new();
+    let _ = stream.read_to_end(&mut response).await;
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+async fn get_health_over_tls(addr: SocketAddr, with_client_cert: bool) -> String {
+    let tcp = TcpStream::connect(addr).await.unwrap();
+    let server_name = ServerName::try_from("localhost").unwrap();
+    match connector(with_client_cert).connect(server_name, tcp).await {
+        Ok(stream) => get_health(stream).await,
+        Err(_) => String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_rest_serves_tls_and_refuses_plaintext() {
+    let addr = start_rest(&TlsIdentity::load(&tls_config(false)).unwrap()).await;
+
+    let response = get_health_over_tls(addr, false).await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let response = get_health(TcpStream::connect(addr).await.unwrap()).await;
+    assert!(!response.starts_with("HTTP/"), "{response}");
+}
+
+#[tokio::test]
+async fn test_mutual_tls_requires_client_certificate() {
+    let addr = start_rest(&TlsIdentity::load(&tls_config(true)).unwrap()).await;
+
+    let response = get_health_over_tls(addr, false).await;
+    assert!(!response.starts_with("HTTP/"), "{response}");
+
+    let response = get_health_over_tls(addr, true).await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+}
+
+#[tokio::test]
+async fn test_grpc_serves_tls_and_refuses_plaintext() {
+    let tls = TlsIdentity::load(&tls_config(false)).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        grpc::serve_with_listener(
+            EventBusService::new(Arc::new(EventRouter::new())),
+            listener,
+            &AppConfig::default().server.grpc,
+            Some(&tls),
+            async {
+                stopped.await.ok();
+            },
+        )
+        .await
+        .unwrap();
+    });
+
+    let heartbeat = Event {
+        r#type: EventType::Heartbeat as i32,
+        source: "tls-test".to_string(),
+        ..Default::default()
+    };
+    let ca = std::fs::read(fixture("ca.pem")).unwrap();
+    let channel = Channel::from_shared(format!("https://{addr}"))
+        .unwrap()
+        .tls_config(
+            ClientTlsConfig::new()
+                .ca_certificate(Certificate::from_pem(ca))
+                .domain_name("localhost"),
+        )
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let response = EventBusClient::new(channel.clone())
+        .publish_event(heartbeat.clone())
+        .await
+        .unwrap();
+    assert!(response.into_inner().success);
+
+    let plaintext = Channel::from_shared(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await;
+    if let Ok(plaintext) = plaintext {
+        assert!(EventBusClient::new(plaintext)
+            .publish_event(heartbeat)
+            .await
+            .is_err());
+    }
+
+    drop(channel);
+    shutdown.send(()).ok();
+    server.await.unwrap();
+}